@@ -345,6 +345,8 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
             validation_computational_gas_limit: config.required.validation_computational_gas_limit,
             default_aa: config.required.default_aa_hash,
             bootloader: config.required.bootloader_hash,
+            // Replacements are checked by the main node when transactions are proxied to it.
+            replacement_fee_bump_percent: None,
         }
     }
 }
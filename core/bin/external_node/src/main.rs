@@ -131,6 +131,7 @@ async fn init_tasks(
     })
     .await;
     healthchecks.push(Box::new(metadata_calculator.tree_health_check()));
+    let tree_reader = metadata_calculator.tree_reader();
 
     let consistency_checker = ConsistencyChecker::new(
         &config
@@ -195,6 +196,7 @@ async fn init_tasks(
             .with_threads(config.required.threads_per_server)
             .with_tx_sender(tx_sender.clone())
             .with_sync_state(sync_state.clone())
+            .with_tree_reader(tree_reader.clone())
             .build(stop_receiver.clone())
             .await;
 
@@ -207,6 +209,7 @@ async fn init_tasks(
             .with_threads(config.required.threads_per_server)
             .with_tx_sender(tx_sender)
             .with_sync_state(sync_state)
+            .with_tree_reader(tree_reader)
             .build(stop_receiver.clone())
             .await;
 
@@ -5,19 +5,22 @@ use crate::{
 };
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     convert::TryInto,
     sync::{Arc, RwLock},
 };
 
 use once_cell::sync::Lazy;
 
-use zksync_basic_types::{AccountTreeId, Bytes, H160, H256, U256, U64};
+use zksync_basic_types::{AccountTreeId, Bytes, MiniblockNumber, H160, H256, U256, U64};
 use zksync_contracts::BaseSystemContracts;
-use zksync_core::api_server::web3::backend_jsonrpc::namespaces::eth::EthNamespaceT;
+use zksync_core::api_server::web3::{
+    backend_jsonrpc::{error::into_jsrpc_error, namespaces::eth::EthNamespaceT},
+    namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
+};
 use zksync_state::{ReadStorage, StorageView, WriteStorage};
 use zksync_types::{
-    api::{TransactionReceipt, TransactionVariant},
+    api::{BlockNumber, Log, TransactionReceipt, TransactionVariant},
     get_code_key, get_nonce_key,
     l2::L2Tx,
     transaction_request::{l2_tx_from_call_req, TransactionRequest},
@@ -26,7 +29,9 @@ use zksync_types::{
     StorageKey, StorageLogQueryType, Transaction, ACCOUNT_CODE_STORAGE_ADDRESS,
     L2_ETH_TOKEN_ADDRESS,
 };
-use zksync_utils::{h256_to_account_address, h256_to_u256, h256_to_u64, u256_to_h256};
+use zksync_utils::{
+    h256_to_account_address, h256_to_u256, h256_to_u64, miniblock_hash, u256_to_h256,
+};
 
 use vm::{
     utils::{create_test_block_params, BASE_SYSTEM_CONTRACTS, BLOCK_GAS_LIMIT, ETH_CALL_GAS_LIMIT},
@@ -37,7 +42,10 @@ use vm::{
     },
     HistoryEnabled, OracleTools,
 };
-use zksync_web3_decl::types::{Filter, FilterChanges};
+use zksync_web3_decl::{
+    error::Web3Error,
+    types::{Filter, FilterChanges},
+};
 
 pub const MAX_TX_SIZE: usize = 1000000;
 // Timestamp of the first block (if not running in fork mode).
@@ -63,6 +71,18 @@ pub struct TxExecutionInfo {
     pub result: VmTxExecutionResult,
 }
 
+/// Filter installed via one of `eth_new*Filter` methods.
+#[derive(Debug, Clone)]
+pub enum InstalledFilter {
+    /// Logs filter with the first miniblock that may contain not yet returned logs.
+    Events(Filter, u64),
+    /// Block filter with the last miniblock that was already returned.
+    Blocks(u64),
+    /// Pending transactions filter. The test node executes transactions immediately,
+    /// so this filter never has any changes.
+    PendingTransactions,
+}
+
 /// Helper struct for InMemoryNode.
 pub struct InMemoryNodeInner {
     /// Timestamp, batch number and miniblock number that will be used by the next block.
@@ -73,14 +93,153 @@ pub struct InMemoryNodeInner {
     pub tx_results: HashMap<H256, TxExecutionInfo>,
     // Map from batch number to information about the block.
     pub blocks: HashMap<u32, BlockInfo>,
+    // Map from miniblock number to the logs emitted in this miniblock.
+    // Only contains miniblocks produced by this node (i.e. not the ones from the fork).
+    pub logs: BTreeMap<u64, Vec<Log>>,
+    // Installed filters, keyed by their IDs.
+    pub filters: HashMap<U256, InstalledFilter>,
+    // ID that will be assigned to the next installed filter.
+    pub next_filter_id: U256,
     // Underlying storage
     pub fork_storage: ForkStorage,
 }
 
+impl InMemoryNodeInner {
+    /// Returns the number of the last produced miniblock.
+    fn latest_miniblock(&self) -> u64 {
+        self.current_miniblock.saturating_sub(1)
+    }
+
+    /// Resolves the block number used in filters. Missing block number is treated as `latest`.
+    fn resolve_filter_block_number(&self, block_number: Option<BlockNumber>) -> u64 {
+        match block_number.unwrap_or(BlockNumber::Latest) {
+            BlockNumber::Number(number) => number.as_u64(),
+            BlockNumber::Earliest => 0,
+            BlockNumber::Pending => self.current_miniblock,
+            BlockNumber::Committed | BlockNumber::Finalized | BlockNumber::Latest => {
+                self.latest_miniblock()
+            }
+        }
+    }
+
+    /// If filter has `block_hash` then it resolves block number by hash and sets it to `from_block` and `to_block`.
+    fn resolve_filter_block_hash(&self, filter: &mut Filter) -> Result<(), Web3Error> {
+        match (filter.block_hash, filter.from_block, filter.to_block) {
+            (Some(block_hash), None, None) => {
+                let block_number = self
+                    .logs
+                    .keys()
+                    .copied()
+                    .find(|&number| miniblock_hash(MiniblockNumber(number as u32)) == block_hash)
+                    .ok_or(Web3Error::NoBlock)?;
+
+                filter.from_block = Some(BlockNumber::Number(block_number.into()));
+                filter.to_block = Some(BlockNumber::Number(block_number.into()));
+                Ok(())
+            }
+            (Some(_), _, _) => Err(Web3Error::InvalidFilterBlockHash),
+            (None, _, _) => Ok(()),
+        }
+    }
+
+    /// Returns logs emitted in the `[from_block, filter.to_block]` range that satisfy the filter.
+    /// Only miniblocks that were actually produced are visited, so the range may be arbitrarily large.
+    fn filter_logs(&self, filter: &Filter, from_block: u64) -> Vec<Log> {
+        let to_block = self.resolve_filter_block_number(filter.to_block);
+        if from_block > to_block {
+            return vec![];
+        }
+        self.logs
+            .range(from_block..=to_block)
+            .flat_map(|(_, logs)| logs)
+            .filter(|log| log_matches_filter(log, filter))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns changes for the filter together with its updated state.
+    fn filter_changes(
+        &self,
+        typed_filter: InstalledFilter,
+    ) -> Result<(FilterChanges, InstalledFilter), Web3Error> {
+        let latest_miniblock = self.latest_miniblock();
+        let res = match typed_filter {
+            InstalledFilter::Blocks(last_block) => {
+                let block_hashes = (last_block + 1..=latest_miniblock)
+                    .map(|number| miniblock_hash(MiniblockNumber(number as u32)))
+                    .collect();
+                (
+                    FilterChanges::Hashes(block_hashes),
+                    InstalledFilter::Blocks(last_block.max(latest_miniblock)),
+                )
+            }
+            InstalledFilter::PendingTransactions => (
+                FilterChanges::Hashes(vec![]),
+                InstalledFilter::PendingTransactions,
+            ),
+            InstalledFilter::Events(filter, from_block) => {
+                if let Some(topics) = filter.topics.as_ref() {
+                    if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
+                        return Err(Web3Error::TooManyTopics);
+                    }
+                }
+                let logs = self.filter_logs(&filter, from_block);
+                let new_from_block = from_block.max(latest_miniblock + 1);
+                (
+                    FilterChanges::Logs(logs),
+                    InstalledFilter::Events(filter, new_from_block),
+                )
+            }
+        };
+        Ok(res)
+    }
+
+    /// Installs the filter and returns its ID.
+    fn install_filter(&mut self, filter: InstalledFilter) -> U256 {
+        let idx = self.next_filter_id;
+        self.next_filter_id += U256::one();
+        self.filters.insert(idx, filter);
+        idx
+    }
+}
+
+/// Checks whether the log satisfies address and topics conditions of the filter.
+/// An empty list of addresses matches any address, and a missing topic matches any topic in its position.
+fn log_matches_filter(log: &Log, filter: &Filter) -> bool {
+    if let Some(addresses) = &filter.address {
+        if !addresses.0.is_empty() && !addresses.0.contains(&log.address) {
+            return false;
+        }
+    }
+    let topics = filter.topics.iter().flatten().enumerate();
+    for (idx, expected_topics) in topics {
+        if let Some(expected_topics) = expected_topics {
+            match log.topics.get(idx) {
+                Some(topic) if expected_topics.0.contains(topic) => {}
+                _ => return false,
+            }
+        }
+    }
+    true
+}
+
 fn not_implemented<T: Send + 'static>() -> jsonrpc_core::BoxFuture<Result<T, jsonrpc_core::Error>> {
     Err(jsonrpc_core::Error::method_not_found()).into_boxed_future()
 }
 
+/// State overrides are not supported by the in-memory node, so requests specifying them are rejected
+/// rather than executed against the unmodified state.
+fn ensure_no_state_override(
+    state_override: &Option<zksync_types::api::StateOverride>,
+) -> Result<(), jsonrpc_core::Error> {
+    match state_override {
+        Some(_) => Err(into_jsrpc_error(Web3Error::InvalidStateOverride(
+            "state overrides are not supported by the test node".to_owned(),
+        ))),
+        None => Ok(()),
+    }
+}
+
 /// In-memory node, that can be used for local & unit testing.
 /// It also supports the option of forking testnet/mainnet.
 /// All contents are removed when object is destroyed.
@@ -114,6 +273,9 @@ impl InMemoryNode {
                 current_miniblock: fork.as_ref().map(|f| f.l2_miniblock + 1).unwrap_or(1),
                 tx_results: Default::default(),
                 blocks: Default::default(),
+                logs: Default::default(),
+                filters: Default::default(),
+                next_filter_id: U256::one(),
                 fork_storage: ForkStorage::new(fork),
             })),
         }
@@ -284,6 +446,30 @@ impl InMemoryNode {
             )
         }
         let current_miniblock = inner.current_miniblock;
+        let block_hash = miniblock_hash(MiniblockNumber(current_miniblock as u32));
+        let logs = result
+            .result
+            .logs
+            .events
+            .iter()
+            .enumerate()
+            .map(|(log_index, event)| Log {
+                address: event.address,
+                topics: event.indexed_topics.clone(),
+                data: event.value.clone().into(),
+                block_hash: Some(block_hash),
+                block_number: Some(current_miniblock.into()),
+                l1_batch_number: Some(U64::from(block.batch_number)),
+                transaction_hash: Some(tx_hash),
+                // There is exactly one transaction per block.
+                transaction_index: Some(U64::zero()),
+                log_index: Some(log_index.into()),
+                transaction_log_index: Some(log_index.into()),
+                log_type: None,
+                removed: Some(false),
+            })
+            .collect();
+        inner.logs.insert(current_miniblock, logs);
         inner.tx_results.insert(
             tx_hash,
             TxExecutionInfo {
@@ -312,7 +498,11 @@ impl EthNamespaceT for InMemoryNode {
         &self,
         req: zksync_types::transaction_request::CallRequest,
         _block: Option<zksync_types::api::BlockIdVariant>,
+        state_override: Option<zksync_types::api::StateOverride>,
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::Bytes>> {
+        if let Err(err) = ensure_no_state_override(&state_override) {
+            return Err(err).into_boxed_future();
+        }
         let mut tx = l2_tx_from_call_req(req, MAX_TX_SIZE).unwrap();
         tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
         let result = self.run_l2_call(tx);
@@ -452,7 +642,11 @@ impl EthNamespaceT for InMemoryNode {
                 cumulative_gas_used: Default::default(),
                 gas_used: Some(info.tx.common_data.fee.gas_limit - info.result.gas_refunded),
                 contract_address: contract_address_from_tx_result(&info.result),
-                logs: vec![],
+                logs: reader
+                    .logs
+                    .get(&info.miniblock_number)
+                    .cloned()
+                    .unwrap_or_default(),
                 l2_to_l1_logs: vec![],
                 status: Some(status),
                 root: None,
@@ -465,6 +659,15 @@ impl EthNamespaceT for InMemoryNode {
         Ok(receipt).into_boxed_future()
     }
 
+    fn get_block_receipts(
+        &self,
+        _block: zksync_types::api::BlockId,
+    ) -> jsonrpc_core::BoxFuture<
+        jsonrpc_core::Result<Option<Vec<zksync_types::api::TransactionReceipt>>>,
+    > {
+        not_implemented()
+    }
+
     fn send_raw_transaction(
         &self,
         tx_bytes: zksync_basic_types::Bytes,
@@ -482,62 +685,142 @@ impl EthNamespaceT for InMemoryNode {
         Ok(hash).into_boxed_future()
     }
 
-    // Methods below are not currently implemented.
-
-    fn get_block_number(
+    fn new_filter(
         &self,
-    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::U64>> {
-        not_implemented()
-    }
-
-    fn estimate_gas(
-        &self,
-        _req: zksync_types::transaction_request::CallRequest,
-        _block: Option<zksync_types::api::BlockNumber>,
+        mut filter: Filter,
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
-        not_implemented()
-    }
-
-    fn gas_price(&self) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
-        not_implemented()
-    }
+        if let Some(topics) = filter.topics.as_ref() {
+            if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
+                return Err(into_jsrpc_error(Web3Error::TooManyTopics)).into_boxed_future();
+            }
+        }
 
-    fn new_filter(&self, _filter: Filter) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
-        not_implemented()
+        let mut inner = self.inner.write().unwrap();
+        if let Err(err) = inner.resolve_filter_block_hash(&mut filter) {
+            return Err(into_jsrpc_error(err)).into_boxed_future();
+        }
+        // Same as in the main node, changes for the filter are only tracked starting from the next block;
+        // logs from already produced blocks can be requested via `eth_getFilterLogs`.
+        let from_block = match filter.from_block {
+            Some(BlockNumber::Number(number)) => number.as_u64().max(inner.current_miniblock),
+            _ => inner.current_miniblock,
+        };
+        let idx = inner.install_filter(InstalledFilter::Events(filter, from_block));
+        Ok(idx).into_boxed_future()
     }
 
     fn new_block_filter(&self) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
-        not_implemented()
+        let mut inner = self.inner.write().unwrap();
+        let last_block = inner.latest_miniblock();
+        let idx = inner.install_filter(InstalledFilter::Blocks(last_block));
+        Ok(idx).into_boxed_future()
     }
 
-    fn uninstall_filter(&self, _idx: U256) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<bool>> {
-        not_implemented()
+    fn uninstall_filter(&self, idx: U256) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<bool>> {
+        let removed = self.inner.write().unwrap().filters.remove(&idx).is_some();
+        Ok(removed).into_boxed_future()
     }
 
     fn new_pending_transaction_filter(
         &self,
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
-        not_implemented()
+        let idx = self
+            .inner
+            .write()
+            .unwrap()
+            .install_filter(InstalledFilter::PendingTransactions);
+        Ok(idx).into_boxed_future()
     }
 
     fn get_logs(
         &self,
-        _filter: Filter,
+        mut filter: Filter,
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<Vec<zksync_types::api::Log>>> {
-        not_implemented()
+        let reader = self.inner.read().unwrap();
+        if let Err(err) = reader.resolve_filter_block_hash(&mut filter) {
+            return Err(into_jsrpc_error(err)).into_boxed_future();
+        }
+        let from_block = reader.resolve_filter_block_number(filter.from_block);
+        let to_block = reader.resolve_filter_block_number(filter.to_block);
+
+        filter.to_block = Some(BlockNumber::Number(to_block.into()));
+        let result = match reader.filter_changes(InstalledFilter::Events(filter, from_block)) {
+            Ok((FilterChanges::Logs(logs), _)) => Ok(logs),
+            Ok(_) => unreachable!("Unexpected `FilterChanges` type, expected `Logs`"),
+            Err(err) => Err(into_jsrpc_error(err)),
+        };
+        result.into_boxed_future()
     }
 
     fn get_filter_logs(
         &self,
-        _filter_index: U256,
+        filter_index: U256,
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<FilterChanges>> {
-        not_implemented()
+        let reader = self.inner.read().unwrap();
+        let filter = match reader.filters.get(&filter_index) {
+            Some(InstalledFilter::Events(filter, _)) => {
+                let from_block = reader.resolve_filter_block_number(filter.from_block);
+                InstalledFilter::Events(filter.clone(), from_block)
+            }
+            _ => return Err(into_jsrpc_error(Web3Error::FilterNotFound)).into_boxed_future(),
+        };
+
+        let result = reader
+            .filter_changes(filter)
+            .map(|(changes, _)| changes)
+            .map_err(into_jsrpc_error);
+        result.into_boxed_future()
     }
 
     fn get_filter_changes(
         &self,
-        _filter_index: U256,
+        filter_index: U256,
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<FilterChanges>> {
+        let mut inner = self.inner.write().unwrap();
+        let filter = match inner.filters.get(&filter_index) {
+            Some(filter) => filter.clone(),
+            None => return Err(into_jsrpc_error(Web3Error::FilterNotFound)).into_boxed_future(),
+        };
+
+        let result = inner
+            .filter_changes(filter)
+            .map(|(changes, updated_filter)| {
+                inner.filters.insert(filter_index, updated_filter);
+                changes
+            });
+        result.map_err(into_jsrpc_error).into_boxed_future()
+    }
+
+    // Methods below are not currently implemented.
+
+    fn get_block_number(
+        &self,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::U64>> {
+        not_implemented()
+    }
+
+    fn estimate_gas(
+        &self,
+        _req: zksync_types::transaction_request::CallRequest,
+        _block: Option<zksync_types::api::BlockNumber>,
+        state_override: Option<zksync_types::api::StateOverride>,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
+        if let Err(err) = ensure_no_state_override(&state_override) {
+            return Err(err).into_boxed_future();
+        }
+        not_implemented()
+    }
+
+    fn gas_price(&self) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
+        not_implemented()
+    }
+
+    fn fee_history(
+        &self,
+        _block_count: U64,
+        _newest_block: zksync_types::api::BlockNumber,
+        _reward_percentiles: Option<Vec<f32>>,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_types::api::FeeHistory>> {
         not_implemented()
     }
 
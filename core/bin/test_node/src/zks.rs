@@ -170,4 +170,23 @@ impl ZksNamespaceT for ZkMockNamespaceImpl {
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::U64>> {
         not_implemented!()
     }
+
+    fn get_proof(
+        &self,
+        _address: zksync_basic_types::Address,
+        _keys: Vec<zksync_basic_types::H256>,
+        _l1_batch_number: zksync_basic_types::L1BatchNumber,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<Option<zksync_types::api::Proof>>> {
+        not_implemented!()
+    }
+
+    fn simulate_bundle(
+        &self,
+        _reqs: Vec<zksync_types::transaction_request::CallRequest>,
+        _block: Option<zksync_types::api::BlockIdVariant>,
+        _state_override: Option<zksync_types::api::StateOverride>,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<Vec<zksync_types::api::SimulatedTransaction>>>
+    {
+        not_implemented!()
+    }
 }
@@ -8,6 +8,7 @@
 
 use std::{
     collections::HashMap,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -21,15 +22,20 @@ use vm::{
 };
 use zksync_config::constants::ZKPORTER_IS_AVAILABLE;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_state::{PostgresStorage, ReadStorage, StorageView, WriteStorage};
+use zksync_state::{OverlayStorage, PostgresStorage, ReadStorage, StorageView, WriteStorage};
 use zksync_types::{
-    api, get_nonce_key,
+    api::{self, StateOverride},
+    get_code_key, get_known_code_key, get_nonce_key,
     utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
-    L1BatchNumber, MiniblockNumber, Nonce, StorageKey, Transaction, H256, U256,
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, Nonce, StorageKey, Transaction, H256,
+    U256,
+};
+use zksync_utils::{
+    bytecode::hash_bytecode, h256_to_u256, time::seconds_since_epoch, u256_to_h256,
 };
-use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{vm_metrics, BlockArgs, TxExecutionArgs, TxSharedArgs};
+use crate::state_keeper::PendingStorage;
 
 #[allow(clippy::too_many_arguments)]
 pub(super) fn apply_vm_in_sandbox<T>(
@@ -42,6 +48,32 @@ pub(super) fn apply_vm_in_sandbox<T>(
     storage_read_cache: HashMap<StorageKey, H256>,
     apply: impl FnOnce(&mut Box<VmInstance<'_, HistoryDisabled>>, Transaction) -> T,
 ) -> (T, HashMap<StorageKey, H256>) {
+    apply_vm_in_sandbox_with_txs(
+        rt_handle,
+        shared_args,
+        execution_args,
+        connection_pool,
+        vec![tx],
+        block_args,
+        storage_read_cache,
+        |vm, mut txs| apply(vm, txs.pop().unwrap()),
+    )
+}
+
+/// Same as [`apply_vm_in_sandbox()`], but prepares the VM for executing an ordered list of transactions.
+/// The list must not be empty.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn apply_vm_in_sandbox_with_txs<T>(
+    rt_handle: tokio::runtime::Handle,
+    shared_args: &TxSharedArgs,
+    execution_args: &TxExecutionArgs,
+    connection_pool: &ConnectionPool,
+    txs: Vec<Transaction>,
+    block_args: BlockArgs,
+    storage_read_cache: HashMap<StorageKey, H256>,
+    apply: impl FnOnce(&mut Box<VmInstance<'_, HistoryDisabled>>, Vec<Transaction>) -> T,
+) -> (T, HashMap<StorageKey, H256>) {
+    assert!(!txs.is_empty(), "No transactions to apply");
     let stage_started_at = Instant::now();
     let span = tracing::debug_span!("initialization").entered();
 
@@ -71,18 +103,25 @@ pub(super) fn apply_vm_in_sandbox<T>(
 
     let storage = PostgresStorage::new(rt_handle, connection, state_block_number, false)
         .with_factory_deps_cache(shared_args.factory_deps_cache.clone());
+    let pending_changes = match &shared_args.pending_state {
+        Some(pending_state) if block_args.is_pending_miniblock() => pending_state.snapshot(),
+        _ => Arc::default(),
+    };
+    let storage = PendingStorage::new(storage, pending_changes);
+    let mut storage = OverlayStorage::new(storage);
+    // The enforced nonce and added balance are only applied for the first transaction; subsequent transactions
+    // must observe the nonces and balances resulting from executing the preceding ones.
+    let tx = &txs[0];
+    apply_overrides(
+        &mut storage,
+        &tx.initiator_account(),
+        execution_args.enforced_nonce,
+        execution_args.state_override.as_ref(),
+    );
     // Moving `storage_read_cache` to `storage_view`. It will be moved back once execution is finished and `storage_view` is not needed.
     let mut storage_view = StorageView::new_with_read_keys(storage, storage_read_cache);
 
     let storage_view_setup_started_at = Instant::now();
-    if let Some(nonce) = execution_args.enforced_nonce {
-        let nonce_key = get_nonce_key(&tx.initiator_account());
-        let full_nonce = storage_view.read_value(&nonce_key);
-        let (_, deployment_nonce) = decompose_full_nonce(h256_to_u256(full_nonce));
-        let enforced_full_nonce = nonces_to_full_nonce(U256::from(nonce.0), deployment_nonce);
-        storage_view.set_value(nonce_key, u256_to_h256(enforced_full_nonce));
-    }
-
     let payer = tx.payer();
     let balance_key = storage_key_for_eth_balance(&payer);
     let mut current_balance = h256_to_u256(storage_view.read_value(&balance_key));
@@ -133,13 +172,16 @@ pub(super) fn apply_vm_in_sandbox<T>(
     metrics::histogram!("api.web3.sandbox", stage_started_at.elapsed(), "stage" => "initialization");
     span.exit();
 
-    let tx_id = format!(
-        "{:?}-{}",
-        tx.initiator_account(),
-        tx.nonce().unwrap_or(Nonce(0))
-    );
+    let tx_id = match txs.as_slice() {
+        [tx] => format!(
+            "{:?}-{}",
+            tx.initiator_account(),
+            tx.nonce().unwrap_or(Nonce(0))
+        ),
+        _ => format!("bundle-{}", txs.len()),
+    };
     let stage_started_at = Instant::now();
-    let result = apply(&mut vm, tx);
+    let result = apply(&mut vm, txs);
     let vm_execution_took = stage_started_at.elapsed();
     metrics::histogram!("api.web3.sandbox", vm_execution_took, "stage" => "execution");
 
@@ -155,6 +197,77 @@ pub(super) fn apply_vm_in_sandbox<T>(
     (result, storage_view.into_read_storage_keys())
 }
 
+/// Installs the enforced nonce of the transaction initiator and the state override on top of the storage.
+/// The enforced nonce is installed first, so that the nonce override provided by the user takes precedence.
+fn apply_overrides<S: ReadStorage>(
+    storage: &mut OverlayStorage<S>,
+    initiator: &Address,
+    enforced_nonce: Option<Nonce>,
+    state_override: Option<&StateOverride>,
+) {
+    if let Some(nonce) = enforced_nonce {
+        set_account_nonce(storage, initiator, U256::from(nonce.0));
+    }
+    if let Some(state_override) = state_override {
+        apply_state_override(storage, state_override);
+    }
+}
+
+/// Installs overrides of the account state on top of the storage. Overrides are expected
+/// to be validated beforehand.
+fn apply_state_override<S: ReadStorage>(
+    storage: &mut OverlayStorage<S>,
+    state_override: &StateOverride,
+) {
+    for (address, account) in state_override {
+        if let Some(balance) = account.balance {
+            let balance_key = storage_key_for_eth_balance(address);
+            storage.set_value(balance_key, u256_to_h256(balance));
+        }
+
+        if let Some(nonce) = account.nonce {
+            set_account_nonce(storage, address, nonce);
+        }
+
+        if let Some(code) = &account.code {
+            if code.0.is_empty() {
+                storage.set_value(get_code_key(address), H256::zero());
+            } else {
+                let code_hash = hash_bytecode(&code.0);
+                storage.set_value(get_code_key(address), code_hash);
+                storage.set_value(get_known_code_key(&code_hash), H256::from_low_u64_be(1));
+                storage.store_factory_dep(code_hash, code.0.clone());
+            }
+        }
+
+        let account_id = AccountTreeId::new(*address);
+        if let Some(state) = &account.state {
+            storage.clear_account_storage(account_id);
+            for (key, value) in state {
+                storage.set_value(StorageKey::new(account_id, *key), *value);
+            }
+        }
+        if let Some(state_diff) = &account.state_diff {
+            for (key, value) in state_diff {
+                storage.set_value(StorageKey::new(account_id, *key), *value);
+            }
+        }
+    }
+}
+
+/// Sets the account nonce of the specified account; its deployment nonce is preserved.
+fn set_account_nonce<S: ReadStorage>(
+    storage: &mut OverlayStorage<S>,
+    address: &Address,
+    nonce: U256,
+) {
+    let nonce_key = get_nonce_key(address);
+    let full_nonce = storage.read_value(&nonce_key);
+    let (_, deployment_nonce) = decompose_full_nonce(h256_to_u256(full_nonce));
+    let full_nonce = nonces_to_full_nonce(nonce, deployment_nonce);
+    storage.set_value(nonce_key, u256_to_h256(full_nonce));
+}
+
 impl BlockArgs {
     fn is_pending_miniblock(&self) -> bool {
         matches!(
@@ -185,7 +298,13 @@ impl BlockArgs {
                 .get_provisional_l1_batch_number_of_miniblock_unchecked(self.resolved_block_number)
                 .await
                 .unwrap();
-            (self.resolved_block_number, l1_batch_number)
+            let state_block_number = if self.is_replay {
+                // Genesis miniblock has no transactions, so it is never replayed.
+                self.resolved_block_number - 1
+            } else {
+                self.resolved_block_number
+            };
+            (state_block_number, l1_batch_number)
         }
     }
 
@@ -202,3 +321,136 @@ impl BlockArgs {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_state::InMemoryStorage;
+    use zksync_types::api::OverrideAccount;
+
+    use super::*;
+
+    const ACCOUNT: Address = Address::repeat_byte(1);
+
+    fn storage_with_account() -> InMemoryStorage {
+        let mut storage = InMemoryStorage::default();
+        let full_nonce = nonces_to_full_nonce(U256::from(3), U256::from(2));
+        storage.set_value(get_nonce_key(&ACCOUNT), u256_to_h256(full_nonce));
+        storage.set_value(
+            storage_key_for_eth_balance(&ACCOUNT),
+            H256::from_low_u64_be(100),
+        );
+        let account_id = AccountTreeId::new(ACCOUNT);
+        for slot in [1, 2] {
+            storage.set_value(
+                StorageKey::new(account_id, H256::from_low_u64_be(slot)),
+                H256::from_low_u64_be(slot * 10),
+            );
+        }
+        storage
+    }
+
+    fn read_nonces(storage: &mut impl ReadStorage) -> (U256, U256) {
+        let full_nonce = storage.read_value(&get_nonce_key(&ACCOUNT));
+        decompose_full_nonce(h256_to_u256(full_nonce))
+    }
+
+    fn read_slot(storage: &mut impl ReadStorage, slot: u64) -> H256 {
+        let key = StorageKey::new(AccountTreeId::new(ACCOUNT), H256::from_low_u64_be(slot));
+        storage.read_value(&key)
+    }
+
+    #[test]
+    fn overriding_balance_nonce_and_code() {
+        let code = vec![1_u8; 32];
+        let code_hash = hash_bytecode(&code);
+        let account = OverrideAccount {
+            balance: Some(U256::from(500)),
+            nonce: Some(U256::from(7)),
+            code: Some(code.clone().into()),
+            ..OverrideAccount::default()
+        };
+        let mut storage = OverlayStorage::new(storage_with_account());
+        apply_state_override(&mut storage, &StateOverride::from([(ACCOUNT, account)]));
+
+        let balance = storage.read_value(&storage_key_for_eth_balance(&ACCOUNT));
+        assert_eq!(balance, H256::from_low_u64_be(500));
+        // The deployment nonce must be preserved.
+        assert_eq!(read_nonces(&mut storage), (U256::from(7), U256::from(2)));
+        assert_eq!(storage.read_value(&get_code_key(&ACCOUNT)), code_hash);
+        assert_eq!(
+            storage.read_value(&get_known_code_key(&code_hash)),
+            H256::from_low_u64_be(1)
+        );
+        assert_eq!(storage.load_factory_dep(code_hash), Some(code));
+        // Slots not mentioned in the override are untouched.
+        assert_eq!(read_slot(&mut storage, 1), H256::from_low_u64_be(10));
+    }
+
+    #[test]
+    fn overriding_code_with_empty_bytecode() {
+        let mut raw_storage = storage_with_account();
+        raw_storage.set_value(get_code_key(&ACCOUNT), H256::repeat_byte(0xff));
+        let account = OverrideAccount {
+            code: Some(vec![].into()),
+            ..OverrideAccount::default()
+        };
+        let mut storage = OverlayStorage::new(raw_storage);
+        apply_state_override(&mut storage, &StateOverride::from([(ACCOUNT, account)]));
+
+        assert_eq!(storage.read_value(&get_code_key(&ACCOUNT)), H256::zero());
+    }
+
+    #[test]
+    fn overriding_entire_account_state() {
+        let account = OverrideAccount {
+            state: Some(HashMap::from([(
+                H256::from_low_u64_be(1),
+                H256::from_low_u64_be(11),
+            )])),
+            ..OverrideAccount::default()
+        };
+        let mut storage = OverlayStorage::new(storage_with_account());
+        apply_state_override(&mut storage, &StateOverride::from([(ACCOUNT, account)]));
+
+        assert_eq!(read_slot(&mut storage, 1), H256::from_low_u64_be(11));
+        assert_eq!(read_slot(&mut storage, 2), H256::zero());
+    }
+
+    #[test]
+    fn overriding_account_state_diff() {
+        let account = OverrideAccount {
+            state_diff: Some(HashMap::from([(
+                H256::from_low_u64_be(1),
+                H256::from_low_u64_be(11),
+            )])),
+            ..OverrideAccount::default()
+        };
+        let mut storage = OverlayStorage::new(storage_with_account());
+        apply_state_override(&mut storage, &StateOverride::from([(ACCOUNT, account)]));
+
+        assert_eq!(read_slot(&mut storage, 1), H256::from_low_u64_be(11));
+        assert_eq!(read_slot(&mut storage, 2), H256::from_low_u64_be(20));
+    }
+
+    #[test]
+    fn nonce_override_takes_precedence_over_enforced_nonce() {
+        let account = OverrideAccount {
+            nonce: Some(U256::from(7)),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride::from([(ACCOUNT, account)]);
+        let mut storage = OverlayStorage::new(storage_with_account());
+        apply_overrides(
+            &mut storage,
+            &ACCOUNT,
+            Some(Nonce(5)),
+            Some(&state_override),
+        );
+        assert_eq!(read_nonces(&mut storage), (U256::from(7), U256::from(2)));
+
+        // Without a nonce override, the enforced nonce is used.
+        let mut storage = OverlayStorage::new(storage_with_account());
+        apply_overrides(&mut storage, &ACCOUNT, Some(Nonce(5)), None);
+        assert_eq!(read_nonces(&mut storage), (U256::from(5), U256::from(2)));
+    }
+}
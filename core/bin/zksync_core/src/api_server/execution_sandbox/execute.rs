@@ -2,19 +2,21 @@
 
 use tracing::{span, Level};
 
-use std::{collections::HashMap, mem};
+use std::{collections::HashMap, mem, ops::Range};
 
 use vm::{
+    oracles::tracer::StructLoggerParams,
     utils::ETH_CALL_GAS_LIMIT,
+    vm::{TxTracer, VmTxExecutionResult},
     vm_with_bootloader::{
         push_transaction_to_bootloader_memory, BootloaderJobType, TxExecutionMode,
     },
-    VmExecutionResult,
+    HistoryDisabled, VmBlockResult, VmExecutionResult, VmInstance,
 };
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Nonce, StorageKey,
-    Transaction, H256, U256,
+    api::StateOverride, fee::TransactionExecutionMetrics, l2::L2Tx, vm_trace::VmTrace,
+    ExecuteTransactionCommon, Nonce, StorageKey, Transaction, H256, U256,
 };
 
 use super::{apply, error::SandboxExecutionError, vm_metrics, BlockArgs, TxSharedArgs, VmPermit};
@@ -25,6 +27,8 @@ pub(crate) struct TxExecutionArgs {
     pub enforced_nonce: Option<Nonce>,
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
+    /// Overrides of the account state installed on top of the storage before the execution.
+    pub state_override: Option<StateOverride>,
 }
 
 impl TxExecutionArgs {
@@ -34,12 +38,14 @@ impl TxExecutionArgs {
             enforced_nonce: Some(tx.nonce()),
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
+            state_override: None,
         }
     }
 
     fn for_eth_call(
         enforced_base_fee: u64,
         vm_execution_cache_misses_limit: Option<usize>,
+        state_override: Option<StateOverride>,
     ) -> Self {
         let missed_storage_invocation_limit = vm_execution_cache_misses_limit.unwrap_or(usize::MAX);
         Self {
@@ -49,6 +55,18 @@ impl TxExecutionArgs {
             enforced_nonce: None,
             added_balance: U256::zero(),
             enforced_base_fee: Some(enforced_base_fee),
+            state_override,
+        }
+    }
+
+    /// Arguments to re-execute transactions included into a miniblock.
+    pub fn for_replay(base_fee: u64) -> Self {
+        Self {
+            execution_mode: TxExecutionMode::VerifyExecute,
+            enforced_nonce: None,
+            added_balance: U256::zero(),
+            enforced_base_fee: Some(base_fee),
+            state_override: None,
         }
     }
 
@@ -56,6 +74,7 @@ impl TxExecutionArgs {
         vm_execution_cache_misses_limit: Option<usize>,
         tx: &Transaction,
         base_fee: u64,
+        state_override: Option<StateOverride>,
     ) -> Self {
         let missed_storage_invocation_limit = vm_execution_cache_misses_limit.unwrap_or(usize::MAX);
         // For L2 transactions we need to explicitly put enough balance into the account of the users
@@ -72,6 +91,73 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            state_override,
+        }
+    }
+}
+
+/// Tracer collecting additional information during the sandboxed execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SandboxTracer {
+    /// No tracing: the execution result has an empty trace.
+    None,
+    /// Call tracer: the call tree is returned as `VmTrace::CallTrace`.
+    Call,
+    /// Prestate tracer: touched storage slots are returned as `VmTrace::PrestateTrace`.
+    Prestate,
+    /// Struct logger: executed opcodes are returned as `VmTrace::ExecutionTrace`. Besides the per-trace
+    /// step limit in `params`, at most `step_budget` steps are recorded for all traced transactions in total.
+    StructLogger {
+        params: StructLoggerParams,
+        step_budget: usize,
+    },
+}
+
+impl SandboxTracer {
+    fn execute_till_block_end(
+        self,
+        vm: &mut VmInstance<'_, HistoryDisabled>,
+        job_type: BootloaderJobType,
+    ) -> VmBlockResult {
+        match self {
+            Self::None => vm.execute_till_block_end(job_type),
+            Self::Call => vm.execute_till_block_end_with_call_tracer(job_type),
+            Self::Prestate => vm.execute_till_block_end_with_prestate_tracer(job_type),
+            Self::StructLogger {
+                params,
+                step_budget,
+            } => vm.execute_till_block_end_with_struct_logger(
+                job_type,
+                Self::limit_steps(params, step_budget),
+            ),
+        }
+    }
+
+    fn tx_tracer(self) -> TxTracer {
+        match self {
+            Self::None => TxTracer::None,
+            Self::Call => TxTracer::Call,
+            Self::Prestate => TxTracer::Prestate,
+            Self::StructLogger {
+                params,
+                step_budget,
+            } => TxTracer::StructLogger(Self::limit_steps(params, step_budget)),
+        }
+    }
+
+    fn limit_steps(params: StructLoggerParams, step_budget: usize) -> StructLoggerParams {
+        StructLoggerParams {
+            step_limit: params.step_limit.min(step_budget),
+            ..params
+        }
+    }
+
+    /// Deducts the steps recorded in the `trace` from the step budget shared by traced transactions.
+    fn consume_step_budget(&mut self, trace: &VmTrace) {
+        if let (Self::StructLogger { step_budget, .. }, VmTrace::ExecutionTrace(trace)) =
+            (self, trace)
+        {
+            *step_budget = step_budget.saturating_sub(trace.steps.len());
         }
     }
 }
@@ -84,11 +170,15 @@ pub(crate) async fn execute_tx_eth_call(
     mut tx: L2Tx,
     block_args: BlockArgs,
     vm_execution_cache_misses_limit: Option<usize>,
-    trace_call: bool,
+    state_override: Option<StateOverride>,
+    tracer: SandboxTracer,
 ) -> Result<VmExecutionResult, SandboxExecutionError> {
     let enforced_base_fee = tx.common_data.fee.max_fee_per_gas.as_u64();
-    let execution_args =
-        TxExecutionArgs::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit);
+    let execution_args = TxExecutionArgs::for_eth_call(
+        enforced_base_fee,
+        vm_execution_cache_misses_limit,
+        state_override,
+    );
 
     // Protection against infinite-loop eth_calls and alike:
     // limiting the amount of gas the call can use.
@@ -102,7 +192,7 @@ pub(crate) async fn execute_tx_eth_call(
         tx.into(),
         block_args,
         BootloaderJobType::TransactionExecution,
-        trace_call,
+        tracer,
         &mut HashMap::new(),
     )
     .await;
@@ -110,6 +200,135 @@ pub(crate) async fn execute_tx_eth_call(
     vm_result
 }
 
+/// Executes an ordered list of transactions on top of the same block, so that each transaction
+/// observes the state changes made by the previous ones.
+///
+/// Transactions are executed one by one using the playground bootloader. Once a transaction is rejected
+/// by the bootloader, the bootloader cannot proceed, so the execution stops; the returned list contains
+/// results for all transactions up to and including the rejected one. The enforced nonce and added balance
+/// from `execution_args` are only applied to the first transaction in the bundle.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn execute_txs_bundle(
+    vm_permit: &VmPermit<'_>, // Proof that permit was acquired.
+    shared_args: TxSharedArgs,
+    connection_pool: ConnectionPool,
+    txs: Vec<L2Tx>,
+    block_args: BlockArgs,
+    vm_execution_cache_misses_limit: Option<usize>,
+    state_override: Option<StateOverride>,
+) -> Vec<Result<VmTxExecutionResult, SandboxExecutionError>> {
+    let enforced_base_fee = txs
+        .iter()
+        .map(|tx| tx.common_data.fee.max_fee_per_gas.as_u64())
+        .min()
+        .expect("Bundle of transactions must not be empty");
+    let execution_args = TxExecutionArgs::for_eth_call(
+        enforced_base_fee,
+        vm_execution_cache_misses_limit,
+        state_override,
+    );
+    let txs: Vec<Transaction> = txs
+        .into_iter()
+        .map(|mut tx| {
+            // Same protection as for a single `eth_call`.
+            tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
+            tx.into()
+        })
+        .collect();
+
+    let rt_handle = vm_permit.rt_handle();
+    let (results, _) = tokio::task::spawn_blocking(move || {
+        let span = span!(Level::DEBUG, "execute_bundle_in_sandbox").entered();
+        let execution_mode = execution_args.execution_mode;
+        let result = apply::apply_vm_in_sandbox_with_txs(
+            rt_handle,
+            &shared_args,
+            &execution_args,
+            &connection_pool,
+            txs,
+            block_args,
+            HashMap::new(),
+            |vm, txs| {
+                let mut results = Vec::with_capacity(txs.len());
+                for tx in &txs {
+                    push_transaction_to_bootloader_memory(vm, tx, execution_mode, None);
+                    let result = vm.execute_next_tx(u32::MAX, true);
+                    let is_rejected = result.is_err();
+                    results.push(result.map_err(SandboxExecutionError::from));
+                    if is_rejected {
+                        break;
+                    }
+                }
+                results
+            },
+        );
+        span.exit();
+        result
+    })
+    .await
+    .unwrap();
+
+    results
+}
+
+/// Re-executes transactions from a sealed miniblock on top of the state before this miniblock,
+/// and traces transactions with indices in `traced_txs`. `txs` must start with the first transaction
+/// in the miniblock; transactions preceding the traced ones are executed without tracing,
+/// and transactions following them are not executed at all. All transactions are executed
+/// in a single VM run, one after another.
+///
+/// Unlike other methods, a reverted transaction is not considered an error; its revert reason
+/// is returned as a part of the execution result.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip_all)]
+pub(crate) async fn replay_txs(
+    vm_permit: &VmPermit<'_>, // Proof that permit was acquired.
+    shared_args: TxSharedArgs,
+    connection_pool: ConnectionPool,
+    txs: Vec<Transaction>,
+    traced_txs: Range<usize>,
+    block_args: BlockArgs,
+    base_fee: u64,
+    mut tracer: SandboxTracer,
+) -> Result<Vec<VmExecutionResult>, SandboxExecutionError> {
+    let execution_args = TxExecutionArgs::for_replay(base_fee);
+    let rt_handle = vm_permit.rt_handle();
+    let (result, _) = tokio::task::spawn_blocking(move || {
+        let span = span!(Level::DEBUG, "replay_in_sandbox").entered();
+        let execution_mode = execution_args.execution_mode;
+        let result = apply::apply_vm_in_sandbox_with_txs(
+            rt_handle,
+            &shared_args,
+            &execution_args,
+            &connection_pool,
+            txs,
+            block_args,
+            HashMap::new(),
+            |vm, txs| -> Result<_, SandboxExecutionError> {
+                let mut results = Vec::with_capacity(traced_txs.len());
+                for (tx_index, tx) in txs.iter().enumerate().take(traced_txs.end) {
+                    push_transaction_to_bootloader_memory(vm, tx, execution_mode, None);
+                    if traced_txs.contains(&tx_index) {
+                        let result =
+                            vm.execute_next_tx_with_tracer(u32::MAX, tracer.tx_tracer())?;
+                        tracer.consume_step_budget(&result.trace);
+                        results.push(result);
+                    } else {
+                        vm.execute_next_tx(u32::MAX, false)?;
+                    }
+                }
+                Ok(results)
+            },
+        );
+        span.exit();
+        result
+    })
+    .await
+    .unwrap();
+
+    result
+}
+
 #[tracing::instrument(skip_all)]
 pub(crate) async fn execute_tx_with_pending_state(
     vm_permit: &VmPermit<'_>, // Proof that permit was acquired.
@@ -137,7 +356,7 @@ pub(crate) async fn execute_tx_with_pending_state(
         tx,
         block_args,
         BootloaderJobType::TransactionExecution,
-        false,
+        SandboxTracer::None,
         storage_read_cache,
     )
     .await
@@ -155,7 +374,7 @@ async fn execute_tx_in_sandbox(
     tx: Transaction,
     block_args: BlockArgs,
     job_type: BootloaderJobType,
-    trace_call: bool,
+    tracer: SandboxTracer,
     storage_read_cache: &mut HashMap<StorageKey, H256>,
 ) -> (
     Result<VmExecutionResult, SandboxExecutionError>,
@@ -182,12 +401,7 @@ async fn execute_tx_in_sandbox(
             moved_cache,
             |vm, tx| {
                 push_transaction_to_bootloader_memory(vm, &tx, execution_mode, None);
-                let result = if trace_call {
-                    vm.execute_till_block_end_with_call_tracer(job_type)
-                } else {
-                    vm.execute_till_block_end(job_type)
-                };
-                result.full_result
+                tracer.execute_till_block_end(vm, job_type).full_result
             },
         );
         span.exit();
@@ -6,9 +6,11 @@ use zksync_config::constants::PUBLISH_BYTECODE_OVERHEAD;
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
 use zksync_state::{FactoryDepsCache, PostgresStorage, ReadStorage, StorageView};
-use zksync_types::{api, AccountTreeId, MiniblockNumber, U256};
+use zksync_types::{api, block::MiniblockHeader, AccountTreeId, MiniblockNumber, U256};
 use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 
+use crate::state_keeper::PendingState;
+
 // Note: keep the modules private, and instead re-export functions that make public interface.
 mod apply;
 mod error;
@@ -18,7 +20,10 @@ mod vm_metrics;
 
 pub(super) use self::{
     error::SandboxExecutionError,
-    execute::{execute_tx_eth_call, execute_tx_with_pending_state, TxExecutionArgs},
+    execute::{
+        execute_tx_eth_call, execute_tx_with_pending_state, execute_txs_bundle, replay_txs,
+        SandboxTracer, TxExecutionArgs,
+    },
 };
 
 /// Permit to invoke VM code.
@@ -209,6 +214,9 @@ pub(crate) struct TxSharedArgs {
     pub fair_l2_gas_price: u64,
     pub base_system_contracts: BaseSystemContracts,
     pub factory_deps_cache: FactoryDepsCache,
+    /// Changes made by the state keeper that are not persisted yet. If set, they are installed
+    /// on top of the storage when executing on the pending block.
+    pub pending_state: Option<PendingState>,
 }
 
 /// Information about a block provided to VM.
@@ -217,6 +225,9 @@ pub(crate) struct BlockArgs {
     block_id: api::BlockId,
     resolved_block_number: MiniblockNumber,
     block_timestamp_s: Option<u64>,
+    /// If set, the VM starts from the state before the resolved miniblock rather than after it,
+    /// so that the miniblock transactions can be re-executed.
+    is_replay: bool,
 }
 
 impl BlockArgs {
@@ -226,6 +237,17 @@ impl BlockArgs {
             block_id,
             resolved_block_number,
             block_timestamp_s: None,
+            is_replay: false,
+        }
+    }
+
+    /// Creates block information for re-executing transactions of the specified miniblock.
+    pub fn for_replay(header: &MiniblockHeader) -> Self {
+        Self {
+            block_id: api::BlockId::Number(api::BlockNumber::Number(header.number.0.into())),
+            resolved_block_number: header.number,
+            block_timestamp_s: Some(header.timestamp),
+            is_replay: true,
         }
     }
 
@@ -248,6 +270,7 @@ impl BlockArgs {
             block_id,
             resolved_block_number,
             block_timestamp_s,
+            is_replay: false,
         }))
     }
 }
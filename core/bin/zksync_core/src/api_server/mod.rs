@@ -2,5 +2,7 @@
 pub mod execution_sandbox;
 pub mod explorer;
 pub mod healthcheck;
+#[cfg(test)]
+mod test_utils;
 pub mod tx_sender;
 pub mod web3;
@@ -0,0 +1,141 @@
+//! Shared utils for unit tests.
+
+use zksync_contracts::BaseSystemContracts;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    block::MiniblockHeader,
+    fee::Fee,
+    l2::L2Tx,
+    tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
+    utils::storage_key_for_eth_balance,
+    Address, L2ChainId, MiniblockNumber, Nonce, PackedEthSignature, StorageLog, H256,
+    SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
+};
+use zksync_utils::u256_to_h256;
+
+use crate::genesis::create_genesis_block;
+
+pub(crate) const CHAIN_ID: L2ChainId = L2ChainId(270);
+
+/// Performs the genesis and funds the account with the specified private key.
+pub(crate) async fn prepare_storage(
+    pool: &ConnectionPool,
+    base_system_contracts: &BaseSystemContracts,
+    private_key: &H256,
+) {
+    let mut storage = pool.access_storage_tagged("api").await;
+    create_genesis_block(
+        &mut storage,
+        Address::repeat_byte(0x01),
+        CHAIN_ID,
+        base_system_contracts.clone(),
+    )
+    .await;
+
+    let sender = PackedEthSignature::address_from_private_key(private_key).unwrap();
+    let balance = u256_to_h256(U256::from(10).pow(U256::from(32)));
+    let storage_logs = vec![StorageLog::new_write_log(
+        storage_key_for_eth_balance(&sender),
+        balance,
+    )];
+    storage
+        .storage_logs_dal()
+        .append_storage_logs(MiniblockNumber(0), &[(H256::zero(), storage_logs.clone())])
+        .await;
+    storage
+        .storage_dal()
+        .apply_storage_logs(&[(H256::zero(), storage_logs)])
+        .await;
+}
+
+/// Creates a transaction with the first nonce of the account with the specified private key.
+pub(crate) fn create_l2_tx(private_key: &H256, max_fee_per_gas: u64) -> L2Tx {
+    create_transfer(
+        private_key,
+        Address::repeat_byte(0x42),
+        U256::zero(),
+        max_fee_per_gas,
+    )
+}
+
+/// Creates a transfer of `value` to the `recipient` with the first nonce of the account
+/// with the specified private key.
+pub(crate) fn create_transfer(
+    private_key: &H256,
+    recipient: Address,
+    value: U256,
+    max_fee_per_gas: u64,
+) -> L2Tx {
+    let fee = Fee {
+        gas_limit: 1_000_000.into(),
+        max_fee_per_gas: max_fee_per_gas.into(),
+        max_priority_fee_per_gas: U256::zero(),
+        gas_per_pubdata_limit: 100.into(),
+    };
+    let mut tx = L2Tx::new_signed(
+        recipient,
+        vec![],
+        Nonce(0),
+        fee,
+        value,
+        CHAIN_ID,
+        private_key,
+        None,
+        Default::default(),
+    )
+    .unwrap();
+    tx.set_input(H256::random().0.to_vec(), H256::random());
+    tx
+}
+
+/// Seals a miniblock with the specified transactions executed successfully. Call traces
+/// are not saved for the transactions.
+pub(crate) async fn seal_miniblock(
+    pool: &ConnectionPool,
+    number: MiniblockNumber,
+    txs: Vec<L2Tx>,
+    base_system_contracts: &BaseSystemContracts,
+) {
+    let mut storage = pool.access_storage_tagged("api").await;
+    for tx in &txs {
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), Default::default())
+            .await;
+    }
+
+    let header = MiniblockHeader {
+        number,
+        timestamp: number.0.into(),
+        hash: H256::from_low_u64_be(number.0.into()),
+        l1_tx_count: 0,
+        l2_tx_count: txs.len() as u16,
+        base_fee_per_gas: SYSTEM_CONTEXT_MINIMAL_BASE_FEE,
+        l1_gas_price: 1,
+        l2_fair_gas_price: 1,
+        base_system_contracts_hashes: base_system_contracts.hashes(),
+    };
+    storage.blocks_dal().insert_miniblock(&header).await;
+    let execution_results: Vec<_> = txs
+        .into_iter()
+        .map(|tx| TransactionExecutionResult {
+            hash: tx.hash(),
+            transaction: tx.into(),
+            execution_info: ExecutionMetrics::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            compressed_bytecodes: vec![],
+            call_traces: vec![],
+            revert_reason: None,
+        })
+        .collect();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(
+            header.number,
+            &execution_results,
+            header.base_fee_per_gas.into(),
+        )
+        .await;
+}
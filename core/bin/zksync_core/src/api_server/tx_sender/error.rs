@@ -61,6 +61,13 @@ pub enum SubmitTxError {
     /// than required to start the invocation.
     #[error("intrinsic gas too low")]
     IntrinsicGas,
+    /// ReplacementUnderpriced is returned if the transaction replaces a pending transaction
+    /// with the same nonce, but doesn't bump its fees by the required percentage.
+    #[error(
+        "replacement transaction underpriced. max fee per gas and max priority fee per gas \
+        must be at least {0}% higher than in the replaced transaction"
+    )]
+    ReplacementUnderpriced(u64),
     /// Error returned from main node
     #[error("{0}")]
     ProxyError(#[from] zksync_web3_decl::jsonrpsee::core::Error),
@@ -94,6 +101,7 @@ impl SubmitTxError {
             SubmitTxError::FeePerPubdataByteTooHigh => "pubdata-price-limit-too-high",
             SubmitTxError::InsufficientFundsForTransfer => "insufficient-funds-for-transfer",
             SubmitTxError::IntrinsicGas => "intrinsic-gas",
+            SubmitTxError::ReplacementUnderpriced(_) => "replacement-underpriced",
             SubmitTxError::ProxyError(_) => "proxy-error",
         }
     }
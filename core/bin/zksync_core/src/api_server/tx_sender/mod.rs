@@ -14,11 +14,15 @@ use std::{cmp, collections::HashMap, num::NonZeroU32, sync::Arc, time::Instant};
 // Workspace uses
 use vm::{
     transaction_data::{derive_overhead, OverheadCoeficients},
+    vm::VmTxExecutionResult,
     vm_with_bootloader::derive_base_fee_and_gas_per_pubdata,
     zk_evm::zkevm_opcode_defs::system_params::MAX_PUBDATA_PER_BLOCK,
     VmExecutionResult,
 };
-use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
+use zksync_config::configs::{
+    api::Web3JsonRpcConfig,
+    chain::{MempoolConfig, StateKeeperConfig},
+};
 use zksync_contracts::{
     BaseSystemContracts, SystemContractCode, ESTIMATE_FEE_BLOCK_CODE,
     PLAYGROUND_BLOCK_BOOTLOADER_CODE,
@@ -26,6 +30,7 @@ use zksync_contracts::{
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
 use zksync_state::FactoryDepsCache;
 use zksync_types::{
+    api::StateOverride,
     fee::{Fee, TransactionExecutionMetrics},
     get_code_key, get_intrinsic_constants,
     l2::error::TxCheckError::TxDuplication,
@@ -34,16 +39,19 @@ use zksync_types::{
     AccountTreeId, Address, ExecuteTransactionCommon, Nonce, StorageKey, Transaction, H160, H256,
     MAX_GAS_PER_PUBDATA_BYTE, MAX_L2_TX_GAS_LIMIT, MAX_NEW_FACTORY_DEPS, U256,
 };
-use zksync_utils::{bytes_to_be_words, h256_to_u256};
+use zksync_utils::{bytecode::hash_bytecode, bytes_to_be_words, h256_to_u256};
 
 // Local uses
 use crate::api_server::execution_sandbox::{
     adjust_l1_gas_price_for_tx, execute_tx_eth_call, execute_tx_with_pending_state,
-    get_pubdata_for_factory_deps, BlockArgs, SandboxExecutionError, TxExecutionArgs, TxSharedArgs,
-    VmConcurrencyLimiter, VmPermit,
+    execute_txs_bundle, get_pubdata_for_factory_deps, BlockArgs, SandboxExecutionError,
+    SandboxTracer, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
 };
 use crate::l1_gas_price::L1GasPriceProvider;
-use crate::state_keeper::seal_criteria::{ConditionalSealer, SealData};
+use crate::state_keeper::{
+    seal_criteria::{ConditionalSealer, SealData},
+    PendingState,
+};
 
 mod error;
 mod proxy;
@@ -70,6 +78,8 @@ pub struct TxSenderBuilder {
     /// Actual state keeper configuration, required for tx verification.
     /// If not set, transactions would not be checked against seal criteria.
     state_keeper_config: Option<StateKeeperConfig>,
+    /// Changes made by the co-located state keeper that are not persisted yet.
+    pending_state: Option<PendingState>,
 }
 
 impl TxSenderBuilder {
@@ -81,6 +91,7 @@ impl TxSenderBuilder {
             rate_limiter: None,
             proxy: None,
             state_keeper_config: None,
+            pending_state: None,
         }
     }
 
@@ -110,6 +121,13 @@ impl TxSenderBuilder {
         self
     }
 
+    /// Makes calls on the pending block observe the changes made by the co-located state keeper
+    /// that are not persisted yet.
+    pub(crate) fn with_pending_state(mut self, pending_state: PendingState) -> Self {
+        self.pending_state = Some(pending_state);
+        self
+    }
+
     pub async fn build<G: L1GasPriceProvider>(
         self,
         l1_gas_price_source: Arc<G>,
@@ -160,6 +178,7 @@ impl TxSenderBuilder {
             state_keeper_config: self.state_keeper_config,
             vm_concurrency_limiter,
             factory_deps_cache,
+            pending_state: self.pending_state,
         }))
     }
 }
@@ -179,12 +198,16 @@ pub struct TxSenderConfig {
     pub validation_computational_gas_limit: u32,
     pub default_aa: H256,
     pub bootloader: H256,
+    /// Min percentage by which a transaction replacing a pending one with the same initiator and nonce
+    /// must bump both fee values. Replacements are not checked if not specified.
+    pub replacement_fee_bump_percent: Option<u64>,
 }
 
 impl TxSenderConfig {
     pub fn new(
         state_keeper_config: &StateKeeperConfig,
         web3_json_config: &Web3JsonRpcConfig,
+        mempool_config: &MempoolConfig,
     ) -> Self {
         Self {
             fee_account_addr: state_keeper_config.fee_account_addr,
@@ -197,6 +220,7 @@ impl TxSenderConfig {
                 .validation_computational_gas_limit,
             default_aa: state_keeper_config.default_aa_hash,
             bootloader: state_keeper_config.bootloader_hash,
+            replacement_fee_bump_percent: mempool_config.replacement_fee_bump_percent,
         }
     }
 }
@@ -221,6 +245,8 @@ pub struct TxSenderInner<G> {
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Smart contract source code cache.
     pub(super) factory_deps_cache: FactoryDepsCache,
+    /// Changes made by the co-located state keeper that are not persisted yet, if any.
+    pub(super) pending_state: Option<PendingState>,
 }
 
 pub struct TxSender<G>(pub Arc<TxSenderInner<G>>);
@@ -317,6 +343,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         let nonce = tx.common_data.nonce.0;
         let hash = tx.hash();
         let expected_nonce = self.get_expected_nonce(&tx).await;
+        let fee_bump_percent = self.0.sender_config.replacement_fee_bump_percent;
         let submission_res_handle = self
             .0
             .master_connection_pool
@@ -325,7 +352,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             .access_storage_tagged("api")
             .await
             .transactions_dal()
-            .insert_transaction_l2(tx, tx_metrics)
+            .insert_transaction_l2_with_fee_bump(tx, tx_metrics, fee_bump_percent)
             .await;
 
         let status: String;
@@ -342,6 +369,12 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                 status = "duplicated".to_string();
                 Err(SubmitTxError::IncorrectTx(TxDuplication(hash)))
             }
+            L2TxSubmissionResult::ReplacementUnderpriced => {
+                status = "replacement_underpriced".to_string();
+                let fee_bump_percent = fee_bump_percent
+                    .expect("underpriced replacements are only detected if fee bump is specified");
+                Err(SubmitTxError::ReplacementUnderpriced(fee_bump_percent))
+            }
             _ => {
                 metrics::histogram!("api.web3.submit_tx", stage_started_at.elapsed(), "stage" => "4_db_insert");
                 status = format!(
@@ -368,6 +401,16 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             fair_l2_gas_price: self.0.sender_config.fair_l2_gas_price,
             base_system_contracts: self.0.playground_base_system_contracts.clone(),
             factory_deps_cache: self.0.factory_deps_cache.clone(),
+            pending_state: None,
+        }
+    }
+
+    /// Same as [`Self::shared_args()`], but calls on the pending block will observe the pending state
+    /// of the co-located state keeper (if any).
+    fn shared_args_for_call(&self) -> TxSharedArgs {
+        TxSharedArgs {
+            pending_state: self.0.pending_state.clone(),
+            ..self.shared_args()
         }
     }
 
@@ -537,6 +580,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         tx_gas_limit: u32,
         l1_gas_price: u64,
         base_fee: u64,
+        state_override: Option<&StateOverride>,
         storage_read_cache: &mut HashMap<StorageKey, H256>,
     ) -> Result<VmExecutionResult, SandboxExecutionError> {
         let gas_limit_with_overhead = tx_gas_limit
@@ -561,8 +605,12 @@ impl<G: L1GasPriceProvider> TxSender<G> {
 
         let shared_args = self.shared_args_for_gas_estimate(l1_gas_price);
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        let execution_args =
-            TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
+        let execution_args = TxExecutionArgs::for_gas_estimate(
+            vm_execution_cache_misses_limit,
+            &tx,
+            base_fee,
+            state_override.cloned(),
+        );
         let (exec_result, tx_metrics) = execute_tx_with_pending_state(
             vm_permit,
             shared_args,
@@ -587,6 +635,8 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             fair_l2_gas_price: self.0.sender_config.fair_l2_gas_price,
             base_system_contracts: self.0.estimate_fee_base_system_contracts.clone(),
             factory_deps_cache: self.0.factory_deps_cache.clone(),
+            // Gas is always estimated in the pending block.
+            pending_state: self.0.pending_state.clone(),
         }
     }
 
@@ -595,6 +645,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         mut tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
+        state_override: Option<StateOverride>,
     ) -> Result<Fee, SubmitTxError> {
         let estimation_started_at = Instant::now();
         let l1_gas_price = {
@@ -625,31 +676,42 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             }
         }
 
-        let hashed_key = get_code_key(&tx.initiator_account());
+        let initiator_override = state_override
+            .as_ref()
+            .and_then(|state_override| state_override.get(&tx.initiator_account()));
         // if the default account does not have enough funds
         // for transferring tx.value, without taking into account the fee,
         // there is no sense to estimate the fee
-        let account_code_hash = self
-            .0
-            .replica_connection_pool
-            .access_storage_tagged("api")
-            .await
-            .storage_dal()
-            .get_by_key(&hashed_key)
-            .await
-            .unwrap_or_default();
+        let account_code_hash = match initiator_override.and_then(|account| account.code.as_ref()) {
+            Some(code) if code.0.is_empty() => H256::zero(),
+            Some(code) => hash_bytecode(&code.0),
+            None => {
+                let hashed_key = get_code_key(&tx.initiator_account());
+                self.0
+                    .replica_connection_pool
+                    .access_storage_tagged("api")
+                    .await
+                    .storage_dal()
+                    .get_by_key(&hashed_key)
+                    .await
+                    .unwrap_or_default()
+            }
+        };
 
-        if !tx.is_l1()
-            && account_code_hash == H256::zero()
-            && tx.execute.value > self.get_balance(&tx.initiator_account()).await
-        {
-            vlog::info!(
-                "fee estimation failed on validation step.
-                account: {} does not have enough funds for for transferring tx.value: {}.",
-                &tx.initiator_account(),
-                tx.execute.value
-            );
-            return Err(SubmitTxError::InsufficientFundsForTransfer);
+        if !tx.is_l1() && account_code_hash == H256::zero() {
+            let balance = match initiator_override.and_then(|account| account.balance) {
+                Some(balance) => balance,
+                None => self.get_balance(&tx.initiator_account()).await,
+            };
+            if tx.execute.value > balance {
+                vlog::info!(
+                    "fee estimation failed on validation step.
+                    account: {} does not have enough funds for for transferring tx.value: {}.",
+                    &tx.initiator_account(),
+                    tx.execute.value
+                );
+                return Err(SubmitTxError::InsufficientFundsForTransfer);
+            }
         }
 
         // For L2 transactions we need a properly formatted signature
@@ -718,6 +780,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                     try_gas_limit,
                     l1_gas_price,
                     base_fee,
+                    state_override.as_ref(),
                     &mut storage_read_cache,
                 )
                 .await;
@@ -756,6 +819,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                 suggested_gas_limit,
                 l1_gas_price,
                 base_fee,
+                state_override.as_ref(),
                 &mut storage_read_cache,
             )
             .await;
@@ -796,17 +860,19 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         &self,
         block_args: BlockArgs,
         tx: L2Tx,
+        state_override: Option<StateOverride>,
     ) -> Result<Vec<u8>, SubmitTxError> {
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
         let result = execute_tx_eth_call(
             &vm_permit,
-            self.shared_args(),
+            self.shared_args_for_call(),
             self.0.replica_connection_pool.clone(),
             tx,
             block_args,
             vm_execution_cache_misses_limit,
-            false,
+            state_override,
+            SandboxTracer::None,
         )
         .await?;
         drop(vm_permit); // Unblock other VMs to enter.
@@ -821,6 +887,35 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         })
     }
 
+    /// Executes an ordered list of transactions on top of the specified block, so that each transaction
+    /// observes the state changes made by the previous ones. Results are returned for transactions
+    /// up to and including the first rejected one.
+    pub(super) async fn simulate_bundle(
+        &self,
+        block_args: BlockArgs,
+        txs: Vec<L2Tx>,
+        state_override: Option<StateOverride>,
+    ) -> Vec<Result<VmTxExecutionResult, SubmitTxError>> {
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let results = execute_txs_bundle(
+            &vm_permit,
+            self.shared_args_for_call(),
+            self.0.replica_connection_pool.clone(),
+            txs,
+            block_args,
+            vm_execution_cache_misses_limit,
+            state_override,
+        )
+        .await;
+        drop(vm_permit); // Unblock other VMs to enter.
+
+        results
+            .into_iter()
+            .map(|result| result.map_err(SubmitTxError::from))
+            .collect()
+    }
+
     pub fn gas_price(&self) -> u64 {
         let gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
         let l1_gas_price = (gas_price as f64 * self.0.sender_config.gas_price_scale_factor).round();
@@ -865,3 +960,145 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use db_test_macro::db_test;
+
+    use super::*;
+    use crate::api_server::test_utils::{create_l2_tx, create_transfer, prepare_storage, CHAIN_ID};
+    use zksync_types::{
+        api::{BlockId, BlockNumber, TransactionId},
+        tx::tx_execution_info::TxExecutionStatus,
+        PackedEthSignature, SYSTEM_CONTEXT_MINIMAL_BASE_FEE,
+    };
+
+    const FEE_BUMP_PERCENT: u64 = 10;
+
+    #[derive(Debug)]
+    struct MockL1GasPriceProvider(u64);
+
+    impl L1GasPriceProvider for MockL1GasPriceProvider {
+        fn estimate_effective_gas_price(&self) -> u64 {
+            self.0
+        }
+    }
+
+    async fn create_tx_sender(
+        pool: ConnectionPool,
+        base_system_contracts: &BaseSystemContracts,
+    ) -> TxSender<MockL1GasPriceProvider> {
+        let config = TxSenderConfig {
+            fee_account_addr: Address::repeat_byte(0x01),
+            gas_price_scale_factor: 1.0,
+            max_nonce_ahead: 5,
+            max_allowed_l2_tx_gas_limit: MAX_L2_TX_GAS_LIMIT as u32,
+            fair_l2_gas_price: SYSTEM_CONTEXT_MINIMAL_BASE_FEE,
+            vm_execution_cache_misses_limit: None,
+            validation_computational_gas_limit: 300_000,
+            default_aa: base_system_contracts.default_aa.hash,
+            bootloader: base_system_contracts.bootloader.hash,
+            replacement_fee_bump_percent: Some(FEE_BUMP_PERCENT),
+        };
+        TxSenderBuilder::new(config, pool.clone())
+            .with_main_connection_pool(pool)
+            .build(
+                Arc::new(MockL1GasPriceProvider(1)),
+                base_system_contracts.default_aa.hash,
+                Arc::new(VmConcurrencyLimiter::new(None)),
+                FactoryDepsCache::new("test_factory_deps_cache", 128),
+            )
+            .await
+    }
+
+    #[db_test]
+    async fn submitting_replacement_transactions(pool: ConnectionPool) {
+        let base_system_contracts = BaseSystemContracts::load_from_disk();
+        let private_key = H256::random();
+        prepare_storage(&pool, &base_system_contracts, &private_key).await;
+        let tx_sender = create_tx_sender(pool.clone(), &base_system_contracts).await;
+
+        let tx = create_l2_tx(&private_key, SYSTEM_CONTEXT_MINIMAL_BASE_FEE);
+        let result = tx_sender.submit_tx(tx.clone()).await.unwrap();
+        assert_eq!(result, L2TxSubmissionResult::Added);
+
+        let underpriced_tx =
+            create_l2_tx(&private_key, SYSTEM_CONTEXT_MINIMAL_BASE_FEE * 105 / 100);
+        let err = tx_sender.submit_tx(underpriced_tx).await.unwrap_err();
+        assert_matches!(err, SubmitTxError::ReplacementUnderpriced(FEE_BUMP_PERCENT));
+        let pending_tx = pool
+            .access_storage_tagged("api")
+            .await
+            .transactions_web3_dal()
+            .get_transaction(TransactionId::Hash(tx.hash()), CHAIN_ID)
+            .await
+            .unwrap();
+        assert!(
+            pending_tx.is_some(),
+            "underpriced replacement evicted the pending tx"
+        );
+
+        let replacement_tx =
+            create_l2_tx(&private_key, SYSTEM_CONTEXT_MINIMAL_BASE_FEE * 110 / 100);
+        let result = tx_sender.submit_tx(replacement_tx).await.unwrap();
+        assert_eq!(result, L2TxSubmissionResult::Replaced);
+        let pending_tx = pool
+            .access_storage_tagged("api")
+            .await
+            .transactions_web3_dal()
+            .get_transaction(TransactionId::Hash(tx.hash()), CHAIN_ID)
+            .await
+            .unwrap();
+        assert!(pending_tx.is_none(), "replaced tx is still stored");
+    }
+
+    #[db_test]
+    async fn simulating_bundle_with_dependent_transactions(pool: ConnectionPool) {
+        let base_system_contracts = BaseSystemContracts::load_from_disk();
+        let private_key = H256::random();
+        prepare_storage(&pool, &base_system_contracts, &private_key).await;
+        let tx_sender = create_tx_sender(pool.clone(), &base_system_contracts).await;
+
+        // The first transaction funds a new account, and the second one transfers the funds further.
+        let intermediate_key = H256::random();
+        let intermediate_account =
+            PackedEthSignature::address_from_private_key(&intermediate_key).unwrap();
+        let value = U256::from(10).pow(U256::from(18));
+        let funding_tx = create_transfer(
+            &private_key,
+            intermediate_account,
+            value,
+            SYSTEM_CONTEXT_MINIMAL_BASE_FEE,
+        );
+        let transfer_tx = create_transfer(
+            &intermediate_key,
+            Address::repeat_byte(0x42),
+            value,
+            SYSTEM_CONTEXT_MINIMAL_BASE_FEE,
+        );
+
+        let mut storage = pool.access_storage_tagged("api").await;
+        let block_args = BlockArgs::new(&mut storage, BlockId::Number(BlockNumber::Pending))
+            .await
+            .unwrap()
+            .unwrap();
+        drop(storage);
+
+        let results = tx_sender
+            .simulate_bundle(block_args, vec![funding_tx, transfer_tx.clone()], None)
+            .await;
+        assert_eq!(results.len(), 2);
+        for result in results {
+            assert_eq!(result.unwrap().status, TxExecutionStatus::Success);
+        }
+
+        // Without the funding transaction, the transfer fails.
+        let results = tx_sender
+            .simulate_bundle(block_args, vec![transfer_tx], None)
+            .await;
+        assert_eq!(results.len(), 1);
+        let result = results.into_iter().next().unwrap();
+        assert_eq!(result.unwrap().status, TxExecutionStatus::Failure);
+    }
+}
@@ -4,7 +4,9 @@ use zksync_web3_decl::error::Web3Error;
 pub fn into_jsrpc_error(err: Web3Error) -> Error {
     Error {
         code: match err {
-            Web3Error::InternalError | Web3Error::NotImplemented => ErrorCode::InternalError,
+            Web3Error::InternalError
+            | Web3Error::NotImplemented
+            | Web3Error::TreeApiUnavailable => ErrorCode::InternalError,
             Web3Error::NoBlock
             | Web3Error::NoSuchFunction
             | Web3Error::RLPError(_)
@@ -13,10 +15,15 @@ pub fn into_jsrpc_error(err: Web3Error) -> Error {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::LogsLimitExceeded(_, _, _)
-            | Web3Error::InvalidFilterBlockHash => ErrorCode::InvalidParams,
+            | Web3Error::InvalidFilterBlockHash
+            | Web3Error::InvalidStateOverride(_)
+            | Web3Error::InvalidBundleSize(_)
+            | Web3Error::TooManyReplayedTransactions(_)
+            | Web3Error::TooManyTraceSteps(_) => ErrorCode::InvalidParams,
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3.into(),
             Web3Error::PubSubTimeout => 4.into(),
             Web3Error::RequestTimeout => 5.into(),
+            Web3Error::RateLimitExceeded => 6.into(),
         },
         message: match err {
             Web3Error::SubmitTransactionError(_, _) => err.to_string(),
@@ -0,0 +1,71 @@
+//! Request metadata and middleware used by the `jsonrpc` backend.
+
+use futures::future::{self, Either};
+use jsonrpc_core::{middleware::NoopFuture, Call, Metadata, Middleware, Output};
+use jsonrpc_pubsub::{PubSubMetadata, Session};
+
+use std::{future::Future, sync::Arc};
+
+use super::error::into_jsrpc_error;
+use crate::api_server::web3::rate_limiter::ApiRateLimiter;
+
+/// Metadata of a JSON-RPC request.
+#[derive(Clone, Default)]
+pub struct RpcMetadata {
+    /// Key identifying the client for rate limiting purposes; only set if rate limiting is enabled.
+    pub client_key: Option<String>,
+    /// Pub-sub session; only set for WebSocket connections.
+    pub session: Option<Arc<Session>>,
+}
+
+impl Metadata for RpcMetadata {}
+
+impl PubSubMetadata for RpcMetadata {
+    fn session(&self) -> Option<Arc<Session>> {
+        self.session.clone()
+    }
+}
+
+/// Middleware applying per-client rate limiting to method calls.
+#[derive(Debug, Clone)]
+pub struct RateLimitMiddleware {
+    rate_limiter: Option<Arc<ApiRateLimiter>>,
+    transport: &'static str,
+}
+
+impl RateLimitMiddleware {
+    pub fn new(rate_limiter: Option<Arc<ApiRateLimiter>>, transport: &'static str) -> Self {
+        Self {
+            rate_limiter,
+            transport,
+        }
+    }
+}
+
+impl Middleware<RpcMetadata> for RateLimitMiddleware {
+    type Future = NoopFuture;
+    type CallFuture = future::Ready<Option<Output>>;
+
+    fn on_call<F, X>(&self, call: Call, meta: RpcMetadata, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, RpcMetadata) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let rate_limiter = self.rate_limiter.as_ref().zip(meta.client_key.as_deref());
+        if let (Some((rate_limiter, client_key)), Call::MethodCall(method_call)) =
+            (rate_limiter, &call)
+        {
+            let methods = [method_call.method.as_str()];
+            let check_result = rate_limiter.check(client_key, methods, self.transport);
+            if let Err(err) = check_result {
+                let output = Output::from(
+                    Err(into_jsrpc_error(err)),
+                    method_call.id.clone(),
+                    method_call.jsonrpc,
+                );
+                return Either::Left(future::ready(Some(output)));
+            }
+        }
+        Either::Right(next(call, meta))
+    }
+}
@@ -1,3 +1,4 @@
 pub mod error;
+pub mod middleware;
 pub mod namespaces;
 pub mod pub_sub;
@@ -7,8 +7,8 @@ use jsonrpc_derive::rpc;
 // Workspace uses
 use zksync_types::{
     api::{
-        BlockId, BlockIdVariant, BlockNumber, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        BlockId, BlockIdVariant, BlockNumber, FeeHistory, StateOverride, Transaction,
+        TransactionId, TransactionReceipt, TransactionVariant,
     },
     transaction_request::CallRequest,
     web3::types::{Index, SyncState},
@@ -30,18 +30,32 @@ pub trait EthNamespaceT {
     fn chain_id(&self) -> BoxFuture<Result<U64>>;
 
     #[rpc(name = "eth_call")]
-    fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> BoxFuture<Result<Bytes>>;
+    fn call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Result<Bytes>>;
 
     #[rpc(name = "eth_estimateGas")]
     fn estimate_gas(
         &self,
         req: CallRequest,
         _block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
     ) -> BoxFuture<Result<U256>>;
 
     #[rpc(name = "eth_gasPrice")]
     fn gas_price(&self) -> BoxFuture<Result<U256>>;
 
+    #[rpc(name = "eth_feeHistory")]
+    fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f32>>,
+    ) -> BoxFuture<Result<FeeHistory>>;
+
     #[rpc(name = "eth_newFilter")]
     fn new_filter(&self, filter: Filter) -> BoxFuture<Result<U256>>;
 
@@ -135,6 +149,12 @@ pub trait EthNamespaceT {
     #[rpc(name = "eth_getTransactionReceipt")]
     fn get_transaction_receipt(&self, hash: H256) -> BoxFuture<Result<Option<TransactionReceipt>>>;
 
+    #[rpc(name = "eth_getBlockReceipts")]
+    fn get_block_receipts(
+        &self,
+        block: BlockId,
+    ) -> BoxFuture<Result<Option<Vec<TransactionReceipt>>>>;
+
     #[rpc(name = "eth_protocolVersion")]
     fn protocol_version(&self) -> BoxFuture<Result<String>>;
 
@@ -191,11 +211,16 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceT for EthNamespa
         Box::pin(async move { Ok(self_.chain_id_impl()) })
     }
 
-    fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> BoxFuture<Result<Bytes>> {
+    fn call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Result<Bytes>> {
         let self_ = self.clone();
         Box::pin(async move {
             self_
-                .call_impl(req, block.map(Into::into))
+                .call_impl(req, block.map(Into::into), state_override)
                 .await
                 .map_err(into_jsrpc_error)
         })
@@ -205,11 +230,12 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceT for EthNamespa
         &self,
         req: CallRequest,
         block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
     ) -> BoxFuture<Result<U256>> {
         let self_ = self.clone();
         Box::pin(async move {
             self_
-                .estimate_gas_impl(req, block)
+                .estimate_gas_impl(req, block, state_override)
                 .await
                 .map_err(into_jsrpc_error)
         })
@@ -220,6 +246,21 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceT for EthNamespa
         Box::pin(async move { self_.gas_price_impl().map_err(into_jsrpc_error) })
     }
 
+    fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f32>>,
+    ) -> BoxFuture<Result<FeeHistory>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .fee_history_impl(block_count, newest_block, reward_percentiles)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
     fn new_filter(&self, filter: Filter) -> BoxFuture<Result<U256>> {
         let self_ = self.clone();
         Box::pin(async move {
@@ -434,6 +475,19 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceT for EthNamespa
         })
     }
 
+    fn get_block_receipts(
+        &self,
+        block: BlockId,
+    ) -> BoxFuture<Result<Option<Vec<TransactionReceipt>>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_block_receipts_impl(block)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
     fn protocol_version(&self) -> BoxFuture<Result<String>> {
         let self_ = self.clone();
         Box::pin(async move { Ok(self_.protocol_version()) })
@@ -8,7 +8,10 @@ use jsonrpc_derive::rpc;
 
 // Workspace uses
 use zksync_types::{
-    api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails},
+    api::{
+        BlockIdVariant, BridgeAddresses, L2ToL1LogProof, Proof, SimulatedTransaction,
+        StateOverride, TransactionDetails,
+    },
     explorer_api::{BlockDetails, L1BatchDetails},
     fee::Fee,
     transaction_request::CallRequest,
@@ -104,6 +107,22 @@ pub trait ZksNamespaceT {
 
     #[rpc(name = "zks_getL1GasPrice")]
     fn get_l1_gas_price(&self) -> BoxFuture<Result<U64>>;
+
+    #[rpc(name = "zks_getProof")]
+    fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> BoxFuture<Result<Option<Proof>>>;
+
+    #[rpc(name = "zks_simulateBundle")]
+    fn simulate_bundle(
+        &self,
+        reqs: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Result<Vec<SimulatedTransaction>>>;
 }
 
 impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespace<G> {
@@ -295,4 +314,34 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         let self_ = self.clone();
         Box::pin(async move { Ok(self_.get_l1_gas_price_impl()) })
     }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> BoxFuture<Result<Option<Proof>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_proof_impl(address, keys, l1_batch_number)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn simulate_bundle(
+        &self,
+        reqs: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Result<Vec<SimulatedTransaction>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .simulate_bundle_impl(reqs, block.map(Into::into), state_override)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
 }
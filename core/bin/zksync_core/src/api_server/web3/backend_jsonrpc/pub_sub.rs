@@ -1,13 +1,12 @@
-use std::sync::Arc;
-
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::typed;
-use jsonrpc_pubsub::{Session, SubscriptionId};
+use jsonrpc_pubsub::SubscriptionId;
 
 use zksync_web3_decl::types::PubSubResult;
 
 use super::super::namespaces::EthSubscribe;
+use super::middleware::RpcMetadata;
 
 #[rpc]
 pub trait Web3PubSub {
@@ -32,10 +31,30 @@ pub trait Web3PubSub {
         meta: Option<Self::Metadata>,
         subscription: SubscriptionId,
     ) -> BoxFuture<Result<bool>>;
+
+    #[pubsub(subscription = "zks_subscription", subscribe, name = "zks_subscribe")]
+    fn zks_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: typed::Subscriber<PubSubResult>,
+        sub_type: String,
+        params: Option<serde_json::Value>,
+    );
+
+    #[pubsub(
+        subscription = "zks_subscription",
+        unsubscribe,
+        name = "zks_unsubscribe"
+    )]
+    fn zks_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> BoxFuture<Result<bool>>;
 }
 
 impl Web3PubSub for EthSubscribe {
-    type Metadata = Arc<Session>;
+    type Metadata = RpcMetadata;
 
     fn subscribe(
         &self,
@@ -58,4 +77,26 @@ impl Web3PubSub for EthSubscribe {
         let self_ = self.clone();
         Box::pin(async move { self_.unsub(id).await })
     }
+
+    fn zks_subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: typed::Subscriber<PubSubResult>,
+        sub_type: String,
+        params: Option<serde_json::Value>,
+    ) {
+        let self_ = self.clone();
+        // Fire and forget is OK here.
+        self.runtime_handle
+            .spawn(async move { self_.zks_sub(subscriber, sub_type, params).await });
+    }
+
+    fn zks_unsubscribe(
+        &self,
+        _meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> BoxFuture<Result<bool>> {
+        let self_ = self.clone();
+        Box::pin(async move { self_.unsub(id).await })
+    }
 }
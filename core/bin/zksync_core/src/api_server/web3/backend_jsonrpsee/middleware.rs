@@ -0,0 +1,116 @@
+//! Tower middleware used by the `jsonrpsee` backend.
+
+use futures::future::BoxFuture;
+use hyper::{header, Body, Request, Response, StatusCode};
+use serde_json::Value;
+use tower::{Layer, Service};
+
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use zksync_web3_decl::error::Web3Error;
+
+use super::into_jsrpc_error;
+use crate::api_server::web3::rate_limiter::ApiRateLimiter;
+
+/// Layer applying per-client rate limiting to HTTP requests. Since the layer operates on raw HTTP requests,
+/// it parses the request body to find out the called methods; batch requests are charged for all their calls.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    rate_limiter: Option<Arc<ApiRateLimiter>>,
+}
+
+impl RateLimitLayer {
+    pub fn new(rate_limiter: Option<Arc<ApiRateLimiter>>) -> Self {
+        Self { rate_limiter }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            rate_limiter: self.rate_limiter.clone(),
+        }
+    }
+}
+
+/// Service produced by [`RateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    rate_limiter: Option<Arc<ApiRateLimiter>>,
+}
+
+impl<S> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The service that was polled for readiness is used for the call,
+        // and its clone is left in place for the subsequent calls.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let Some(rate_limiter) = self.rate_limiter.clone() else {
+            return Box::pin(inner.call(request));
+        };
+
+        Box::pin(async move {
+            let client_key = rate_limiter.http_client_key(request.headers());
+            let (parts, body) = request.into_parts();
+            let Ok(body) = hyper::body::to_bytes(body).await else {
+                return Ok(plain_response(StatusCode::BAD_REQUEST, Body::empty()));
+            };
+
+            // Invalid requests are rejected by the server itself, but they still consume the client's quota.
+            let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+            let (methods, id): (Vec<_>, _) = match &request {
+                Value::Array(calls) => (calls.iter().map(method_name).collect(), Value::Null),
+                call => (vec![method_name(call)], call["id"].clone()),
+            };
+            let check_result = rate_limiter.check(&client_key, methods, "http");
+            if let Err(err) = check_result {
+                return Ok(rate_limited_response(err, id));
+            }
+
+            inner.call(Request::from_parts(parts, body.into())).await
+        })
+    }
+}
+
+fn method_name(call: &Value) -> &str {
+    call["method"].as_str().unwrap_or_default()
+}
+
+fn plain_response(status: StatusCode, body: Body) -> Response<Body> {
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    response
+}
+
+fn rate_limited_response(err: Web3Error, id: Value) -> Response<Body> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": into_jsrpc_error(err),
+        "id": id,
+    });
+    let mut response = plain_response(StatusCode::TOO_MANY_REQUESTS, body.to_string().into());
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
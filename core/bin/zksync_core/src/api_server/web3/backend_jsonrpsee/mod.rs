@@ -6,6 +6,7 @@ use std::error::Error;
 use zksync_web3_decl::error::Web3Error;
 use zksync_web3_decl::jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned};
 
+pub mod middleware;
 pub mod namespaces;
 
 pub fn from_std_error(e: impl Error) -> ErrorObjectOwned {
@@ -15,7 +16,9 @@ pub fn from_std_error(e: impl Error) -> ErrorObjectOwned {
 pub fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
     ErrorObjectOwned::owned(
         match err {
-            Web3Error::InternalError | Web3Error::NotImplemented => ErrorCode::InternalError.code(),
+            Web3Error::InternalError
+            | Web3Error::NotImplemented
+            | Web3Error::TreeApiUnavailable => ErrorCode::InternalError.code(),
             Web3Error::NoBlock
             | Web3Error::NoSuchFunction
             | Web3Error::RLPError(_)
@@ -24,10 +27,15 @@ pub fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
+            | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::InvalidStateOverride(_)
+            | Web3Error::InvalidBundleSize(_)
+            | Web3Error::TooManyReplayedTransactions(_)
+            | Web3Error::TooManyTraceSteps(_) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
+            Web3Error::RateLimitExceeded => 6,
         },
         match err {
             Web3Error::SubmitTransactionError(ref message, _) => message.clone(),
@@ -1,7 +1,7 @@
 use zksync_types::{
     api::{
-        Block, BlockId, BlockIdVariant, BlockNumber, Log, Transaction, TransactionId,
-        TransactionReceipt, TransactionVariant,
+        Block, BlockId, BlockIdVariant, BlockNumber, FeeHistory, Log, StateOverride, Transaction,
+        TransactionId, TransactionReceipt, TransactionVariant,
     },
     transaction_request::CallRequest,
     web3::types::{Index, SyncState},
@@ -28,14 +28,24 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceServer for EthNa
         Ok(self.chain_id_impl())
     }
 
-    async fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> RpcResult<Bytes> {
-        self.call_impl(req, block.map(Into::into))
+    async fn call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Bytes> {
+        self.call_impl(req, block.map(Into::into), state_override)
             .await
             .map_err(into_jsrpc_error)
     }
 
-    async fn estimate_gas(&self, req: CallRequest, block: Option<BlockNumber>) -> RpcResult<U256> {
-        self.estimate_gas_impl(req, block)
+    async fn estimate_gas(
+        &self,
+        req: CallRequest,
+        block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<U256> {
+        self.estimate_gas_impl(req, block, state_override)
             .await
             .map_err(into_jsrpc_error)
     }
@@ -44,6 +54,17 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceServer for EthNa
         self.gas_price_impl().map_err(into_jsrpc_error)
     }
 
+    async fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f32>>,
+    ) -> RpcResult<FeeHistory> {
+        self.fee_history_impl(block_count, newest_block, reward_percentiles)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
         self.new_filter_impl(filter).await.map_err(into_jsrpc_error)
     }
@@ -183,6 +204,15 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceServer for EthNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_block_receipts(
+        &self,
+        block: BlockId,
+    ) -> RpcResult<Option<Vec<TransactionReceipt>>> {
+        self.get_block_receipts_impl(block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn protocol_version(&self) -> RpcResult<String> {
         Ok(self.protocol_version())
     }
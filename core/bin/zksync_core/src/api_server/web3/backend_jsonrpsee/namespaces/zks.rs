@@ -3,7 +3,10 @@ use bigdecimal::BigDecimal;
 use std::collections::HashMap;
 
 use zksync_types::{
-    api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails, U64},
+    api::{
+        BlockIdVariant, BridgeAddresses, L2ToL1LogProof, Proof, SimulatedTransaction,
+        StateOverride, TransactionDetails, U64,
+    },
     explorer_api::{BlockDetails, L1BatchDetails},
     fee::Fee,
     transaction_request::CallRequest,
@@ -143,4 +146,26 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
     async fn get_l1_gas_price(&self) -> RpcResult<U64> {
         Ok(self.get_l1_gas_price_impl())
     }
+
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<Proof>> {
+        self.get_proof_impl(address, keys, l1_batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn simulate_bundle(
+        &self,
+        reqs: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        self.simulate_bundle_impl(reqs, block.map(Into::into), state_override)
+            .await
+            .map_err(into_jsrpc_error)
+    }
 }
@@ -7,9 +7,10 @@ use std::time::Duration;
 // External uses
 use futures::channel::oneshot;
 use futures::FutureExt;
-use jsonrpc_core::IoHandler;
+use jsonrpc_core::MetaIoHandler;
 use jsonrpc_http_server::hyper;
 use jsonrpc_pubsub::PubSubHandler;
+use once_cell::sync::OnceCell;
 use tokio::sync::{watch, RwLock};
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 
@@ -29,6 +30,8 @@ use zksync_web3_decl::{
 
 use self::state::InternalApiConfig;
 use crate::l1_gas_price::L1GasPriceProvider;
+use crate::metadata_calculator::AsyncTreeReader;
+use crate::state_keeper::{MiniblockEventsSender, PendingState};
 use crate::sync_layer::SyncState;
 
 // Local uses
@@ -36,17 +39,23 @@ use super::tx_sender::TxSender;
 use crate::api_server::web3::api_health_check::ApiHealthCheck;
 use backend_jsonrpc::{
     error::internal_error,
+    middleware::{RateLimitMiddleware, RpcMetadata},
     namespaces::{
         debug::DebugNamespaceT, en::EnNamespaceT, eth::EthNamespaceT, net::NetNamespaceT,
         web3::Web3NamespaceT, zks::ZksNamespaceT,
     },
     pub_sub::Web3PubSub,
 };
+use backend_jsonrpsee::middleware::RateLimitLayer;
 use namespaces::{
     DebugNamespace, EnNamespace, EthNamespace, EthSubscribe, NetNamespace, Web3Namespace,
     ZksNamespace,
 };
-use pubsub_notifier::{notify_blocks, notify_logs, notify_txs};
+use pubsub_notifier::{
+    notify_blocks, notify_from_miniblock_events, notify_l1_batch_statuses, notify_logs,
+    notify_syncing, notify_tx_statuses, notify_txs, MiniblockSubscribers,
+};
+use rate_limiter::{ApiRateLimiter, ClientIdentification};
 use state::{Filters, RpcState};
 use zksync_health_check::CheckHealthStatus;
 
@@ -55,6 +64,7 @@ pub mod backend_jsonrpc;
 pub mod backend_jsonrpsee;
 pub mod namespaces;
 mod pubsub_notifier;
+pub mod rate_limiter;
 pub mod state;
 
 #[derive(Debug, Clone, Copy)]
@@ -79,11 +89,15 @@ pub struct ApiBuilder<G> {
     filters_limit: Option<usize>,
     subscriptions_limit: Option<usize>,
     sync_state: Option<SyncState>,
+    tree_reader: Arc<OnceCell<AsyncTreeReader>>,
     threads: Option<usize>,
     vm_concurrency_limit: Option<usize>,
+    rate_limiter: Option<Arc<ApiRateLimiter>>,
     polling_interval: Option<Duration>,
+    miniblock_events: Option<MiniblockEventsSender>,
+    pending_state: Option<PendingState>,
     accounts: HashMap<Address, PrivateKeySigner>,
-    debug_namespace_config: Option<(BaseSystemContractsHashes, u64, Option<usize>)>,
+    debug_namespace_config: Option<(BaseSystemContractsHashes, u64, Option<usize>, bool)>,
 }
 
 impl<G> ApiBuilder<G> {
@@ -93,12 +107,16 @@ impl<G> ApiBuilder<G> {
             transport: None,
             pool,
             sync_state: None,
+            tree_reader: Arc::default(),
             tx_sender: None,
             filters_limit: None,
             subscriptions_limit: None,
             threads: None,
             vm_concurrency_limit: None,
+            rate_limiter: None,
             polling_interval: None,
+            miniblock_events: None,
+            pending_state: None,
             debug_namespace_config: None,
             accounts: Default::default(),
             config,
@@ -111,12 +129,16 @@ impl<G> ApiBuilder<G> {
             transport: None,
             pool,
             sync_state: None,
+            tree_reader: Arc::default(),
             tx_sender: None,
             filters_limit: None,
             subscriptions_limit: None,
             threads: None,
             vm_concurrency_limit: None,
+            rate_limiter: None,
             polling_interval: None,
+            miniblock_events: None,
+            pending_state: None,
             debug_namespace_config: None,
             accounts: Default::default(),
             config,
@@ -153,6 +175,21 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    pub fn with_tree_reader(mut self, tree_reader: AsyncTreeReader) -> Self {
+        self.tree_reader = Arc::new(OnceCell::with_value(tree_reader));
+        self
+    }
+
+    /// Sets the tree reader that may be initialized after the server has started. Until then,
+    /// methods requiring the tree return an error.
+    pub fn with_deferred_tree_reader(
+        mut self,
+        tree_reader: Arc<OnceCell<AsyncTreeReader>>,
+    ) -> Self {
+        self.tree_reader = tree_reader;
+        self
+    }
+
     pub fn with_threads(mut self, threads: usize) -> Self {
         self.threads = Some(threads);
         self
@@ -163,21 +200,51 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    /// Makes pubsub notifiers listen to miniblocks sealed by the co-located state keeper instead of
+    /// polling Postgres. Only has effect for the WebSocket transport of the `jsonrpc` backend.
+    pub(crate) fn with_miniblock_events(mut self, miniblock_events: MiniblockEventsSender) -> Self {
+        self.miniblock_events = Some(miniblock_events);
+        self
+    }
+
+    /// Makes reads on the pending block observe the changes made by the co-located state keeper
+    /// that are not persisted in Postgres yet.
+    pub(crate) fn with_pending_state(mut self, pending_state: PendingState) -> Self {
+        self.pending_state = Some(pending_state);
+        self
+    }
+
     pub fn with_vm_concurrency_limit(mut self, vm_concurrency_limit: usize) -> Self {
         self.vm_concurrency_limit = Some(vm_concurrency_limit);
         self
     }
 
+    /// Enables per-client rate limiting: each client can spend at most `weight_per_sec` per second,
+    /// with the weights of the methods specified in `method_weights` (1 for methods not listed there).
+    /// Clients are identified according to `identification`.
+    pub fn with_rate_limit(
+        mut self,
+        weight_per_sec: u32,
+        method_weights: HashMap<String, u32>,
+        identification: ClientIdentification,
+    ) -> Self {
+        let rate_limiter = ApiRateLimiter::new(weight_per_sec, method_weights, identification);
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
+
     pub fn enable_debug_namespace(
         mut self,
         base_system_contract_hashes: BaseSystemContractsHashes,
         fair_l2_gas_price: u64,
         cache_misses_limit: Option<usize>,
+        replay_block_traces: bool,
     ) -> Self {
         self.debug_namespace_config = Some((
             base_system_contract_hashes,
             fair_l2_gas_price,
             cache_misses_limit,
+            replay_block_traces,
         ));
         self
     }
@@ -209,6 +276,8 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             connection_pool: self.pool.clone(),
             tx_sender: self.tx_sender.clone().expect("TxSender is not provided"),
             sync_state: self.sync_state.clone(),
+            tree_reader: self.tree_reader.clone(),
+            pending_state: self.pending_state.clone(),
             api_config: self.config.clone(),
             accounts: self.accounts.clone(),
             #[cfg(feature = "openzeppelin_tests")]
@@ -237,7 +306,9 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             .expect("Can't merge zks namespace");
         rpc.merge(en.into_rpc()).expect("Can't merge en namespace");
 
-        if let Some((hashes, fair_l2_gas_price, cache_misses_limit)) = self.debug_namespace_config {
+        if let Some((hashes, fair_l2_gas_price, cache_misses_limit, replay_block_traces)) =
+            self.debug_namespace_config
+        {
             rpc.merge(
                 DebugNamespace::new(
                     rpc_app.connection_pool,
@@ -246,6 +317,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                     cache_misses_limit,
                     rpc_app.tx_sender.0.vm_concurrency_limiter.clone(),
                     rpc_app.tx_sender.0.factory_deps_cache.clone(),
+                    replay_block_traces,
                 )
                 .await
                 .into_rpc(),
@@ -275,8 +347,26 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             }
             _ => {}
         }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            assert!(
+                !matches!(
+                    (&self.backend, &self.transport),
+                    (ApiBackend::Jsonrpsee, Some(ApiTransport::WebSocket(_)))
+                ),
+                "Rate limiting is not supported for WS transport with jsonrpsee backend, use jsonrpc instead"
+            );
+            let is_http = matches!(self.transport, Some(ApiTransport::Http(_)));
+            if is_http && !rate_limiter.identification().behind_trusted_proxy {
+                vlog::warn!(
+                    "API server is not configured to run behind a trusted proxy; \
+                     all HTTP clients without a known API key share the same rate limit"
+                );
+            }
+        }
 
-        match (self.backend, self.transport.take()) {
+        let rate_limiter = self.rate_limiter.clone();
+        let rate_limiter_stop_receiver = stop_receiver.clone();
+        let (mut tasks, api_health_check) = match (self.backend, self.transport.take()) {
             (ApiBackend::Jsonrpc, Some(ApiTransport::Http(addr))) => {
                 let (api_health_check, status_sender) = self.create_health_check();
                 (
@@ -315,7 +405,14 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                 )
             }
             (_, None) => panic!("ApiTransport is not specified"),
+        };
+
+        if let Some(rate_limiter) = rate_limiter {
+            tasks.push(tokio::spawn(
+                rate_limiter.prune_periodically(rate_limiter_stop_receiver),
+            ));
         }
+        (tasks, api_health_check)
     }
 
     fn create_health_check(&self) -> (ApiHealthCheck, watch::Sender<CheckHealthStatus>) {
@@ -333,13 +430,14 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
         let io_handler = {
             let zksync_network_id = self.config.l2_chain_id;
             let rpc_state = self.build_rpc_state();
-            let mut io = IoHandler::new();
+            let rate_limit_middleware = RateLimitMiddleware::new(self.rate_limiter.clone(), "http");
+            let mut io = MetaIoHandler::with_middleware(rate_limit_middleware);
             io.extend_with(EthNamespace::new(rpc_state.clone()).to_delegate());
             io.extend_with(ZksNamespace::new(rpc_state.clone()).to_delegate());
             io.extend_with(EnNamespace::new(rpc_state.clone()).to_delegate());
             io.extend_with(Web3Namespace.to_delegate());
             io.extend_with(NetNamespace::new(zksync_network_id).to_delegate());
-            if let Some((hashes, fair_l2_gas_price, cache_misses_limit)) =
+            if let Some((hashes, fair_l2_gas_price, cache_misses_limit, replay_block_traces)) =
                 self.debug_namespace_config
             {
                 io.extend_with(
@@ -350,6 +448,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                         cache_misses_limit,
                         rpc_state.tx_sender.0.vm_concurrency_limiter.clone(),
                         rpc_state.tx_sender.0.factory_deps_cache.clone(),
+                        replay_block_traces,
                     )
                     .await
                     .to_delegate(),
@@ -367,11 +466,20 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                 .build()
                 .unwrap();
 
-            let server = jsonrpc_http_server::ServerBuilder::new(io_handler)
-                .threads(1)
-                .event_loop_executor(runtime.handle().clone())
-                .start_http(&addr)
-                .unwrap();
+            let rate_limiter = self.rate_limiter.clone();
+            let server = jsonrpc_http_server::ServerBuilder::with_meta_extractor(
+                io_handler,
+                move |request: &hyper::Request<hyper::Body>| RpcMetadata {
+                    client_key: rate_limiter
+                        .as_ref()
+                        .map(|limiter| limiter.http_client_key(request.headers())),
+                    session: None,
+                },
+            )
+            .threads(1)
+            .event_loop_executor(runtime.handle().clone())
+            .start_http(&addr)
+            .unwrap();
 
             let close_handler = server.close_handle();
             std::thread::spawn(move || {
@@ -425,7 +533,8 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             // Prepare middleware.
             let middleware = tower::ServiceBuilder::new()
                 .layer(in_flight_requests_layer)
-                .layer(cors);
+                .layer(cors)
+                .layer(RateLimitLayer::new(self.rate_limiter.clone()));
 
             runtime.block_on(async move {
                 let server = ServerBuilder::default()
@@ -470,7 +579,6 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
         vlog::warn!(
             "`eth_subscribe` is not implemented for jsonrpsee backend, use jsonrpc instead"
         );
-
         let rpc = self.build_rpc_module().await;
 
         // Start the server in a separate tokio runtime from a dedicated thread.
@@ -523,35 +631,73 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             .build()
             .unwrap();
 
-        let pub_sub = EthSubscribe::new(jsonrpc_runtime.handle().clone());
+        let pub_sub = EthSubscribe::new(jsonrpc_runtime.handle().clone(), self.sync_state.clone());
         let polling_interval = self.polling_interval.expect("Polling interval is not set");
 
-        let mut notify_handles = vec![
-            tokio::spawn(notify_blocks(
-                pub_sub.active_block_subs.clone(),
+        let mut notify_handles = if let Some(miniblock_events) = &self.miniblock_events {
+            vlog::info!(
+                "Block and log pubsub notifications are driven by miniblocks sealed by the state keeper"
+            );
+            let subscribers = MiniblockSubscribers {
+                blocks: pub_sub.active_block_subs.clone(),
+                logs: pub_sub.active_log_subs.clone(),
+            };
+            vec![tokio::spawn(notify_from_miniblock_events(
+                subscribers,
+                miniblock_events.subscribe(),
                 self.pool.clone(),
-                polling_interval,
-                stop_receiver.clone(),
-            )),
-            tokio::spawn(notify_txs(
-                pub_sub.active_tx_subs.clone(),
-                self.pool.clone(),
-                polling_interval,
                 stop_receiver.clone(),
-            )),
-            tokio::spawn(notify_logs(
-                pub_sub.active_log_subs.clone(),
-                self.pool.clone(),
+            ))]
+        } else {
+            vec![
+                tokio::spawn(notify_blocks(
+                    pub_sub.active_block_subs.clone(),
+                    self.pool.clone(),
+                    polling_interval,
+                    stop_receiver.clone(),
+                )),
+                tokio::spawn(notify_logs(
+                    pub_sub.active_log_subs.clone(),
+                    self.pool.clone(),
+                    polling_interval,
+                    stop_receiver.clone(),
+                )),
+            ]
+        };
+        // Pending transactions are reported once they are received, so they are always polled from Postgres.
+        notify_handles.push(tokio::spawn(notify_txs(
+            pub_sub.active_tx_subs.clone(),
+            self.pool.clone(),
+            polling_interval,
+            stop_receiver.clone(),
+        )));
+        notify_handles.push(tokio::spawn(notify_l1_batch_statuses(
+            pub_sub.active_l1_batch_subs.clone(),
+            self.pool.clone(),
+            polling_interval,
+            stop_receiver.clone(),
+        )));
+        notify_handles.push(tokio::spawn(notify_tx_statuses(
+            pub_sub.active_tx_status_subs.clone(),
+            self.pool.clone(),
+            polling_interval,
+            stop_receiver.clone(),
+        )));
+        if let Some(sync_state) = self.sync_state.clone() {
+            notify_handles.push(tokio::spawn(notify_syncing(
+                pub_sub.active_sync_subs.clone(),
+                sync_state,
                 polling_interval,
                 stop_receiver.clone(),
-            )),
-        ];
+            )));
+        }
 
         let (sender, recv) = oneshot::channel::<()>();
         let io = {
             let zksync_network_id = self.config.l2_chain_id;
             let rpc_state = self.build_rpc_state();
-            let mut io = PubSubHandler::default();
+            let rate_limit_middleware = RateLimitMiddleware::new(self.rate_limiter.clone(), "ws");
+            let mut io = PubSubHandler::new(MetaIoHandler::with_middleware(rate_limit_middleware));
             io.extend_with(pub_sub.to_delegate());
             io.extend_with(EthNamespace::new(rpc_state.clone()).to_delegate());
             io.extend_with(ZksNamespace::new(rpc_state.clone()).to_delegate());
@@ -561,11 +707,15 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             io
         };
 
+        let is_rate_limited = self.rate_limiter.is_some();
         std::thread::spawn(move || {
             let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
                 io,
-                |context: &jsonrpc_ws_server::RequestContext| {
-                    Arc::new(jsonrpc_pubsub::Session::new(context.sender()))
+                move |context: &jsonrpc_ws_server::RequestContext| RpcMetadata {
+                    // The metadata is extracted once per connection, so each connection gets its own quota.
+                    client_key: is_rate_limited
+                        .then(|| ApiRateLimiter::ws_client_key(context.session_id)),
+                    session: Some(Arc::new(jsonrpc_pubsub::Session::new(context.sender()))),
                 },
             )
             .event_loop_executor(jsonrpc_runtime.handle().clone())
@@ -1,24 +1,47 @@
-use std::{sync::Arc, time::Instant};
+use std::{ops::Range, slice, sync::Arc, time::Instant};
 
+use vm::{oracles::tracer::StructLoggerParams, VmExecutionResult};
 use zksync_contracts::{
     BaseSystemContracts, BaseSystemContractsHashes, PLAYGROUND_BLOCK_BOOTLOADER_CODE,
 };
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_state::FactoryDepsCache;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, CallTracerConfig, DebugCall, DebugTrace, PrestateTrace,
+        ResultDebugCall, StructLoggerConfig, StructLogsTrace, SupportedTracers, TracerConfig,
+    },
+    block::MiniblockHeader,
     transaction_request::{l2_tx_from_call_req, CallRequest},
     vm_trace::{Call, VmTrace},
-    AccountTreeId, H256, USED_BOOTLOADER_MEMORY_BYTES,
+    AccountTreeId, MiniblockNumber, Transaction, H256, U256, USED_BOOTLOADER_MEMORY_BYTES,
 };
 use zksync_web3_decl::error::Web3Error;
 
 use crate::api_server::{
-    execution_sandbox::{execute_tx_eth_call, BlockArgs, TxSharedArgs, VmConcurrencyLimiter},
+    execution_sandbox::{
+        execute_tx_eth_call, replay_txs, BlockArgs, SandboxTracer, TxSharedArgs,
+        VmConcurrencyLimiter,
+    },
     tx_sender::SubmitTxError,
     web3::{backend_jsonrpc::error::internal_error, resolve_block},
 };
 
+/// Data necessary to re-execute transactions in a sealed miniblock.
+#[derive(Debug)]
+struct MiniblockReplay {
+    header: MiniblockHeader,
+    txs: Vec<Transaction>,
+    base_system_contracts: BaseSystemContracts,
+}
+
+/// Maximum total number of steps recorded by the struct logger for all transactions traced in a single request,
+/// so that the request cannot exhaust the server memory. Requests may set a lower limit for each trace.
+const MAX_STRUCT_LOGGER_STEPS: usize = 100_000;
+/// Maximum number of transactions re-executed to serve a single request. Since transactions
+/// are replayed from the start of the miniblock, this limits both traced and preceding transactions.
+const MAX_REPLAYED_TXS: usize = 1_000;
+
 #[derive(Debug, Clone)]
 pub struct DebugNamespace {
     connection_pool: ConnectionPool,
@@ -27,6 +50,8 @@ pub struct DebugNamespace {
     vm_execution_cache_misses_limit: Option<usize>,
     vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     factory_deps_cache: FactoryDepsCache,
+    /// Whether to re-execute miniblocks with no stored call traces when tracing them with the call tracer.
+    replay_block_traces: bool,
 }
 
 impl DebugNamespace {
@@ -37,6 +62,7 @@ impl DebugNamespace {
         vm_execution_cache_misses_limit: Option<usize>,
         vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
         factory_deps_cache: FactoryDepsCache,
+        replay_block_traces: bool,
     ) -> Self {
         let mut storage = connection_pool.access_storage_tagged("api").await;
 
@@ -58,6 +84,7 @@ impl DebugNamespace {
             vm_execution_cache_misses_limit,
             vm_concurrency_limiter,
             factory_deps_cache,
+            replay_block_traces,
         }
     }
 
@@ -69,25 +96,45 @@ impl DebugNamespace {
     ) -> Result<Vec<ResultDebugCall>, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_block";
 
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+        let (tracer, tracer_config, struct_logger_config) = Self::split_options(options);
         let mut connection = self.connection_pool.access_storage_tagged("api").await;
         let block_number = resolve_block(&mut connection, block, METHOD_NAME).await?;
-        let call_trace = connection
-            .blocks_web3_dal()
-            .get_trace_for_miniblock(block_number)
-            .await;
+        if Self::is_stored_trace_supported(tracer) {
+            let call_traces = connection
+                .blocks_web3_dal()
+                .get_trace_for_miniblock(block_number)
+                .await;
+            // Traces are not stored if the node doesn't save call traces; in this case,
+            // we fall back to re-executing the miniblock below if it's enabled.
+            if !call_traces.is_empty() || !self.replay_block_traces {
+                return Ok(call_traces
+                    .into_iter()
+                    .map(|call_trace| ResultDebugCall {
+                        result: Self::stored_trace(call_trace, tracer, &tracer_config),
+                    })
+                    .collect());
+            }
+        }
 
-        Ok(call_trace
+        // The tracer output cannot be built from stored traces, so we need to re-execute
+        // all transactions in the miniblock.
+        let replay = Self::load_replay(&mut connection, block_number, METHOD_NAME).await?;
+        drop(connection);
+        let Some(replay) = replay else {
+            return Ok(vec![]);
+        };
+        let traces = self
+            .replay_traces(
+                &replay,
+                0..replay.txs.len(),
+                tracer,
+                &tracer_config,
+                &struct_logger_config,
+            )
+            .await?;
+        Ok(traces
             .into_iter()
-            .map(|call_trace| {
-                let mut result: DebugCall = call_trace.into();
-                if only_top_call {
-                    result.calls = vec![];
-                }
-                ResultDebugCall { result }
-            })
+            .map(|result| ResultDebugCall { result })
             .collect())
     }
 
@@ -96,24 +143,215 @@ impl DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> Option<DebugCall> {
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
-        let call_trace = self
-            .connection_pool
-            .access_storage_tagged("api")
+    ) -> Result<Option<DebugTrace>, Web3Error> {
+        const METHOD_NAME: &str = "debug_trace_transaction";
+
+        let start = Instant::now();
+        let (tracer, tracer_config, struct_logger_config) = Self::split_options(options);
+        let mut connection = self.connection_pool.access_storage_tagged("api").await;
+        if Self::is_stored_trace_supported(tracer) {
+            let call_trace = connection.transactions_dal().get_call_trace(tx_hash).await;
+            if let Some(call_trace) = call_trace {
+                return Ok(Some(Self::stored_trace(call_trace, tracer, &tracer_config)));
+            }
+        }
+
+        // The trace is not stored, so we need to re-execute the transaction.
+        let miniblock_number = connection
+            .transactions_web3_dal()
+            .get_miniblock_number_of_transaction(tx_hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(miniblock_number) = miniblock_number else {
+            return Ok(None);
+        };
+        let replay = Self::load_replay(&mut connection, miniblock_number, METHOD_NAME).await?;
+        drop(connection);
+        let Some(replay) = replay else {
+            return Ok(None);
+        };
+        let Some(tx_index) = replay.txs.iter().position(|tx| tx.hash() == tx_hash) else {
+            return Ok(None);
+        };
+
+        let mut traces = self
+            .replay_traces(
+                &replay,
+                tx_index..tx_index + 1,
+                tracer,
+                &tracer_config,
+                &struct_logger_config,
+            )
+            .await?;
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(traces.pop())
+    }
+
+    /// Loads the data necessary to re-execute transactions in the specified miniblock. The transactions
+    /// are re-executed on top of the state after the parent miniblock (see [`BlockArgs::for_replay()`]).
+    async fn load_replay(
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        method_name: &'static str,
+    ) -> Result<Option<MiniblockReplay>, Web3Error> {
+        let Some(header) = connection
+            .blocks_dal()
+            .get_miniblock_header(miniblock_number)
             .await
-            .transactions_dal()
-            .get_call_trace(tx_hash)
+        else {
+            return Ok(None);
+        };
+        let txs = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(miniblock_number)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+
+        // Unlike calls, replayed transactions are executed by the bootloader they were originally
+        // executed with, so that the traces match the sealed results.
+        let base_system_contracts = connection
+            .storage_dal()
+            .get_base_system_contracts(
+                header.base_system_contracts_hashes.bootloader,
+                header.base_system_contracts_hashes.default_aa,
+            )
             .await;
-        call_trace.map(|call_trace| {
-            let mut result: DebugCall = call_trace.into();
-            if only_top_call {
-                result.calls = vec![];
+        Ok(Some(MiniblockReplay {
+            header,
+            txs,
+            base_system_contracts,
+        }))
+    }
+
+    /// Re-executes transactions with the specified indices in the miniblock and returns their traces.
+    /// Each transaction is executed on top of the state after the parent miniblock and all preceding transactions
+    /// in the miniblock; the miniblock is executed once, up to the last traced transaction. Replay doesn't reproduce
+    /// the execution of the entire L1 batch: the VM starts a new batch on top of the parent miniblock state, with
+    /// the timestamp of the replayed miniblock.
+    async fn replay_traces(
+        &self,
+        replay: &MiniblockReplay,
+        tx_indices: Range<usize>,
+        tracer: SupportedTracers,
+        tracer_config: &CallTracerConfig,
+        struct_logger_config: &StructLoggerConfig,
+    ) -> Result<Vec<DebugTrace>, Web3Error> {
+        if tx_indices.end > MAX_REPLAYED_TXS {
+            return Err(Web3Error::TooManyReplayedTransactions(MAX_REPLAYED_TXS));
+        }
+
+        let shared_args = TxSharedArgs {
+            operator_account: AccountTreeId::default(),
+            l1_gas_price: replay.header.l1_gas_price,
+            fair_l2_gas_price: replay.header.l2_fair_gas_price,
+            base_system_contracts: replay.base_system_contracts.clone(),
+            factory_deps_cache: self.factory_deps_cache.clone(),
+            pending_state: None,
+        };
+
+        let sandbox_tracer = Self::sandbox_tracer(tracer, tracer_config, struct_logger_config);
+
+        let vm_permit = self.vm_concurrency_limiter.acquire().await;
+        let results = replay_txs(
+            &vm_permit,
+            shared_args,
+            self.connection_pool.clone(),
+            replay.txs[..tx_indices.end].to_vec(),
+            tx_indices.clone(),
+            BlockArgs::for_replay(&replay.header),
+            replay.header.base_fee_per_gas,
+            sandbox_tracer,
+        )
+        .await
+        .map_err(|err| {
+            let submit_tx_error = SubmitTxError::from(err);
+            Web3Error::SubmitTransactionError(submit_tx_error.to_string(), submit_tx_error.data())
+        })?;
+        drop(vm_permit); // Unblock other VMs to enter.
+        Self::check_step_budget(&results)?;
+
+        let traces = results
+            .into_iter()
+            .zip(&replay.txs[tx_indices])
+            .map(|(result, tx)| {
+                Self::executed_trace(
+                    result,
+                    tracer,
+                    tracer_config,
+                    struct_logger_config,
+                    tx.execute.value,
+                    tx.execute.calldata.clone(),
+                )
+            })
+            .collect();
+        Ok(traces)
+    }
+
+    fn split_options(
+        options: Option<TracerConfig>,
+    ) -> (SupportedTracers, CallTracerConfig, StructLoggerConfig) {
+        options.map_or(
+            (
+                SupportedTracers::CallTracer,
+                CallTracerConfig::default(),
+                StructLoggerConfig::default(),
+            ),
+            |options| (options.tracer, options.tracer_config, options.struct_logger),
+        )
+    }
+
+    fn struct_logger_params(config: &StructLoggerConfig) -> StructLoggerParams {
+        let step_limit = match config.limit {
+            0 => usize::MAX,
+            limit => limit,
+        };
+        StructLoggerParams {
+            capture_registers: !config.disable_stack,
+            capture_memory: !config.disable_memory,
+            capture_storage: !config.disable_storage,
+            step_limit,
+        }
+    }
+
+    /// Checks that the struct logger hasn't used up the step budget of the request. Once the budget is used up,
+    /// steps are no longer recorded, so the traces would be incomplete.
+    fn check_step_budget(results: &[VmExecutionResult]) -> Result<(), Web3Error> {
+        let recorded_steps: usize = results
+            .iter()
+            .map(|result| match &result.trace {
+                VmTrace::ExecutionTrace(trace) => trace.steps.len(),
+                _ => 0,
+            })
+            .sum();
+        if recorded_steps >= MAX_STRUCT_LOGGER_STEPS {
+            return Err(Web3Error::TooManyTraceSteps(MAX_STRUCT_LOGGER_STEPS));
+        }
+        Ok(())
+    }
+
+    /// Checks whether the tracer output can be built from the call traces stored in Postgres.
+    fn is_stored_trace_supported(tracer: SupportedTracers) -> bool {
+        matches!(
+            tracer,
+            SupportedTracers::CallTracer | SupportedTracers::FourByteTracer
+        )
+    }
+
+    fn stored_trace(
+        call_trace: Call,
+        tracer: SupportedTracers,
+        tracer_config: &CallTracerConfig,
+    ) -> DebugTrace {
+        let mut call: DebugCall = call_trace.into();
+        match tracer {
+            SupportedTracers::FourByteTracer => DebugTrace::FourByte(call.four_byte_trace()),
+            _ => {
+                if tracer_config.only_top_call {
+                    call.calls = vec![];
+                }
+                DebugTrace::Call(call)
             }
-            result
-        })
+        }
     }
 
     #[tracing::instrument(skip(self, request, block))]
@@ -122,11 +360,10 @@ impl DebugNamespace {
         request: CallRequest,
         block: Option<BlockId>,
         options: Option<TracerConfig>,
-    ) -> Result<DebugCall, Web3Error> {
+    ) -> Result<DebugTrace, Web3Error> {
         let start = Instant::now();
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+        let (tracer, tracer_config, struct_logger_config) = Self::split_options(options);
+        let sandbox_tracer = Self::sandbox_tracer(tracer, &tracer_config, &struct_logger_config);
 
         let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let mut connection = self.connection_pool.access_storage_tagged("api").await;
@@ -140,7 +377,6 @@ impl DebugNamespace {
 
         let shared_args = self.shared_args();
         let vm_permit = self.vm_concurrency_limiter.acquire().await;
-        // We don't need properly trace if we only need top call
         let result = execute_tx_eth_call(
             &vm_permit,
             shared_args,
@@ -148,7 +384,8 @@ impl DebugNamespace {
             tx.clone(),
             block_args,
             self.vm_execution_cache_misses_limit,
-            !only_top_call,
+            None,
+            sandbox_tracer,
         )
         .await
         .map_err(|err| {
@@ -156,34 +393,96 @@ impl DebugNamespace {
             Web3Error::SubmitTransactionError(submit_tx_error.to_string(), submit_tx_error.data())
         })?;
         drop(vm_permit); // Unblock other VMs to enter.
+        Self::check_step_budget(slice::from_ref(&result))?;
 
-        let (output, revert_reason) = match result.revert_reason {
-            Some(result) => (vec![], Some(result.revert_reason.to_string())),
-            None => (
-                result
-                    .return_data
-                    .into_iter()
-                    .flat_map(<[u8; 32]>::from)
-                    .collect(),
-                None,
-            ),
-        };
-        let trace = match result.trace {
-            VmTrace::CallTrace(trace) => trace,
-            VmTrace::ExecutionTrace(_) => vec![],
-        };
-        let call = Call::new_high_level(
-            u32::MAX,
-            result.gas_used,
+        let debug_trace = Self::executed_trace(
+            result,
+            tracer,
+            &tracer_config,
+            &struct_logger_config,
             tx.execute.value,
             tx.execute.calldata,
-            output,
-            revert_reason,
-            trace,
         );
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => "debug_trace_call");
-        Ok(call.into())
+        Ok(debug_trace)
+    }
+
+    fn sandbox_tracer(
+        tracer: SupportedTracers,
+        tracer_config: &CallTracerConfig,
+        struct_logger_config: &StructLoggerConfig,
+    ) -> SandboxTracer {
+        match tracer {
+            // We don't need properly trace if we only need top call
+            SupportedTracers::CallTracer if tracer_config.only_top_call => SandboxTracer::None,
+            SupportedTracers::CallTracer | SupportedTracers::FourByteTracer => SandboxTracer::Call,
+            SupportedTracers::PrestateTracer => SandboxTracer::Prestate,
+            SupportedTracers::StructLogger => SandboxTracer::StructLogger {
+                params: Self::struct_logger_params(struct_logger_config),
+                step_budget: MAX_STRUCT_LOGGER_STEPS,
+            },
+        }
+    }
+
+    /// Builds the tracer output from the result of the sandboxed execution.
+    fn executed_trace(
+        result: VmExecutionResult,
+        tracer: SupportedTracers,
+        tracer_config: &CallTracerConfig,
+        struct_logger_config: &StructLoggerConfig,
+        value: U256,
+        calldata: Vec<u8>,
+    ) -> DebugTrace {
+        if let VmTrace::PrestateTrace(trace) = &result.trace {
+            DebugTrace::Prestate(PrestateTrace::new(trace, tracer_config.diff_mode))
+        } else if tracer == SupportedTracers::StructLogger {
+            let VmTrace::ExecutionTrace(trace) = &result.trace else {
+                unreachable!("struct logger always produces an execution trace");
+            };
+            let return_value: Vec<u8> = result
+                .return_data
+                .iter()
+                .flat_map(|&word| <[u8; 32]>::from(word))
+                .collect();
+            DebugTrace::StructLogs(StructLogsTrace::new(
+                trace,
+                struct_logger_config,
+                result.gas_used.into(),
+                result.revert_reason.is_some(),
+                return_value.into(),
+            ))
+        } else {
+            let (output, revert_reason) = match result.revert_reason {
+                Some(result) => (vec![], Some(result.revert_reason.to_string())),
+                None => (
+                    result
+                        .return_data
+                        .into_iter()
+                        .flat_map(<[u8; 32]>::from)
+                        .collect(),
+                    None,
+                ),
+            };
+            let trace = match result.trace {
+                VmTrace::CallTrace(trace) => trace,
+                VmTrace::ExecutionTrace(_) | VmTrace::PrestateTrace(_) => vec![],
+            };
+            let call: DebugCall = Call::new_high_level(
+                u32::MAX,
+                result.gas_used,
+                value,
+                calldata,
+                output,
+                revert_reason,
+                trace,
+            )
+            .into();
+            match tracer {
+                SupportedTracers::FourByteTracer => DebugTrace::FourByte(call.four_byte_trace()),
+                _ => DebugTrace::Call(call),
+            }
+        }
     }
 
     fn shared_args(&self) -> TxSharedArgs {
@@ -193,6 +492,85 @@ impl DebugNamespace {
             fair_l2_gas_price: self.fair_l2_gas_price,
             base_system_contracts: self.base_system_contracts.clone(),
             factory_deps_cache: self.factory_deps_cache.clone(),
+            pending_state: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use db_test_macro::db_test;
+
+    use super::*;
+    use crate::api_server::test_utils::{create_l2_tx, prepare_storage, seal_miniblock};
+    use zksync_types::SYSTEM_CONTEXT_MINIMAL_BASE_FEE;
+
+    async fn create_namespace(pool: ConnectionPool, replay_block_traces: bool) -> DebugNamespace {
+        DebugNamespace::new(
+            pool,
+            BaseSystemContracts::load_from_disk().hashes(),
+            1,
+            None,
+            Arc::new(VmConcurrencyLimiter::new(None)),
+            FactoryDepsCache::new("test_factory_deps_cache", 128),
+            replay_block_traces,
+        )
+        .await
+    }
+
+    #[db_test]
+    async fn tracing_sealed_transaction_via_replay(pool: ConnectionPool) {
+        let base_system_contracts = BaseSystemContracts::load_from_disk();
+        let private_key = H256::random();
+        prepare_storage(&pool, &base_system_contracts, &private_key).await;
+        let tx = create_l2_tx(&private_key, SYSTEM_CONTEXT_MINIMAL_BASE_FEE);
+        let tx_hash = tx.hash();
+        seal_miniblock(&pool, MiniblockNumber(1), vec![tx], &base_system_contracts).await;
+        let namespace = create_namespace(pool.clone(), true).await;
+
+        let trace = namespace
+            .debug_trace_transaction_impl(tx_hash, None)
+            .await
+            .unwrap()
+            .expect("no trace for sealed transaction");
+        let DebugTrace::Call(call) = trace else {
+            panic!("unexpected trace: {trace:?}");
+        };
+        assert_eq!(call.revert_reason, None);
+        assert!(!call.calls.is_empty());
+
+        let options = TracerConfig {
+            tracer: SupportedTracers::StructLogger,
+            tracer_config: CallTracerConfig::default(),
+            struct_logger: StructLoggerConfig::default(),
+        };
+        let trace = namespace
+            .debug_trace_transaction_impl(tx_hash, Some(options))
+            .await
+            .unwrap()
+            .expect("no trace for sealed transaction");
+        let DebugTrace::StructLogs(trace) = trace else {
+            panic!("unexpected trace: {trace:?}");
+        };
+        assert!(!trace.failed);
+        assert!(!trace.struct_logs.is_empty());
+
+        // Since there are no stored call traces, the miniblock is re-executed.
+        let block_id = BlockId::Number(BlockNumber::Number(1.into()));
+        let block_traces = namespace
+            .debug_trace_block_impl(block_id, None)
+            .await
+            .unwrap();
+        assert_eq!(block_traces.len(), 1);
+        assert_matches!(block_traces[0].result, DebugTrace::Call(_));
+
+        // If replaying block traces is disabled, no traces are returned.
+        let namespace = create_namespace(pool, false).await;
+        let block_traces = namespace
+            .debug_trace_block_impl(block_id, None)
+            .await
+            .unwrap();
+        assert!(block_traces.is_empty(), "{block_traces:?}");
+    }
+}
@@ -1,20 +1,22 @@
 use itertools::Itertools;
 
+use std::collections::HashMap;
 use std::time::Instant;
 
+use zksync_dal::blocks_web3_dal::BLOCK_GAS_LIMIT;
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        BlockId, BlockNumber, FeeHistory, GetLogsFilter, StateOverride, Transaction, TransactionId,
+        TransactionReceipt, TransactionVariant,
     },
     l2::{L2Tx, TransactionType},
     transaction_request::{l2_tx_from_call_req, CallRequest},
-    utils::decompose_full_nonce,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
     web3::types::{SyncInfo, SyncState},
     AccountTreeId, Bytes, MiniblockNumber, StorageKey, H256, L2_ETH_TOKEN_ADDRESS,
     MAX_GAS_PER_PUBDATA_BYTE, U256,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{bytecode::validate_bytecode, h256_to_u256, u256_to_h256};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Block, Filter, FilterChanges, Log, TypedFilter, U64},
@@ -40,6 +42,8 @@ use crate::{
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
 pub const PROTOCOL_VERSION: &str = "zks/1";
+/// Maximum number of blocks that can be requested in a single `eth_feeHistory` call.
+pub const FEE_HISTORY_MAX_BLOCK_COUNT: u64 = 1024;
 
 #[derive(Debug)]
 pub struct EthNamespace<G> {
@@ -79,13 +83,17 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         block_number
     }
 
-    #[tracing::instrument(skip(self, request, block))]
+    #[tracing::instrument(skip(self, request, block, state_override))]
     pub async fn call_impl(
         &self,
         request: CallRequest,
         block: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> Result<Bytes, Web3Error> {
         let start = Instant::now();
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override)?;
+        }
 
         let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let mut connection = self
@@ -114,7 +122,11 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             )?)?
             .try_into()?;
 
-        let call_result = self.state.tx_sender.eth_call(block_args, tx).await;
+        let call_result = self
+            .state
+            .tx_sender
+            .eth_call(block_args, tx, state_override)
+            .await;
         let mut res_bytes = call_result
             .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
 
@@ -129,15 +141,29 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         Ok(res_bytes.into())
     }
 
-    #[tracing::instrument(skip(self, request, _block))]
+    #[tracing::instrument(skip(self, request, _block, state_override))]
     pub async fn estimate_gas_impl(
         &self,
         request: CallRequest,
         _block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
     ) -> Result<U256, Web3Error> {
         let start = Instant::now();
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override)?;
+        }
         let mut request_with_gas_per_pubdata_overridden = request;
 
+        if request_with_gas_per_pubdata_overridden.nonce.is_none() {
+            // The overridden nonce of the initiator must be used by the estimated transaction;
+            // otherwise, its validation would fail.
+            let from = request_with_gas_per_pubdata_overridden
+                .from
+                .unwrap_or_default();
+            request_with_gas_per_pubdata_overridden.nonce = state_override
+                .as_ref()
+                .and_then(|state_override| state_override.get(&from)?.nonce);
+        }
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
             .await?;
@@ -186,7 +212,12 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(
+                tx.into(),
+                scale_factor,
+                acceptable_overestimation,
+                state_override,
+            )
             .await
             .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
 
@@ -204,6 +235,108 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         Ok(price.into())
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn fee_history_impl(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f32>>,
+    ) -> Result<FeeHistory, Web3Error> {
+        const METHOD_NAME: &str = "fee_history";
+
+        let start = Instant::now();
+        if let Some(percentiles) = &reward_percentiles {
+            let is_valid = percentiles.iter().all(|p| (0.0..=100.0).contains(p))
+                && percentiles.windows(2).all(|pair| pair[0] <= pair[1]);
+            if !is_valid {
+                return Err(Web3Error::InvalidFeeParams(
+                    "reward percentiles must be monotonically increasing values in [0, 100]"
+                        .to_owned(),
+                ));
+            }
+        }
+
+        let block_count = block_count.as_u64().min(FEE_HISTORY_MAX_BLOCK_COUNT);
+        if block_count == 0 {
+            return Ok(FeeHistory::default());
+        }
+        // There is no data for the pending block yet, so we use the latest sealed one instead.
+        let newest_block = match newest_block {
+            BlockNumber::Pending => BlockNumber::Latest,
+            block_number => block_number,
+        };
+
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await;
+        let newest_block =
+            resolve_block(&mut connection, BlockId::Number(newest_block), METHOD_NAME).await?;
+        let oldest_block = MiniblockNumber((newest_block.0 + 1).saturating_sub(block_count as u32));
+
+        let fee_history = connection
+            .blocks_web3_dal()
+            .get_fee_history(oldest_block, newest_block + 1)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let (blocks, next_block): (Vec<_>, Vec<_>) = fee_history
+            .into_iter()
+            .partition(|(number, _, _)| *number <= newest_block);
+
+        let mut base_fee_per_gas: Vec<_> =
+            blocks.iter().map(|(_, base_fee, _)| *base_fee).collect();
+        // The base fee of the block following the newest one is known only if such a block is already sealed;
+        // otherwise, it's the base fee that would be used for the next block.
+        let next_base_fee = match next_block.first() {
+            Some((_, base_fee, _)) => *base_fee,
+            None => self.state.tx_sender.gas_price().into(),
+        };
+        base_fee_per_gas.push(next_base_fee);
+
+        let gas_used_ratio = blocks
+            .iter()
+            .map(|(_, _, gas_used)| gas_used.as_u64() as f64 / BLOCK_GAS_LIMIT as f64)
+            .collect();
+
+        let reward = if let Some(percentiles) = &reward_percentiles {
+            let priority_fees = connection
+                .blocks_web3_dal()
+                .get_tx_priority_fees(oldest_block, newest_block)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            let mut priority_fees_by_block: HashMap<_, Vec<_>> = HashMap::new();
+            for (number, gas_used, priority_fee) in priority_fees {
+                priority_fees_by_block
+                    .entry(number)
+                    .or_default()
+                    .push((gas_used, priority_fee));
+            }
+
+            let reward = blocks
+                .iter()
+                .map(|(number, _, gas_used)| {
+                    let block_fees = priority_fees_by_block
+                        .get(number)
+                        .map_or(&[][..], Vec::as_slice);
+                    reward_percentiles(block_fees, *gas_used, percentiles)
+                })
+                .collect();
+            Some(reward)
+        } else {
+            None
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(FeeHistory {
+            oldest_block: oldest_block.0.into(),
+            base_fee_per_gas,
+            gas_used_ratio,
+            reward,
+            base_fee_per_blob_gas: vec![],
+        })
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_balance_impl(
         &self,
@@ -213,12 +346,18 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_balance";
 
         let start = Instant::now();
+        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let balance_key = storage_key_for_eth_balance(&address);
+        if let Some(balance) = self.state.pending_storage_value(block, &balance_key) {
+            metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+            return Ok(h256_to_u256(balance));
+        }
+
         let mut connection = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await;
-        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let block_number = resolve_block(&mut connection, block, METHOD_NAME).await?;
         let balance = connection
             .storage_web3_dal()
@@ -370,6 +509,11 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let start = Instant::now();
         let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let storage_key = StorageKey::new(AccountTreeId::new(address), u256_to_h256(idx));
+        if let Some(value) = self.state.pending_storage_value(block, &storage_key) {
+            metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+            return Ok(value);
+        }
+
         let mut connection = self
             .state
             .connection_pool
@@ -520,6 +664,39 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         receipt
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_receipts_impl(
+        &self,
+        block: BlockId,
+    ) -> Result<Option<Vec<TransactionReceipt>>, Web3Error> {
+        const METHOD_NAME: &str = "get_block_receipts";
+
+        let start = Instant::now();
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await;
+        let block_number = connection
+            .blocks_web3_dal()
+            .resolve_block_id(block)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let receipts = match block_number {
+            Some(block_number) => Some(
+                connection
+                    .transactions_web3_dal()
+                    .get_block_receipts(block_number)
+                    .await
+                    .map_err(|err| internal_error(METHOD_NAME, err))?,
+            ),
+            None => None,
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(receipts)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn new_block_filter_impl(&self) -> Result<U256, Web3Error> {
         const METHOD_NAME: &str = "new_block_filter";
@@ -920,6 +1097,27 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
     }
 }
 
+pub(super) fn validate_state_override(state_override: &StateOverride) -> Result<(), Web3Error> {
+    for (address, account) in state_override {
+        if account.state.is_some() && account.state_diff.is_some() {
+            return Err(Web3Error::InvalidStateOverride(format!(
+                "account {address:?} has both `state` and `stateDiff`"
+            )));
+        }
+        if let Some(code) = &account.code {
+            // Empty code is allowed; it removes the code of the account.
+            if !code.0.is_empty() {
+                validate_bytecode(&code.0).map_err(|err| {
+                    Web3Error::InvalidStateOverride(format!(
+                        "invalid code of account {address:?}: {err}"
+                    ))
+                })?;
+            }
+        }
+    }
+    Ok(())
+}
+
 // Bogus methods.
 // They are moved into a separate `impl` block so they don't make the actual implementation noisy.
 // This `impl` block contains methods that we *have* to implement for compliance, but don't really
@@ -959,3 +1157,106 @@ impl<E> EthNamespace<E> {
     // - `compile_solidity`.
     // - `compile_serpent`.
 }
+
+/// Computes the effective priority fees at the given percentiles of the gas used in a block.
+/// `fees` must contain `(gas_used, priority_fee)` pairs sorted by the priority fee, and `percentiles`
+/// must be sorted in the ascending order.
+fn reward_percentiles(
+    fees: &[(U256, U256)],
+    block_gas_used: U256,
+    percentiles: &[f32],
+) -> Vec<U256> {
+    if fees.is_empty() {
+        return vec![U256::zero(); percentiles.len()];
+    }
+
+    let block_gas_used = block_gas_used.as_u64() as f64;
+    let mut tx_index = 0;
+    let mut cumulative_gas_used = fees[0].0.as_u64() as f64;
+    percentiles
+        .iter()
+        .map(|&percentile| {
+            let threshold = block_gas_used * f64::from(percentile) / 100.0;
+            while cumulative_gas_used < threshold && tx_index < fees.len() - 1 {
+                tx_index += 1;
+                cumulative_gas_used += fees[tx_index].0.as_u64() as f64;
+            }
+            fees[tx_index].1
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::api::OverrideAccount;
+
+    use super::*;
+
+    #[test]
+    fn reward_percentiles_for_empty_block() {
+        let rewards = reward_percentiles(&[], U256::zero(), &[0.0, 50.0, 100.0]);
+        assert_eq!(rewards, vec![U256::zero(); 3]);
+    }
+
+    #[test]
+    fn reward_percentiles_are_weighted_by_gas_used() {
+        // `(gas_used, priority_fee)` pairs sorted by the priority fee.
+        let fees = [
+            (U256::from(10), U256::from(1)),
+            (U256::from(30), U256::from(2)),
+            (U256::from(60), U256::from(3)),
+        ];
+        let percentiles = [0.0, 10.0, 10.5, 40.0, 41.0, 99.0, 100.0];
+        let rewards = reward_percentiles(&fees, U256::from(100), &percentiles);
+        let expected: Vec<_> = [1, 1, 2, 2, 3, 3, 3]
+            .iter()
+            .map(|&fee| U256::from(fee))
+            .collect();
+        assert_eq!(rewards, expected);
+    }
+
+    #[test]
+    fn reward_percentiles_with_single_tx() {
+        let fees = [(U256::from(21_000), U256::from(5))];
+        let rewards = reward_percentiles(&fees, U256::from(21_000), &[0.0, 100.0]);
+        assert_eq!(rewards, vec![U256::from(5); 2]);
+    }
+
+    #[test]
+    fn validating_state_override() {
+        let address = Address::repeat_byte(1);
+        let valid_account = OverrideAccount {
+            balance: Some(U256::from(1)),
+            nonce: Some(U256::from(2)),
+            code: Some(vec![0_u8; 32].into()),
+            state_diff: Some(HashMap::from([(H256::zero(), H256::repeat_byte(1))])),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride::from([(address, valid_account)]);
+        validate_state_override(&state_override).unwrap();
+
+        let account_without_code = OverrideAccount {
+            code: Some(vec![].into()),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride::from([(address, account_without_code)]);
+        validate_state_override(&state_override).unwrap();
+
+        let account_with_invalid_code = OverrideAccount {
+            code: Some(vec![0_u8; 64].into()),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride::from([(address, account_with_invalid_code)]);
+        let err = validate_state_override(&state_override).unwrap_err();
+        assert!(matches!(err, Web3Error::InvalidStateOverride(_)), "{err:?}");
+
+        let account_with_both_states = OverrideAccount {
+            state: Some(HashMap::new()),
+            state_diff: Some(HashMap::new()),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride::from([(address, account_with_both_states)]);
+        let err = validate_state_override(&state_override).unwrap_err();
+        assert!(matches!(err, Web3Error::InvalidStateOverride(_)), "{err:?}");
+    }
+}
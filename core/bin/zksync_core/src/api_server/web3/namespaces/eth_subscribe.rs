@@ -7,17 +7,25 @@ use jsonrpc_pubsub::SubscriptionId;
 use tokio::sync::RwLock;
 
 use zksync_types::web3::types::H128;
-use zksync_web3_decl::types::{PubSubFilter, PubSubResult};
+use zksync_web3_decl::types::{PubSubFilter, PubSubResult, PubSubTxStatusFilter};
 
 use super::eth::EVENT_TOPIC_NUMBER_LIMIT;
+use crate::api_server::web3::pubsub_notifier::sync_status;
+use crate::sync_layer::SyncState;
 
 pub type SubscriptionMap<T> = Arc<RwLock<HashMap<SubscriptionId, T>>>;
 
+/// Maximum number of transaction hashes, and separately of addresses, in a `txStatus` subscription filter.
+const TX_STATUS_FILTER_ITEMS_LIMIT: usize = 100;
+
 #[derive(Debug, Clone, Copy)]
 enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    Syncing,
+    TxStatus,
+    L1Batches,
 }
 
 impl SubscriptionType {
@@ -26,6 +34,9 @@ impl SubscriptionType {
             Self::Blocks => "blocks",
             Self::Txs => "txs",
             Self::Logs => "logs",
+            Self::Syncing => "syncing",
+            Self::TxStatus => "tx_status",
+            Self::L1Batches => "l1_batches",
         }
     }
 }
@@ -37,15 +48,23 @@ pub struct EthSubscribe {
     pub active_block_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
     pub active_tx_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
     pub active_log_subs: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubFilter)>,
+    pub active_sync_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
+    pub active_tx_status_subs: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubTxStatusFilter)>,
+    pub active_l1_batch_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
+    sync_state: Option<SyncState>,
 }
 
 impl EthSubscribe {
-    pub fn new(runtime_handle: tokio::runtime::Handle) -> Self {
+    pub fn new(runtime_handle: tokio::runtime::Handle, sync_state: Option<SyncState>) -> Self {
         Self {
             runtime_handle,
             active_block_subs: SubscriptionMap::default(),
             active_tx_subs: SubscriptionMap::default(),
             active_log_subs: SubscriptionMap::default(),
+            active_sync_subs: SubscriptionMap::default(),
+            active_tx_status_subs: SubscriptionMap::default(),
+            active_l1_batch_subs: SubscriptionMap::default(),
+            sync_state,
         }
     }
 
@@ -115,11 +134,66 @@ impl EthSubscribe {
                     }
                 }
             }
+            "l1BatchStatus" => {
+                let mut l1_batch_subs = self.active_l1_batch_subs.write().await;
+                let (sink, id) = Self::assign_id(subscriber);
+                l1_batch_subs.insert(id, sink);
+                Some(SubscriptionType::L1Batches)
+            }
             "syncing" => {
-                let (sink, _) = Self::assign_id(subscriber);
-                let _ = sink.notify(Ok(PubSubResult::Syncing(false)));
+                let mut sync_subs = self.active_sync_subs.write().await;
+                let (sink, id) = Self::assign_id(subscriber);
+                // Further updates are pushed by the notifier only when the status changes,
+                // so the subscriber is informed about the current status right away.
+                let status = sync_status(self.sync_state.as_ref());
+                if sink.notify(Ok(PubSubResult::Syncing(status))).is_err() {
+                    // Subscriber disconnected.
+                    None
+                } else {
+                    sync_subs.insert(id, sink);
+                    Some(SubscriptionType::Syncing)
+                }
+            }
+            _ => {
+                Self::reject(subscriber);
                 None
             }
+        };
+
+        if let Some(sub_type) = sub_type {
+            metrics::increment_gauge!("api.web3.pubsub.active_subscribers", 1f64, "subscription_type" => sub_type.as_str());
+        }
+    }
+
+    /// Handles zkSync-specific subscriptions, i.e. the ones created with `zks_subscribe`.
+    #[tracing::instrument(skip(self, subscriber, params))]
+    pub async fn zks_sub(
+        &self,
+        subscriber: typed::Subscriber<PubSubResult>,
+        sub_type: String,
+        params: Option<serde_json::Value>,
+    ) {
+        let sub_type = match sub_type.as_str() {
+            "txStatus" => {
+                let filter = params.map(serde_json::from_value::<PubSubTxStatusFilter>);
+                match filter {
+                    Some(Ok(filter))
+                        if !filter.is_empty()
+                            && filter.tx_hashes.len() <= TX_STATUS_FILTER_ITEMS_LIMIT
+                            && filter.addresses.len() <= TX_STATUS_FILTER_ITEMS_LIMIT =>
+                    {
+                        let mut tx_status_subs = self.active_tx_status_subs.write().await;
+                        let (sink, id) = Self::assign_id(subscriber);
+                        tx_status_subs.insert(id, (sink, filter));
+                        Some(SubscriptionType::TxStatus)
+                    }
+                    // Subscribing to all transactions or to too many of them is not allowed.
+                    _ => {
+                        Self::reject(subscriber);
+                        None
+                    }
+                }
+            }
             _ => {
                 Self::reject(subscriber);
                 None
@@ -139,6 +213,24 @@ impl EthSubscribe {
             Some(SubscriptionType::Txs)
         } else if self.active_log_subs.write().await.remove(&id).is_some() {
             Some(SubscriptionType::Logs)
+        } else if self.active_sync_subs.write().await.remove(&id).is_some() {
+            Some(SubscriptionType::Syncing)
+        } else if self
+            .active_tx_status_subs
+            .write()
+            .await
+            .remove(&id)
+            .is_some()
+        {
+            Some(SubscriptionType::TxStatus)
+        } else if self
+            .active_l1_batch_subs
+            .write()
+            .await
+            .remove(&id)
+            .is_some()
+        {
+            Some(SubscriptionType::L1Batches)
         } else {
             None
         };
@@ -3,21 +3,29 @@ use std::{collections::HashMap, convert::TryInto};
 
 use bigdecimal::{BigDecimal, Zero};
 
+use vm::{utils::ETH_CALL_GAS_LIMIT, vm::VmTxExecutionResult};
+use zksync_merkle_tree::TreeLogEntry;
 use zksync_mini_merkle_tree::MiniMerkleTree;
 
-#[cfg(feature = "openzeppelin_tests")]
-use zksync_types::Bytes;
 use zksync_types::{
-    api::{BridgeAddresses, GetLogsFilter, L2ToL1LogProof, TransactionDetails, U64},
+    api::{
+        BlockId, BlockNumber, BridgeAddresses, GetLogsFilter, L2ToL1LogProof, Log, Proof,
+        SimulatedTransaction, SimulatedTransactionStatus, StateOverride, StorageProof,
+        TransactionDetails, U64,
+    },
     commitment::SerializeCommitment,
     explorer_api::{BlockDetails, L1BatchDetails},
     fee::Fee,
     l1::L1Tx,
+    l2::L2Tx,
     l2_to_l1_log::L2ToL1Log,
     tokens::ETHEREUM_ADDRESS,
     transaction_request::{l2_tx_from_call_req, CallRequest},
-    L1BatchNumber, MiniblockNumber, Transaction, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS,
-    MAX_GAS_PER_PUBDATA_BYTE, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
+    tx::tx_execution_info::TxExecutionStatus,
+    vm_trace::Call,
+    AccountTreeId, Bytes, L1BatchNumber, MiniblockNumber, StorageKey, Transaction,
+    L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, MAX_GAS_PER_PUBDATA_BYTE,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
 };
 use zksync_utils::address_to_h256;
 use zksync_web3_decl::{
@@ -25,11 +33,19 @@ use zksync_web3_decl::{
     types::{Address, Token, H256},
 };
 
-use crate::api_server::web3::{backend_jsonrpc::error::internal_error, RpcState};
+use super::eth::validate_state_override;
+use crate::api_server::{
+    execution_sandbox::BlockArgs,
+    tx_sender::SubmitTxError,
+    web3::{backend_jsonrpc::error::internal_error, RpcState},
+};
 use crate::fee_ticker::FeeTicker;
 use crate::fee_ticker::{error::TickerError, TokenPriceRequestType};
 use crate::l1_gas_price::L1GasPriceProvider;
 
+/// Maximum number of transactions in a bundle simulated by `zks_simulateBundle`.
+pub const MAX_SIMULATED_BUNDLE_SIZE: usize = 16;
+
 #[derive(Debug)]
 pub struct ZksNamespace<G> {
     pub state: RpcState<G>,
@@ -110,7 +126,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation, None)
             .await
             .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
 
@@ -532,6 +548,54 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         l1_batch
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_proof_impl(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<Proof>, Web3Error> {
+        const METHOD_NAME: &str = "get_proof";
+
+        let start = Instant::now();
+        let tree_reader = self
+            .state
+            .tree_reader
+            .get()
+            .ok_or(Web3Error::TreeApiUnavailable)?;
+        let storage_keys = keys
+            .iter()
+            .map(|key| StorageKey::new(AccountTreeId::new(address), *key))
+            .collect();
+        let root_hash_with_proofs = tree_reader
+            .root_hash_with_proofs(l1_batch_number, storage_keys)
+            .await;
+
+        let proof = root_hash_with_proofs.map(|(root_hash, entries)| {
+            let storage_proof = keys.into_iter().zip(entries).map(|(key, entry)| {
+                let (value, index) = match entry.base {
+                    TreeLogEntry::Read { value, leaf_index } => (value, leaf_index),
+                    TreeLogEntry::ReadMissingKey => (H256::zero(), 0),
+                    _ => unreachable!("tree reader only produces read entries"),
+                };
+                StorageProof {
+                    key,
+                    value,
+                    index,
+                    proof: entry.merkle_path,
+                }
+            });
+            Proof {
+                address,
+                root_hash,
+                storage_proof: storage_proof.collect(),
+            }
+        });
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(proof)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_bytecode_by_hash_impl(&self, hash: H256) -> Option<Vec<u8>> {
         const METHOD_NAME: &str = "get_bytecode_by_hash";
@@ -566,6 +630,145 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         gas_price.into()
     }
 
+    #[tracing::instrument(skip(self, requests, state_override))]
+    pub async fn simulate_bundle_impl(
+        &self,
+        requests: Vec<CallRequest>,
+        block: Option<BlockId>,
+        state_override: Option<StateOverride>,
+    ) -> Result<Vec<SimulatedTransaction>, Web3Error> {
+        const METHOD_NAME: &str = "simulate_bundle";
+
+        let start = Instant::now();
+        if requests.is_empty() || requests.len() > MAX_SIMULATED_BUNDLE_SIZE {
+            return Err(Web3Error::InvalidBundleSize(MAX_SIMULATED_BUNDLE_SIZE));
+        }
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override)?;
+        }
+
+        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await;
+        let block_args = BlockArgs::new(&mut connection, block)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .ok_or(Web3Error::NoBlock)?;
+        drop(connection);
+
+        let max_tx_size = self.state.api_config.max_tx_size;
+        let txs = requests
+            .into_iter()
+            .map(|request| l2_tx_from_call_req(request, max_tx_size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let results = self
+            .state
+            .tx_sender
+            .simulate_bundle(block_args, txs.clone(), state_override)
+            .await;
+
+        // Execution stops at the first rejected transaction, so `results` may be shorter than `txs`;
+        // the remaining transactions are reported as not executed.
+        let mut results = results.into_iter();
+        let mut log_index_in_bundle = 0_usize;
+        let simulated_txs = txs.into_iter().enumerate();
+        let simulated_txs = simulated_txs
+            .map(|(tx_index, tx)| {
+                let Some(result) = results.next() else {
+                    return SimulatedTransaction {
+                        status: SimulatedTransactionStatus::NotExecuted,
+                        gas_used: U256::zero(),
+                        revert_reason: None,
+                        logs: vec![],
+                        trace: None,
+                    };
+                };
+                let mut simulated_tx = Self::simulated_transaction(tx, result, tx_index);
+                for log in &mut simulated_tx.logs {
+                    log.log_index = Some(log_index_in_bundle.into());
+                    log_index_in_bundle += 1;
+                }
+                simulated_tx
+            })
+            .collect();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(simulated_txs)
+    }
+
+    fn simulated_transaction(
+        tx: L2Tx,
+        result: Result<VmTxExecutionResult, SubmitTxError>,
+        tx_index: usize,
+    ) -> SimulatedTransaction {
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => {
+                return SimulatedTransaction {
+                    status: SimulatedTransactionStatus::Rejected,
+                    gas_used: U256::zero(),
+                    revert_reason: Some(err.to_string()),
+                    logs: vec![],
+                    trace: None,
+                };
+            }
+        };
+
+        let (status, revert_reason) = match result.status {
+            TxExecutionStatus::Success => (SimulatedTransactionStatus::Success, None),
+            TxExecutionStatus::Failure => {
+                // The VM doesn't provide a revert reason for executed transactions,
+                // so it's taken from the top-level calls.
+                let revert_reason = result
+                    .call_traces
+                    .iter()
+                    .rev()
+                    .find_map(|call| call.revert_reason.clone());
+                (SimulatedTransactionStatus::Reverted, revert_reason)
+            }
+        };
+        // All transactions in the bundle are executed with the same gas limit as `eth_call`s,
+        // so the gas used is determined by the amount refunded by the bootloader.
+        let gas_used = ETH_CALL_GAS_LIMIT - result.gas_refunded;
+        let logs = result.result.logs.events.into_iter().enumerate();
+        let logs = logs
+            .map(|(log_index_in_tx, event)| Log {
+                address: event.address,
+                topics: event.indexed_topics,
+                data: Bytes(event.value),
+                block_hash: None,
+                block_number: None,
+                l1_batch_number: None,
+                transaction_hash: None,
+                transaction_index: Some(tx_index.into()),
+                log_index: None,
+                transaction_log_index: Some(log_index_in_tx.into()),
+                log_type: None,
+                removed: Some(false),
+            })
+            .collect();
+        let trace = Call::new_high_level(
+            ETH_CALL_GAS_LIMIT,
+            gas_used,
+            tx.execute.value,
+            tx.execute.calldata,
+            vec![],
+            revert_reason.clone(),
+            result.call_traces,
+        );
+
+        SimulatedTransaction {
+            status,
+            gas_used: gas_used.into(),
+            revert_reason,
+            logs,
+            trace: Some(trace.into()),
+        }
+    }
+
     #[cfg(feature = "openzeppelin_tests")]
     /// Saves contract bytecode to memory.
     pub fn set_known_bytecode_impl(&self, bytecode: Bytes) -> bool {
@@ -1,12 +1,21 @@
 use jsonrpc_pubsub::typed;
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
 use tokio::time::{interval, Duration, Instant};
 
-use zksync_dal::ConnectionPool;
-use zksync_types::MiniblockNumber;
-use zksync_web3_decl::types::{PubSubFilter, PubSubResult};
+use std::{ops::RangeInclusive, sync::Arc};
+
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    api::{self, Log},
+    web3::types::BlockHeader,
+    L1BatchNumber, MiniblockNumber, H256,
+};
+use zksync_web3_decl::types::{
+    PubSubFilter, PubSubResult, PubSubSyncInfo, PubSubSyncStatus, PubSubTxStatusFilter,
+};
 
 use super::namespaces::SubscriptionMap;
+use crate::{state_keeper::SealedMiniblockEvent, sync_layer::SyncState};
 
 pub async fn notify_blocks(
     subscribers: SubscriptionMap<typed::Sink<PubSubResult>>,
@@ -42,26 +51,32 @@ pub async fn notify_blocks(
         if !new_blocks.is_empty() {
             last_block_number =
                 MiniblockNumber(new_blocks.last().unwrap().number.unwrap().as_u32());
+            notify_block_subscribers(&subscribers, &new_blocks).await;
+        }
+    }
+}
 
-            let start = Instant::now();
-            let subscribers = subscribers
-                .read()
-                .await
-                .values()
-                .cloned()
-                .collect::<Vec<_>>();
-            for sink in subscribers {
-                for block in new_blocks.iter().cloned() {
-                    if sink.notify(Ok(PubSubResult::Header(block))).is_err() {
-                        // Subscriber disconnected.
-                        break;
-                    }
-                    metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "blocks");
-                }
+async fn notify_block_subscribers(
+    subscribers: &SubscriptionMap<typed::Sink<PubSubResult>>,
+    new_blocks: &[BlockHeader],
+) {
+    let start = Instant::now();
+    let subscribers = subscribers
+        .read()
+        .await
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    for sink in subscribers {
+        for block in new_blocks.iter().cloned() {
+            if sink.notify(Ok(PubSubResult::Header(block))).is_err() {
+                // Subscriber disconnected.
+                break;
             }
-            metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "blocks");
+            metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "blocks");
         }
     }
+    metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "blocks");
 }
 
 pub async fn notify_txs(
@@ -91,26 +106,32 @@ pub async fn notify_txs(
         metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "txs");
         if let Some(new_last_time) = new_last_time {
             last_time = new_last_time;
-            let start = Instant::now();
+            notify_tx_subscribers(&subscribers, &new_txs).await;
+        }
+    }
+}
 
-            let subscribers = subscribers
-                .read()
-                .await
-                .values()
-                .cloned()
-                .collect::<Vec<_>>();
-            for sink in subscribers {
-                for tx_hash in new_txs.iter().cloned() {
-                    if sink.notify(Ok(PubSubResult::TxHash(tx_hash))).is_err() {
-                        // Subscriber disconnected.
-                        break;
-                    }
-                    metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "txs");
-                }
+async fn notify_tx_subscribers(
+    subscribers: &SubscriptionMap<typed::Sink<PubSubResult>>,
+    new_txs: &[H256],
+) {
+    let start = Instant::now();
+    let subscribers = subscribers
+        .read()
+        .await
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    for sink in subscribers {
+        for tx_hash in new_txs.iter().cloned() {
+            if sink.notify(Ok(PubSubResult::TxHash(tx_hash))).is_err() {
+                // Subscriber disconnected.
+                break;
             }
-            metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "txs");
+            metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "txs");
         }
     }
+    metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "txs");
 }
 
 pub async fn notify_logs(
@@ -147,6 +168,189 @@ pub async fn notify_logs(
         if !new_logs.is_empty() {
             last_block_number =
                 MiniblockNumber(new_logs.last().unwrap().block_number.unwrap().as_u32());
+            notify_log_subscribers(&subscribers, &new_logs).await;
+        }
+    }
+}
+
+async fn notify_log_subscribers(
+    subscribers: &SubscriptionMap<(typed::Sink<PubSubResult>, PubSubFilter)>,
+    new_logs: &[Log],
+) {
+    let start = Instant::now();
+    let subscribers = subscribers
+        .read()
+        .await
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for (sink, filter) in subscribers {
+        for log in new_logs.iter().cloned() {
+            if filter.matches(&log) {
+                if sink.notify(Ok(PubSubResult::Log(log))).is_err() {
+                    // Subscriber disconnected.
+                    break;
+                }
+                metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "logs");
+            }
+        }
+    }
+    metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "logs");
+}
+
+/// Subscribers notified about new miniblocks by [`notify_from_miniblock_events()`].
+#[derive(Clone)]
+pub struct MiniblockSubscribers {
+    pub blocks: SubscriptionMap<typed::Sink<PubSubResult>>,
+    pub logs: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubFilter)>,
+}
+
+/// Notifies `newHeads` and `logs` subscribers about miniblocks reported by the co-located state keeper,
+/// replacing [`notify_blocks()`] and [`notify_logs()`]. `newPendingTransactions` subscribers are still
+/// notified by [`notify_txs()`], since they are interested in transactions once they are received
+/// rather than once they are included into a miniblock.
+///
+/// If the notifier falls behind the bus, the missed miniblocks are loaded from Postgres.
+pub async fn notify_from_miniblock_events(
+    subscribers: MiniblockSubscribers,
+    events_receiver: broadcast::Receiver<Arc<SealedMiniblockEvent>>,
+    connection_pool: ConnectionPool,
+    stop_receiver: watch::Receiver<bool>,
+) {
+    let last_block_number = connection_pool
+        .access_storage_tagged("api")
+        .await
+        .blocks_web3_dal()
+        .get_sealed_miniblock_number()
+        .await
+        .unwrap();
+    process_miniblock_events(
+        &subscribers,
+        events_receiver,
+        &connection_pool,
+        last_block_number,
+        stop_receiver,
+    )
+    .await;
+}
+
+/// Notifies subscribers about miniblocks after `last_block_number` received from the bus.
+async fn process_miniblock_events(
+    subscribers: &MiniblockSubscribers,
+    mut events_receiver: broadcast::Receiver<Arc<SealedMiniblockEvent>>,
+    connection_pool: &ConnectionPool,
+    mut last_block_number: MiniblockNumber,
+    mut stop_receiver: watch::Receiver<bool>,
+) {
+    loop {
+        let event = tokio::select! {
+            event = events_receiver.recv() => event,
+            _ = stop_receiver.changed() => {
+                vlog::info!("Stop signal received, pubsub_miniblock_events_notifier is shutting down");
+                break;
+            }
+        };
+
+        match event {
+            Ok(event) => {
+                let block_number = MiniblockNumber(event.header.number.unwrap().as_u32());
+                if block_number <= last_block_number {
+                    continue; // The miniblock was already loaded from Postgres.
+                }
+                last_block_number = block_number;
+
+                notify_block_subscribers(&subscribers.blocks, &[event.header.clone()]).await;
+                notify_log_subscribers(&subscribers.logs, &event.logs).await;
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped_events)) => {
+                vlog::warn!(
+                    "Pubsub notifier skipped {skipped_events} miniblock events; loading missed miniblocks \
+                     after #{last_block_number} from Postgres"
+                );
+                metrics::counter!("api.web3.pubsub.miniblock_events_lagged", skipped_events);
+                last_block_number =
+                    catch_up_from_storage(subscribers, connection_pool, last_block_number).await;
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                vlog::warn!("Miniblock events bus is closed, pubsub_miniblock_events_notifier is shutting down");
+                break;
+            }
+        }
+    }
+}
+
+/// Notifies subscribers about all miniblocks after `last_block_number` persisted in Postgres.
+/// Returns the number of the last notified miniblock.
+async fn catch_up_from_storage(
+    subscribers: &MiniblockSubscribers,
+    connection_pool: &ConnectionPool,
+    last_block_number: MiniblockNumber,
+) -> MiniblockNumber {
+    let start = Instant::now();
+    let mut storage = connection_pool.access_storage_tagged("api").await;
+    let new_blocks = storage
+        .blocks_web3_dal()
+        .get_block_headers_after(last_block_number)
+        .await
+        .unwrap();
+    let Some(new_last_block_number) = new_blocks.last().map(|block| block.number.unwrap()) else {
+        return last_block_number;
+    };
+    let new_last_block_number = MiniblockNumber(new_last_block_number.as_u32());
+
+    // Logs for miniblocks sealed after the headers were loaded are notified later from the bus.
+    let new_logs = storage
+        .events_web3_dal()
+        .get_all_logs(last_block_number)
+        .await
+        .unwrap();
+    let new_logs: Vec<_> = new_logs
+        .into_iter()
+        .filter(|log| log.block_number.unwrap().as_u32() <= new_last_block_number.0)
+        .collect();
+    drop(storage);
+    metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "miniblock_events");
+
+    notify_block_subscribers(&subscribers.blocks, &new_blocks).await;
+    notify_log_subscribers(&subscribers.logs, &new_logs).await;
+    new_last_block_number
+}
+
+/// Returns the sync status to be reported to `syncing` subscribers. If there is no `sync_state`,
+/// the node is the main node, and it's always synced.
+pub(super) fn sync_status(sync_state: Option<&SyncState>) -> PubSubSyncStatus {
+    match sync_state {
+        Some(state) if !state.is_synced() => PubSubSyncStatus::syncing(PubSubSyncInfo {
+            starting_block: 0.into(), // We always start syncing from genesis right now.
+            current_block: state.get_local_block().0.into(),
+            highest_block: state.get_main_node_block().0.into(),
+        }),
+        _ => PubSubSyncStatus::synced(),
+    }
+}
+
+pub async fn notify_syncing(
+    subscribers: SubscriptionMap<typed::Sink<PubSubResult>>,
+    sync_state: SyncState,
+    polling_interval: Duration,
+    stop_receiver: watch::Receiver<bool>,
+) {
+    let mut last_status = sync_status(Some(&sync_state));
+    let mut timer = interval(polling_interval);
+    loop {
+        if *stop_receiver.borrow() {
+            vlog::info!("Stop signal received, pubsub_syncing_notifier is shutting down");
+            break;
+        }
+
+        timer.tick().await;
+
+        // Subscribers receive the current status on subscription, so we only need to notify them
+        // about changes: progress while syncing, and the transitions between syncing and synced states.
+        let status = sync_status(Some(&sync_state));
+        if status != last_status {
+            last_status = status;
             let start = Instant::now();
 
             let subscribers = subscribers
@@ -155,19 +359,373 @@ pub async fn notify_logs(
                 .values()
                 .cloned()
                 .collect::<Vec<_>>();
+            for sink in subscribers {
+                if sink.notify(Ok(PubSubResult::Syncing(status))).is_ok() {
+                    metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "syncing");
+                }
+            }
+            metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "syncing");
+        }
+    }
+}
+
+/// Stages of L1 batches on L1, in the order they are reached.
+const L1_BATCH_STAGES: [api::L1BatchStage; 3] = [
+    api::L1BatchStage::Committed,
+    api::L1BatchStage::Proven,
+    api::L1BatchStage::Executed,
+];
+
+/// Tracks the last L1 batch that has reached each of [`L1_BATCH_STAGES`].
+#[derive(Debug)]
+struct L1BatchStageTracker {
+    last_numbers: [Option<L1BatchNumber>; L1_BATCH_STAGES.len()],
+}
+
+impl L1BatchStageTracker {
+    async fn new(storage: &mut StorageProcessor<'_>) -> Self {
+        let mut last_numbers = [None; L1_BATCH_STAGES.len()];
+        for (stage, last_number) in L1_BATCH_STAGES.into_iter().zip(&mut last_numbers) {
+            *last_number = Self::last_l1_batch_at_stage(storage, stage).await;
+        }
+        Self { last_numbers }
+    }
+
+    async fn last_l1_batch_at_stage(
+        storage: &mut StorageProcessor<'_>,
+        stage: api::L1BatchStage,
+    ) -> Option<L1BatchNumber> {
+        let mut blocks_dal = storage.blocks_dal();
+        match stage {
+            api::L1BatchStage::Committed => {
+                blocks_dal.get_number_of_last_block_committed_on_eth().await
+            }
+            api::L1BatchStage::Proven => blocks_dal.get_number_of_last_block_proven_on_eth().await,
+            api::L1BatchStage::Executed => {
+                blocks_dal.get_number_of_last_block_executed_on_eth().await
+            }
+        }
+    }
+
+    /// Returns ranges of L1 batches that have reached each stage since the previous call.
+    async fn advance(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Vec<(api::L1BatchStage, RangeInclusive<L1BatchNumber>)> {
+        let mut new_ranges = vec![];
+        for (stage, last_number) in L1_BATCH_STAGES.into_iter().zip(&mut self.last_numbers) {
+            let new_last_number = Self::last_l1_batch_at_stage(storage, stage).await;
+            if let Some(new_last_number) =
+                new_last_number.filter(|&number| Some(number) > *last_number)
+            {
+                let first_new_number = last_number.map_or(L1BatchNumber(0), |number| number + 1);
+                *last_number = Some(new_last_number);
+                new_ranges.push((stage, first_new_number..=new_last_number));
+            }
+        }
+        new_ranges
+    }
+}
+
+pub async fn notify_tx_statuses(
+    subscribers: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubTxStatusFilter)>,
+    connection_pool: ConnectionPool,
+    polling_interval: Duration,
+    stop_receiver: watch::Receiver<bool>,
+) {
+    // The tracker is only maintained while there are subscribers, so that the database isn't polled
+    // in vain. It's reset once there are no subscribers, so that new subscribers aren't notified
+    // about batches that have reached a stage before they've subscribed.
+    let mut stage_tracker: Option<L1BatchStageTracker> = None;
+    let mut timer = interval(polling_interval);
+    loop {
+        if *stop_receiver.borrow() {
+            vlog::info!("Stop signal received, pubsub_tx_status_notifier is shutting down");
+            break;
+        }
+
+        timer.tick().await;
+
+        let start = Instant::now();
+        let filters: Vec<_> = subscribers
+            .read()
+            .await
+            .values()
+            .map(|(_, filter)| filter.clone())
+            .collect();
+        if filters.is_empty() {
+            stage_tracker = None;
+            continue;
+        }
+        let tx_hashes: Vec<_> = filters
+            .iter()
+            .flat_map(|filter| filter.tx_hashes.iter().copied())
+            .collect();
+        let addresses: Vec<_> = filters
+            .iter()
+            .flat_map(|filter| filter.addresses.iter().copied())
+            .collect();
+
+        let mut storage = connection_pool.access_storage_tagged("api").await;
+        let new_ranges = match &mut stage_tracker {
+            Some(stage_tracker) => stage_tracker.advance(&mut storage).await,
+            None => {
+                stage_tracker = Some(L1BatchStageTracker::new(&mut storage).await);
+                vec![]
+            }
+        };
+        let mut updates = vec![];
+        for (stage, l1_batches) in new_ranges {
+            let new_updates = storage
+                .blocks_web3_dal()
+                .get_tx_status_updates(l1_batches, stage, &tx_hashes, &addresses)
+                .await
+                .unwrap();
+            updates.extend(new_updates);
+        }
+        drop(storage);
+        metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "tx_status");
+
+        if !updates.is_empty() {
+            notify_tx_status_subscribers(&subscribers, &updates).await;
+        }
+    }
+}
+
+async fn notify_tx_status_subscribers(
+    subscribers: &SubscriptionMap<(typed::Sink<PubSubResult>, PubSubTxStatusFilter)>,
+    updates: &[api::TxStatusUpdate],
+) {
+    let start = Instant::now();
+    let subscribers = subscribers
+        .read()
+        .await
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for (sink, filter) in subscribers {
+        for update in updates {
+            if filter.matches(update) {
+                if sink
+                    .notify(Ok(PubSubResult::TxStatus(update.clone())))
+                    .is_err()
+                {
+                    // Subscriber disconnected.
+                    break;
+                }
+                metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "tx_status");
+            }
+        }
+    }
+    metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "tx_status");
+}
+
+pub async fn notify_l1_batch_statuses(
+    subscribers: SubscriptionMap<typed::Sink<PubSubResult>>,
+    connection_pool: ConnectionPool,
+    polling_interval: Duration,
+    stop_receiver: watch::Receiver<bool>,
+) {
+    // Like with `txStatus` subscriptions, the last sealed batch and batch stages are only tracked
+    // while there are subscribers.
+    let mut tracked_state: Option<(L1BatchNumber, L1BatchStageTracker)> = None;
+    let mut timer = interval(polling_interval);
+    loop {
+        if *stop_receiver.borrow() {
+            vlog::info!("Stop signal received, pubsub_l1_batch_status_notifier is shutting down");
+            break;
+        }
+
+        timer.tick().await;
 
-            for (sink, filter) in subscribers {
-                for log in new_logs.iter().cloned() {
-                    if filter.matches(&log) {
-                        if sink.notify(Ok(PubSubResult::Log(log))).is_err() {
-                            // Subscriber disconnected.
-                            break;
-                        }
-                        metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "logs");
-                    }
+        if subscribers.read().await.is_empty() {
+            tracked_state = None;
+            continue;
+        }
+
+        let start = Instant::now();
+        let mut storage = connection_pool.access_storage_tagged("api").await;
+        let sealed_number = storage
+            .blocks_web3_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        let mut new_ranges = vec![];
+        match &mut tracked_state {
+            Some((last_sealed_number, stage_tracker)) => {
+                if sealed_number > *last_sealed_number {
+                    new_ranges.push((None, *last_sealed_number + 1..=sealed_number));
+                    *last_sealed_number = sealed_number;
                 }
+                let stage_ranges = stage_tracker.advance(&mut storage).await;
+                new_ranges.extend(
+                    stage_ranges
+                        .into_iter()
+                        .map(|(stage, l1_batches)| (Some(stage), l1_batches)),
+                );
+            }
+            None => {
+                let stage_tracker = L1BatchStageTracker::new(&mut storage).await;
+                tracked_state = Some((sealed_number, stage_tracker));
+            }
+        }
+
+        let mut updates = vec![];
+        for (stage, l1_batches) in new_ranges {
+            let new_updates = storage
+                .blocks_web3_dal()
+                .get_l1_batch_status_updates(l1_batches, stage)
+                .await
+                .unwrap();
+            updates.extend(new_updates);
+        }
+        drop(storage);
+        metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "l1_batches");
+
+        if !updates.is_empty() {
+            notify_l1_batch_status_subscribers(&subscribers, &updates).await;
+        }
+    }
+}
+
+async fn notify_l1_batch_status_subscribers(
+    subscribers: &SubscriptionMap<typed::Sink<PubSubResult>>,
+    updates: &[api::L1BatchStatusUpdate],
+) {
+    let start = Instant::now();
+    let subscribers = subscribers
+        .read()
+        .await
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    for sink in subscribers {
+        for update in updates.iter().cloned() {
+            if sink
+                .notify(Ok(PubSubResult::L1BatchStatus(update)))
+                .is_err()
+            {
+                // Subscriber disconnected.
+                break;
             }
-            metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "logs");
+            metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "l1_batches");
         }
     }
+    metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "l1_batches");
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use futures::channel::mpsc;
+    use jsonrpc_pubsub::SubscriptionId;
+    use tokio::sync::RwLock;
+
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::state_keeper::miniblock_events_bus;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::MiniblockHeader, U64};
+
+    async fn insert_miniblocks(pool: &ConnectionPool, numbers: RangeInclusive<u32>) {
+        let mut storage = pool.access_storage_tagged("api").await;
+        for number in numbers {
+            let header = MiniblockHeader {
+                number: MiniblockNumber(number),
+                timestamp: number.into(),
+                hash: H256::from_low_u64_be(number.into()),
+                l1_tx_count: 0,
+                l2_tx_count: 0,
+                base_fee_per_gas: 100,
+                l1_gas_price: 100,
+                l2_fair_gas_price: 100,
+                base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+            };
+            storage.blocks_dal().insert_miniblock(&header).await;
+        }
+    }
+
+    fn block_subscribers() -> (MiniblockSubscribers, mpsc::UnboundedReceiver<String>) {
+        let (subscriber, _id_receiver, notifications) =
+            typed::Subscriber::new_test("eth_subscription");
+        let id = SubscriptionId::String("0x1".to_owned());
+        let sink = subscriber.assign_id(id.clone()).unwrap();
+        let subscribers = MiniblockSubscribers {
+            blocks: Arc::new(RwLock::new(HashMap::from([(id, sink)]))),
+            logs: SubscriptionMap::default(),
+        };
+        (subscribers, notifications)
+    }
+
+    /// Extracts numbers of the blocks sent to a `newHeads` subscriber.
+    fn notified_block_numbers(notifications: &mut mpsc::UnboundedReceiver<String>) -> Vec<u64> {
+        let mut numbers = vec![];
+        while let Ok(Some(notification)) = notifications.try_next() {
+            let notification: serde_json::Value = serde_json::from_str(&notification).unwrap();
+            let number = &notification["params"]["result"]["number"];
+            let number = serde_json::from_value::<U64>(number.clone()).unwrap();
+            numbers.push(number.as_u64());
+        }
+        numbers
+    }
+
+    #[db_test]
+    async fn catching_up_from_storage(pool: ConnectionPool) {
+        insert_miniblocks(&pool, 0..=3).await;
+        let (subscribers, mut notifications) = block_subscribers();
+
+        let last_block_number =
+            catch_up_from_storage(&subscribers, &pool, MiniblockNumber(1)).await;
+        assert_eq!(last_block_number, MiniblockNumber(3));
+        assert_eq!(notified_block_numbers(&mut notifications), [2, 3]);
+
+        // If there are no new miniblocks, nothing should be notified.
+        let last_block_number =
+            catch_up_from_storage(&subscribers, &pool, MiniblockNumber(3)).await;
+        assert_eq!(last_block_number, MiniblockNumber(3));
+        assert!(notified_block_numbers(&mut notifications).is_empty());
+    }
+
+    #[db_test]
+    async fn notifying_from_lagged_miniblock_events(pool: ConnectionPool) {
+        // Overflow the bus, so that the notifier lags and has to load miniblocks from Postgres.
+        let last_block_number = 300;
+        insert_miniblocks(&pool, 0..=last_block_number).await;
+        let mut storage = pool.access_storage_tagged("api").await;
+        let headers = storage
+            .blocks_web3_dal()
+            .get_block_headers_after(MiniblockNumber(0))
+            .await
+            .unwrap();
+        drop(storage);
+
+        let events_sender = miniblock_events_bus();
+        let events_receiver = events_sender.subscribe();
+        for header in headers {
+            let event = SealedMiniblockEvent {
+                header,
+                logs: vec![],
+            };
+            events_sender.send(Arc::new(event)).unwrap();
+        }
+        drop(events_sender);
+
+        let (subscribers, mut notifications) = block_subscribers();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        // Processing terminates once all events are received since the bus is closed.
+        process_miniblock_events(
+            &subscribers,
+            events_receiver,
+            &pool,
+            MiniblockNumber(0),
+            stop_receiver,
+        )
+        .await;
+
+        // Each miniblock must be notified exactly once, in order.
+        let expected_numbers: Vec<_> = (1..=u64::from(last_block_number)).collect();
+        assert_eq!(notified_block_numbers(&mut notifications), expected_numbers);
+    }
 }
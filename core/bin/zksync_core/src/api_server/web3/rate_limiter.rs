@@ -0,0 +1,284 @@
+//! Per-client rate limiting for the Web3 API servers.
+//!
+//! Each call costs a certain weight (1 unless configured otherwise for the method), and each client
+//! is allowed to spend a limited total weight per second. HTTP clients are identified by a known API key
+//! or, if the server runs behind a trusted reverse proxy, by the IP address reported by the proxy.
+//! HTTP servers don't expose the peer address to request handlers, so HTTP clients that cannot be identified
+//! share a single quota. WebSocket clients are identified by their connection.
+
+use governor::{clock::DefaultClock, state::keyed::DefaultKeyedStateStore, Quota, RateLimiter};
+use tokio::sync::watch;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    net::IpAddr,
+    num::NonZeroU32,
+    sync::Arc,
+    time::Duration,
+};
+
+use zksync_web3_decl::error::Web3Error;
+
+/// Header with the API key identifying the client.
+pub const API_KEY_HEADER: &str = "x-api-key";
+/// Key shared by all HTTP clients that could not be identified.
+const UNIDENTIFIED_HTTP_CLIENT_KEY: &str = "http:unidentified";
+/// Interval between removals of the state for clients that have fully replenished their quota.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Rules of identifying clients by the headers of HTTP requests.
+#[derive(Debug, Clone, Default)]
+pub struct ClientIdentification {
+    /// API keys recognized as client identifiers; other keys are ignored.
+    pub api_keys: HashSet<String>,
+    /// Whether the server is only reachable via a reverse proxy overwriting `X-Forwarded-For` / `X-Real-IP` headers.
+    /// If not set, the headers are ignored since they can be set by the clients to arbitrary values.
+    pub behind_trusted_proxy: bool,
+}
+
+impl ClientIdentification {
+    /// Identifies the client given a getter for the request headers. A known API key takes precedence
+    /// over the IP address. Returns `None` if the client cannot be identified.
+    pub fn client_key<'a>(&self, header_value: impl Fn(&str) -> Option<&'a str>) -> Option<String> {
+        if let Some(api_key) = header_value(API_KEY_HEADER) {
+            if self.api_keys.contains(api_key.trim()) {
+                return Some(format!("key:{}", api_key.trim()));
+            }
+        }
+        if !self.behind_trusted_proxy {
+            return None;
+        }
+
+        // The last address in `X-Forwarded-For` is appended by the proxy closest to the server,
+        // so unlike other addresses it cannot be spoofed by the client.
+        let ip_address = header_value("x-forwarded-for")
+            .and_then(|addresses| addresses.rsplit(',').next())
+            .or_else(|| header_value("x-real-ip"))?;
+        let ip_address: IpAddr = ip_address.trim().parse().ok()?;
+        Some(format!("ip:{ip_address}"))
+    }
+}
+
+/// Type alias for the rate limiter implementation.
+type KeyedRateLimiter = RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>;
+
+/// Rate limiter keyed by the client, with configurable weights of the methods.
+pub struct ApiRateLimiter {
+    limiter: KeyedRateLimiter,
+    method_weights: HashMap<String, u32>,
+    identification: ClientIdentification,
+}
+
+impl fmt::Debug for ApiRateLimiter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ApiRateLimiter")
+            .field("method_weights", &self.method_weights)
+            .field("identification", &self.identification)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ApiRateLimiter {
+    /// Creates a rate limiter allowing each client to spend `weight_per_sec` per second.
+    /// Methods not present in `method_weights` have the weight of 1; methods with zero weight are not limited.
+    /// Clients are identified according to `identification`.
+    pub fn new(
+        weight_per_sec: u32,
+        method_weights: HashMap<String, u32>,
+        identification: ClientIdentification,
+    ) -> Self {
+        let weight_per_sec =
+            NonZeroU32::new(weight_per_sec).expect("Rate limit weight per second must be positive");
+        for (method, &weight) in &method_weights {
+            if weight > weight_per_sec.get() {
+                vlog::warn!(
+                    "Weight of `{method}` ({weight}) exceeds the rate limit ({weight_per_sec}), \
+                     so this method can never be called"
+                );
+            }
+        }
+
+        Self {
+            limiter: RateLimiter::keyed(Quota::per_second(weight_per_sec)),
+            method_weights,
+            identification,
+        }
+    }
+
+    pub fn identification(&self) -> &ClientIdentification {
+        &self.identification
+    }
+
+    /// Identifies the client sending an HTTP request.
+    pub fn http_client_key(&self, headers: &hyper::HeaderMap) -> String {
+        let header_value = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        self.identification
+            .client_key(header_value)
+            .unwrap_or_else(|| UNIDENTIFIED_HTTP_CLIENT_KEY.to_owned())
+    }
+
+    /// Identifies the client by its WebSocket connection.
+    pub fn ws_client_key(session_id: u64) -> String {
+        format!("ws:{session_id}")
+    }
+
+    fn method_weight(&self, method: &str) -> u32 {
+        self.method_weights.get(method).copied().unwrap_or(1)
+    }
+
+    /// Checks whether the client is allowed to make the specified calls (a batch request may contain
+    /// several calls) and consumes their total weight from the client's quota if it is.
+    pub fn check<'a>(
+        &self,
+        client_key: &str,
+        methods: impl IntoIterator<Item = &'a str>,
+        transport: &'static str,
+    ) -> Result<(), Web3Error> {
+        let total_weight = methods
+            .into_iter()
+            .map(|method| self.method_weight(method))
+            .fold(0, u32::saturating_add);
+        let Some(total_weight) = NonZeroU32::new(total_weight) else {
+            return Ok(());
+        };
+
+        let client_key = client_key.to_owned();
+        if self.limiter.check_key_n(&client_key, total_weight).is_ok() {
+            Ok(())
+        } else {
+            metrics::increment_counter!("api.web3.rate_limited", "transport" => transport);
+            Err(Web3Error::RateLimitExceeded)
+        }
+    }
+
+    /// Periodically removes the state for clients that have fully replenished their quota,
+    /// so that the memory used by the limiter doesn't grow indefinitely.
+    pub(super) async fn prune_periodically(
+        self: Arc<Self>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) {
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => self.limiter.retain_recent(),
+                _ = stop_receiver.changed() => break,
+            }
+        }
+        vlog::info!("Stop signal received, API rate limiter pruning is shutting down");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limiter(behind_trusted_proxy: bool) -> ApiRateLimiter {
+        let identification = ClientIdentification {
+            api_keys: HashSet::from(["known".to_owned()]),
+            behind_trusted_proxy,
+        };
+        ApiRateLimiter::new(5, HashMap::new(), identification)
+    }
+
+    fn headers(entries: &[(&'static str, &'static str)]) -> hyper::HeaderMap {
+        entries
+            .iter()
+            .map(|&(name, value)| (name, hyper::header::HeaderValue::from_static(value)))
+            .map(|(name, value)| (hyper::header::HeaderName::from_static(name), value))
+            .collect()
+    }
+
+    #[test]
+    fn identifying_clients_by_api_key() {
+        let rate_limiter = rate_limiter(true);
+        let key = rate_limiter.http_client_key(&headers(&[(API_KEY_HEADER, "known")]));
+        assert_eq!(key, "key:known");
+
+        // Unknown API keys are ignored.
+        let key = rate_limiter.http_client_key(&headers(&[(API_KEY_HEADER, "unknown")]));
+        assert_eq!(key, UNIDENTIFIED_HTTP_CLIENT_KEY);
+        let key = rate_limiter.http_client_key(&headers(&[
+            (API_KEY_HEADER, "unknown"),
+            ("x-real-ip", "10.0.0.1"),
+        ]));
+        assert_eq!(key, "ip:10.0.0.1");
+
+        // A known API key takes precedence over the IP address.
+        let key = rate_limiter.http_client_key(&headers(&[
+            (API_KEY_HEADER, "known"),
+            ("x-real-ip", "10.0.0.1"),
+        ]));
+        assert_eq!(key, "key:known");
+    }
+
+    #[test]
+    fn identifying_clients_by_forwarded_ip_address() {
+        let rate_limiter = rate_limiter(true);
+        let forwarded_headers = headers(&[
+            ("x-forwarded-for", "1.2.3.4, 10.0.0.1"),
+            ("x-real-ip", "10.0.0.2"),
+        ]);
+        let key = rate_limiter.http_client_key(&forwarded_headers);
+        assert_eq!(key, "ip:10.0.0.1");
+
+        let key = rate_limiter.http_client_key(&headers(&[("x-real-ip", "::1")]));
+        assert_eq!(key, "ip:::1");
+        let key = rate_limiter.http_client_key(&headers(&[("x-real-ip", "garbage")]));
+        assert_eq!(key, UNIDENTIFIED_HTTP_CLIENT_KEY);
+        let key = rate_limiter.http_client_key(&headers(&[]));
+        assert_eq!(key, UNIDENTIFIED_HTTP_CLIENT_KEY);
+
+        // Forwarded headers are ignored if the server isn't behind a trusted proxy.
+        let rate_limiter = self::rate_limiter(false);
+        let key = rate_limiter.http_client_key(&forwarded_headers);
+        assert_eq!(key, UNIDENTIFIED_HTTP_CLIENT_KEY);
+    }
+
+    #[test]
+    fn checking_rate_limit() {
+        let method_weights =
+            HashMap::from([("eth_call".to_owned(), 3), ("net_version".to_owned(), 0)]);
+        let rate_limiter = ApiRateLimiter::new(5, method_weights, ClientIdentification::default());
+
+        rate_limiter
+            .check("ip:10.0.0.1", ["eth_call"], "http")
+            .unwrap();
+        let err = rate_limiter
+            .check("ip:10.0.0.1", ["eth_call"], "http")
+            .unwrap_err();
+        assert!(matches!(err, Web3Error::RateLimitExceeded), "{err:?}");
+        // The remaining quota can still be used by cheaper calls.
+        rate_limiter
+            .check("ip:10.0.0.1", ["eth_chainId", "eth_chainId"], "http")
+            .unwrap();
+        rate_limiter
+            .check("ip:10.0.0.1", ["eth_chainId"], "http")
+            .unwrap_err();
+        // Calls with zero weight are never limited.
+        rate_limiter
+            .check("ip:10.0.0.1", ["net_version"], "http")
+            .unwrap();
+
+        // Other clients have independent quotas.
+        rate_limiter
+            .check("ip:10.0.0.2", ["eth_call"], "http")
+            .unwrap();
+        // Batches are charged for all their calls.
+        rate_limiter
+            .check("ip:10.0.0.3", ["eth_call", "eth_call"], "http")
+            .unwrap_err();
+        rate_limiter
+            .check("ip:10.0.0.3", ["eth_call"], "http")
+            .unwrap();
+        // WebSocket connections have independent quotas.
+        let ws_key = ApiRateLimiter::ws_client_key(1);
+        rate_limiter.check(&ws_key, ["eth_call"], "ws").unwrap();
+        rate_limiter.check(&ws_key, ["eth_call"], "ws").unwrap_err();
+        let other_ws_key = ApiRateLimiter::ws_client_key(2);
+        rate_limiter
+            .check(&other_ws_key, ["eth_call"], "ws")
+            .unwrap();
+    }
+}
@@ -4,11 +4,14 @@ use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
 
+use once_cell::sync::OnceCell;
 use tokio::sync::RwLock;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
 
 use crate::api_server::tx_sender::TxSender;
 use crate::api_server::web3::{backend_jsonrpc::error::internal_error, resolve_block};
+use crate::metadata_calculator::AsyncTreeReader;
+use crate::state_keeper::PendingState;
 use crate::sync_layer::SyncState;
 
 use zksync_dal::ConnectionPool;
@@ -16,7 +19,7 @@ use zksync_eth_signer::PrivateKeySigner;
 
 use zksync_types::{
     api, l2::L2Tx, transaction_request::CallRequest, Address, L1ChainId, L2ChainId,
-    MiniblockNumber, H256, U256, U64,
+    MiniblockNumber, StorageKey, H256, U256, U64,
 };
 use zksync_web3_decl::{
     error::Web3Error,
@@ -73,6 +76,10 @@ pub struct RpcState<E> {
     pub connection_pool: ConnectionPool,
     pub tx_sender: TxSender<E>,
     pub sync_state: Option<SyncState>,
+    /// Set once the co-located Merkle tree is initialized.
+    pub tree_reader: Arc<OnceCell<AsyncTreeReader>>,
+    /// Changes made by the co-located state keeper that are not persisted yet, if any.
+    pub(crate) pending_state: Option<PendingState>,
     pub(super) api_config: InternalApiConfig,
     pub accounts: HashMap<Address, PrivateKeySigner>,
     #[cfg(feature = "openzeppelin_tests")]
@@ -89,6 +96,8 @@ impl<E> Clone for RpcState<E> {
             connection_pool: self.connection_pool.clone(),
             tx_sender: self.tx_sender.clone(),
             sync_state: self.sync_state.clone(),
+            tree_reader: self.tree_reader.clone(),
+            pending_state: self.pending_state.clone(),
             api_config: self.api_config.clone(),
             accounts: self.accounts.clone(),
             #[cfg(feature = "openzeppelin_tests")]
@@ -106,6 +115,15 @@ impl<E> RpcState<E> {
         Ok((tx_request.try_into()?, hash))
     }
 
+    /// Returns the value at the specified `key` changed by the co-located state keeper, provided that
+    /// `block` is the pending block. `None` means that the value should be read from Postgres.
+    pub fn pending_storage_value(&self, block: api::BlockId, key: &StorageKey) -> Option<H256> {
+        if !matches!(block, api::BlockId::Number(api::BlockNumber::Pending)) {
+            return None;
+        }
+        self.pending_state.as_ref()?.read_value(key)
+    }
+
     pub fn u64_to_block_number(n: U64) -> MiniblockNumber {
         if n.as_u64() > u32::MAX as u64 {
             MiniblockNumber(u32::MAX)
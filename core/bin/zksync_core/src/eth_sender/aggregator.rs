@@ -1,3 +1,5 @@
+use std::{fmt, sync::Arc};
+
 use zksync_config::configs::eth_sender::{ProofSendingMode, SenderConfig};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::StorageProcessor;
@@ -13,8 +15,9 @@ use zksync_types::{
 
 use crate::eth_sender::block_publish_criterion::{
     BlockNumberCriterion, BlockPublishCriterion, DataSizeCriterion, GasCriterion,
-    TimestampDeadlineCriterion,
+    GasPriceCriterion, TimestampDeadlineCriterion,
 };
+use crate::l1_gas_price::L1TxParamsProvider;
 
 #[derive(Debug)]
 pub struct Aggregator {
@@ -26,26 +29,9 @@ pub struct Aggregator {
 
 impl Aggregator {
     pub fn new(config: SenderConfig) -> Self {
+        let commit_deadline_criterion = Self::commit_deadline_criterion(&config);
         Self {
-            commit_criterion: vec![
-                Box::from(BlockNumberCriterion {
-                    op: AggregatedActionType::CommitBlocks,
-                    limit: config.max_aggregated_blocks_to_commit,
-                }),
-                Box::from(GasCriterion::new(
-                    AggregatedActionType::CommitBlocks,
-                    config.max_aggregated_tx_gas,
-                )),
-                Box::from(DataSizeCriterion {
-                    op: AggregatedActionType::CommitBlocks,
-                    data_limit: config.max_eth_tx_data_size,
-                }),
-                Box::from(TimestampDeadlineCriterion {
-                    op: AggregatedActionType::CommitBlocks,
-                    deadline_seconds: config.aggregated_block_commit_deadline,
-                    max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
-                }),
-            ],
+            commit_criterion: Self::commit_criteria(&config, Box::from(commit_deadline_criterion)),
             proof_criterion: vec![
                 Box::from(BlockNumberCriterion {
                     op: AggregatedActionType::PublishProofBlocksOnchain,
@@ -83,6 +69,57 @@ impl Aggregator {
         }
     }
 
+    /// Makes commit operations take the L1 gas price into account: while the L1 base fee spikes,
+    /// commits are postponed (see [`GasPriceCriterion`] for details). Does nothing if
+    /// `aggregated_block_commit_fee_spike_ratio` is not set in the config.
+    pub fn with_gas_price_criterion<G>(mut self, gas_price_provider: Arc<G>) -> Self
+    where
+        G: L1TxParamsProvider + fmt::Debug + Send + Sync + 'static,
+    {
+        let Some(spike_ratio) = self.config.aggregated_block_commit_fee_spike_ratio else {
+            return self;
+        };
+        let max_delay_seconds = self.config.aggregated_block_commit_max_fee_spike_delay();
+
+        let gas_price_criterion = GasPriceCriterion::new(
+            gas_price_provider,
+            spike_ratio,
+            max_delay_seconds,
+            Self::commit_deadline_criterion(&self.config),
+        );
+        self.commit_criterion = Self::commit_criteria(&self.config, Box::from(gas_price_criterion));
+        self
+    }
+
+    fn commit_criteria(
+        config: &SenderConfig,
+        deadline_criterion: Box<dyn BlockPublishCriterion>,
+    ) -> Vec<Box<dyn BlockPublishCriterion>> {
+        vec![
+            Box::from(BlockNumberCriterion {
+                op: AggregatedActionType::CommitBlocks,
+                limit: config.max_aggregated_blocks_to_commit,
+            }),
+            Box::from(GasCriterion::new(
+                AggregatedActionType::CommitBlocks,
+                config.max_aggregated_tx_gas,
+            )),
+            Box::from(DataSizeCriterion {
+                op: AggregatedActionType::CommitBlocks,
+                data_limit: config.max_eth_tx_data_size,
+            }),
+            deadline_criterion,
+        ]
+    }
+
+    fn commit_deadline_criterion(config: &SenderConfig) -> TimestampDeadlineCriterion {
+        TimestampDeadlineCriterion {
+            op: AggregatedActionType::CommitBlocks,
+            deadline_seconds: config.aggregated_block_commit_deadline,
+            max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
+        }
+    }
+
     pub async fn get_next_ready_operation(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -1,6 +1,8 @@
 use crate::gas_tracker::agg_block_base_cost;
+use crate::l1_gas_price::L1TxParamsProvider;
 use async_trait::async_trait;
 use chrono::Utc;
+use std::{fmt, mem, sync::Arc};
 use zksync_dal::StorageProcessor;
 use zksync_types::commitment::BlockWithMetadata;
 use zksync_types::{aggregated_operations::AggregatedActionType, L1BatchNumber};
@@ -254,3 +256,107 @@ impl BlockPublishCriterion for DataSizeCriterion {
         "data_size"
     }
 }
+
+/// Criterion that takes the L1 gas price into account. While the L1 base fee spikes (i.e., the base fee
+/// of the last L1 block exceeds the median base fee more than `spike_ratio` times), publishing is postponed,
+/// so that blocks are packed into larger ranges. Once the spike is over, all postponed blocks are published
+/// immediately. Publishing cannot be postponed for longer than `max_delay_seconds` since the oldest block.
+///
+/// Outside of spikes, the criterion delegates to the wrapped deadline criterion. Hence, it should be used
+/// *instead* of a `TimestampDeadlineCriterion`; otherwise, the deadline would trigger publishing during spikes.
+#[derive(Debug)]
+pub struct GasPriceCriterion<G> {
+    op: AggregatedActionType,
+    gas_price_provider: Arc<G>,
+    spike_ratio: f64,
+    max_delay_seconds: u64,
+    deadline_criterion: TimestampDeadlineCriterion,
+    // Whether a base fee spike was detected during the previous check.
+    is_spike: bool,
+}
+
+impl<G: L1TxParamsProvider> GasPriceCriterion<G> {
+    pub fn new(
+        gas_price_provider: Arc<G>,
+        spike_ratio: f64,
+        max_delay_seconds: u64,
+        deadline_criterion: TimestampDeadlineCriterion,
+    ) -> Self {
+        Self {
+            op: deadline_criterion.op,
+            gas_price_provider,
+            spike_ratio,
+            max_delay_seconds,
+            deadline_criterion,
+            is_spike: false,
+        }
+    }
+
+    fn check_spike(&self) -> bool {
+        let median_base_fee = self.gas_price_provider.get_median_base_fee();
+        let last_block_base_fee = self.gas_price_provider.get_last_block_base_fee();
+        last_block_base_fee as f64 > median_base_fee as f64 * self.spike_ratio
+    }
+}
+
+#[async_trait]
+impl<G> BlockPublishCriterion for GasPriceCriterion<G>
+where
+    G: L1TxParamsProvider + fmt::Debug + Send + Sync + 'static,
+{
+    async fn last_block_to_publish(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        consecutive_blocks: &[BlockWithMetadata],
+        last_sealed_block: L1BatchNumber,
+    ) -> Option<L1BatchNumber> {
+        let first_block = consecutive_blocks.first()?;
+        // The spike status is only updated if there are blocks to publish, so that blocks postponed
+        // because of a spike are published once it's over.
+        let is_spike = self.check_spike();
+        let was_spike = mem::replace(&mut self.is_spike, is_spike);
+
+        if is_spike {
+            let oldest_block_age_seconds =
+                (Utc::now().timestamp() as u64).saturating_sub(first_block.header.timestamp);
+            if oldest_block_age_seconds < self.max_delay_seconds {
+                vlog::debug!(
+                    "{} block range starting from {}: publishing postponed because of L1 base fee spike",
+                    self.op.to_string(),
+                    first_block.header.number.0
+                );
+                return None;
+            }
+        } else if !was_spike {
+            return self
+                .deadline_criterion
+                .last_block_to_publish(storage, consecutive_blocks, last_sealed_block)
+                .await;
+        }
+
+        let result = consecutive_blocks.last().unwrap().header.number;
+        let spike_status = if is_spike {
+            "max delay reached"
+        } else {
+            "over"
+        };
+        vlog::debug!(
+            "{} block range {}-{}: GAS PRICE triggered (base fee spike: {})",
+            self.op.to_string(),
+            first_block.header.number.0,
+            result.0,
+            spike_status
+        );
+        metrics::counter!(
+            "server.eth_sender.block_aggregation_reason",
+            1,
+            "type" => "gas_price",
+            "op" => self.op.to_string()
+        );
+        Some(result)
+    }
+
+    fn name(&self) -> &'static str {
+        "gas_price"
+    }
+}
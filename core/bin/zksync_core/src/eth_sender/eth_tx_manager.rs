@@ -14,7 +14,7 @@ use zksync_types::{
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use crate::eth_sender::ETHSenderError;
+use crate::eth_sender::{tx_status_webhook::TxStatusWebhook, ETHSenderError};
 use crate::{eth_sender::grafana_metrics::track_eth_tx_metrics, l1_gas_price::L1TxParamsProvider};
 
 #[derive(Debug)]
@@ -47,6 +47,7 @@ pub struct EthTxManager<E, G> {
     ethereum_gateway: E,
     config: SenderConfig,
     gas_adjuster: Arc<G>,
+    tx_status_webhook: Option<TxStatusWebhook>,
 }
 
 impl<E, G> EthTxManager<E, G>
@@ -57,6 +58,7 @@ where
     pub fn new(config: SenderConfig, gas_adjuster: Arc<G>, ethereum_gateway: E) -> Self {
         Self {
             ethereum_gateway,
+            tx_status_webhook: TxStatusWebhook::new(&config),
             config,
             gas_adjuster,
         }
@@ -110,7 +112,7 @@ where
         let priority_fee_per_gas = if time_in_mempool != 0 {
             metrics::increment_counter!("server.eth_sender.transaction_resent");
             let priority_fee_per_gas = self
-                .increase_priority_fee(storage, tx.id, base_fee_per_gas)
+                .increase_priority_fee(storage, tx.id, base_fee_per_gas, time_in_mempool)
                 .await?;
             vlog::info!(
                 "Resending operation {} with base fee {:?} and priority fee {:?}",
@@ -120,7 +122,7 @@ where
             );
             priority_fee_per_gas
         } else {
-            self.gas_adjuster.get_priority_fee()
+            self.choose_priority_fee(time_in_mempool)
         };
 
         // Extra check to prevent sending transaction will extremely high priority fee.
@@ -138,11 +140,33 @@ where
         })
     }
 
+    /// Chooses the priority fee from the percentiles of priority fees paid in the recent L1 blocks:
+    /// the longer the transaction stays in the mempool, the higher percentile is used. The default
+    /// priority fee is used as the lower bound, and if percentiles are not tracked by the gas adjuster.
+    fn choose_priority_fee(&self, time_in_mempool: u32) -> u64 {
+        let default_fee = self.gas_adjuster.get_priority_fee();
+        let percentiles = self.gas_adjuster.get_priority_fee_percentiles();
+        match percentiles.last() {
+            Some(&max_fee) => {
+                let fee = percentiles
+                    .get(time_in_mempool as usize)
+                    .copied()
+                    .unwrap_or(max_fee);
+                // Fees observed on L1 may spike; we don't want to trip the safeguard
+                // in `calculate_fee()` because of that.
+                fee.max(default_fee)
+                    .min(self.config.max_acceptable_priority_fee_in_gwei)
+            }
+            None => default_fee,
+        }
+    }
+
     async fn increase_priority_fee(
         &self,
         storage: &mut StorageProcessor<'_>,
         eth_tx_id: u32,
         base_fee_per_gas: u64,
+        time_in_mempool: u32,
     ) -> Result<u64, ETHSenderError> {
         let previous_sent_tx = storage
             .eth_sender_dal()
@@ -170,7 +194,7 @@ where
 
         // Increase `priority_fee_per_gas` by at least 20% to prevent "replacement transaction underpriced" error.
         Ok((previous_priority_fee + (previous_priority_fee / 5) + 1)
-            .max(self.gas_adjuster.get_priority_fee()))
+            .max(self.choose_priority_fee(time_in_mempool)))
     }
 
     pub(crate) async fn send_eth_tx(
@@ -549,6 +573,10 @@ where
             (tx_status.receipt.block_number.unwrap().as_u32() - sent_at_block) as f64,
             "type" => tx.tx_type.to_string()
         );
+
+        if let Some(webhook) = &self.tx_status_webhook {
+            webhook.notify(storage, tx).await;
+        }
     }
 
     pub async fn run(mut self, pool: ConnectionPool, stop_receiver: watch::Receiver<bool>) {
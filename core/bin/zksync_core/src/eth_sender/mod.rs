@@ -5,6 +5,7 @@ mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
 mod grafana_metrics;
+mod tx_status_webhook;
 mod zksync_functions;
 
 #[cfg(test)]
@@ -10,7 +10,8 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{clients::mock::MockEthereum, EthInterface};
 use zksync_types::{
     aggregated_operations::{
-        AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation, BlocksProofOperation,
+        AggregatedActionType, AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation,
+        BlocksProofOperation,
     },
     block::L1BatchHeader,
     commitment::{BlockMetaParameters, BlockMetadata, BlockWithMetadata},
@@ -19,7 +20,11 @@ use zksync_types::{
 };
 
 use crate::eth_sender::{
-    eth_tx_manager::L1BlockNumbers, Aggregator, EthTxAggregator, EthTxManager,
+    block_publish_criterion::{
+        BlockPublishCriterion, GasPriceCriterion, TimestampDeadlineCriterion,
+    },
+    eth_tx_manager::L1BlockNumbers,
+    Aggregator, EthTxAggregator, EthTxManager,
 };
 use crate::l1_gas_price::GasAdjuster;
 
@@ -266,6 +271,61 @@ async fn resend_each_block(connection_pool: ConnectionPool) -> anyhow::Result<()
     Ok(())
 }
 
+// Tests that priority fees are chosen from the percentiles tracked by the gas adjuster if they are configured,
+// with the default priority fee used as the lower bound.
+#[db_test]
+async fn priority_fee_percentiles(connection_pool: ConnectionPool) -> anyhow::Result<()> {
+    let mut tester = EthSenderTester::new(connection_pool, vec![7, 6, 5, 5, 5, 2, 1], false).await;
+    *tester.gateway.reward_history.write().unwrap() = vec![vec![10, 50, 90]; 20];
+    tester.gateway.advance_block_number(3);
+
+    // median base fee is 6
+    let eth_sender_config = ETHSenderConfig::from_env();
+    let gas_adjuster = GasAdjuster::new(
+        tester.gateway.clone(),
+        GasAdjusterConfig {
+            max_base_fee_samples: EthSenderTester::MAX_BASE_FEE_SAMPLES,
+            pricing_formula_parameter_a: 3.0,
+            pricing_formula_parameter_b: 2.0,
+            default_priority_fee_per_gas: 20,
+            priority_fee_percentiles: vec![10.0, 50.0, 90.0],
+            ..eth_sender_config.gas_adjuster
+        },
+    )
+    .await
+    .unwrap();
+    let mut manager = EthTxManager::new(
+        eth_sender_config.sender,
+        Arc::new(gas_adjuster),
+        tester.gateway.clone(),
+    );
+
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+        .await?;
+
+    // The longer the transaction stays in the mempool, the higher percentile is used.
+    // The 10th percentile is below the default priority fee, so the default fee is used instead.
+    let expected_fees = [(18, 20), (36, 50), (72, 90)];
+    for (time_in_mempool, (base_fee, priority_fee)) in expected_fees.into_iter().enumerate() {
+        let hash = manager
+            .send_eth_tx(
+                &mut tester.storage().await,
+                &tx,
+                time_in_mempool as u32,
+                block,
+            )
+            .await?;
+        let sent_tx = tester.gateway.sent_txs.read().unwrap()[&hash];
+        assert_eq!(sent_tx.base_fee.as_u64(), base_fee); // 6 * 3 * 2^time_in_mempool
+        assert_eq!(sent_tx.priority_fee.as_u64(), priority_fee);
+    }
+
+    Ok(())
+}
+
 // Tests that if transaction was mined, but not enough blocks has been mined since,
 // we won't mark it as confirmed but also won't resend it.
 #[db_test]
@@ -585,6 +645,77 @@ async fn skipped_block_in_the_middle(connection_pool: ConnectionPool) -> anyhow:
     Ok(())
 }
 
+#[db_test]
+async fn gas_price_criterion(connection_pool: ConnectionPool) -> anyhow::Result<()> {
+    let tester = EthSenderTester::new(connection_pool, vec![10, 10, 10, 50, 10, 10], false).await;
+    let mut storage = tester.storage().await;
+    let mut criterion = GasPriceCriterion::new(
+        tester.gas_adjuster.clone(),
+        2.0,
+        600,
+        TimestampDeadlineCriterion {
+            op: AggregatedActionType::CommitBlocks,
+            deadline_seconds: 3_600,
+            max_allowed_lag: None,
+        },
+    );
+
+    let now = unix_timestamp_ms() / 1_000;
+    let blocks_with_timestamp = |timestamp: u64| -> Vec<_> {
+        (1..=3)
+            .map(|number| {
+                let header = L1BatchHeader::new(
+                    L1BatchNumber(number),
+                    timestamp,
+                    Address::zero(),
+                    BaseSystemContractsHashes::default(),
+                );
+                block_metadata(&header)
+            })
+            .collect()
+    };
+    let fresh_blocks = blocks_with_timestamp(now);
+    let old_blocks = blocks_with_timestamp(now - 1_000);
+    let last_sealed_block = L1BatchNumber(3);
+
+    // No base fee spike; the deadline is not reached.
+    tester.gateway.advance_block_number(3);
+    tester.gas_adjuster.keep_updated().await?;
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &fresh_blocks, last_sealed_block)
+        .await;
+    assert_eq!(last_block, None);
+
+    // Base fee spike: publishing is postponed, unless the max delay is reached.
+    tester.gateway.advance_block_number(1);
+    tester.gas_adjuster.keep_updated().await?;
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &fresh_blocks, last_sealed_block)
+        .await;
+    assert_eq!(last_block, None);
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &old_blocks, last_sealed_block)
+        .await;
+    assert_eq!(last_block, Some(last_sealed_block));
+
+    // Spike is over: postponed blocks are published immediately, but only once.
+    tester.gateway.advance_block_number(1);
+    tester.gas_adjuster.keep_updated().await?;
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &[], last_sealed_block)
+        .await;
+    assert_eq!(last_block, None);
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &fresh_blocks, last_sealed_block)
+        .await;
+    assert_eq!(last_block, Some(last_sealed_block));
+    let last_block = criterion
+        .last_block_to_publish(&mut storage, &fresh_blocks, last_sealed_block)
+        .await;
+    assert_eq!(last_block, None);
+    Ok(())
+}
+
 async fn insert_block(tester: &mut EthSenderTester, number: L1BatchNumber) -> L1BatchHeader {
     let mut block = L1BatchHeader::new(
         number,
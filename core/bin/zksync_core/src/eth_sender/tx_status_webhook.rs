@@ -0,0 +1,105 @@
+use reqwest::{Client, Url};
+
+use std::{str::FromStr, time::Duration};
+
+use zksync_config::configs::eth_sender::SenderConfig;
+use zksync_dal::StorageProcessor;
+use zksync_types::{eth_sender::EthTx, Address, H256};
+
+/// Timeout for a single webhook request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reports watched transactions and transactions of the watched accounts to an external HTTP endpoint
+/// once the L1 batches containing them are committed, proven or executed. Each report is a POST request with a JSON array
+/// of `TxStatusUpdate`s for a single confirmed L1 transaction.
+///
+/// Delivery is best-effort: failed requests are logged, but not retried.
+#[derive(Debug)]
+pub(super) struct TxStatusWebhook {
+    client: Client,
+    url: Url,
+    tx_hashes: Vec<H256>,
+    addresses: Vec<Address>,
+}
+
+impl TxStatusWebhook {
+    pub fn new(config: &SenderConfig) -> Option<Self> {
+        let url = config.tx_status_webhook_url.as_ref()?;
+        if config.tx_status_webhook_tx_hashes.is_empty()
+            && config.tx_status_webhook_addresses.is_empty()
+        {
+            vlog::warn!(
+                "Tx status webhook URL is set, but there are no watched transactions or addresses"
+            );
+            return None;
+        }
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to build HTTP client for tx status webhook");
+        Some(Self {
+            client,
+            url: Url::from_str(url).expect("failed to parse tx status webhook URL"),
+            tx_hashes: config.tx_status_webhook_tx_hashes.clone(),
+            addresses: config.tx_status_webhook_addresses.clone(),
+        })
+    }
+
+    /// Sends updates for the L1 batches affected by the confirmed `tx`. The request is sent
+    /// in the background, so that it doesn't hold up the eth tx manager. Errors are logged
+    /// rather than propagated, since reporting is best-effort.
+    pub async fn notify(&self, storage: &mut StorageProcessor<'_>, tx: &EthTx) {
+        let l1_batches = storage.blocks_dal().get_blocks_for_eth_tx_id(tx.id).await;
+        let (Some(first_batch), Some(last_batch)) = (l1_batches.first(), l1_batches.last()) else {
+            return;
+        };
+        let updates = storage
+            .blocks_web3_dal()
+            .get_tx_status_updates(
+                first_batch.number..=last_batch.number,
+                tx.tx_type.into(),
+                &self.tx_hashes,
+                &self.addresses,
+            )
+            .await;
+        let updates = match updates {
+            Ok(updates) => updates,
+            Err(err) => {
+                vlog::warn!(
+                    "Failed loading tx status updates for eth_tx {}: {err}",
+                    tx.id
+                );
+                return;
+            }
+        };
+        if updates.is_empty() {
+            return;
+        }
+
+        let request = self.client.post(self.url.clone()).json(&updates);
+        let tx_type = tx.tx_type.to_string();
+        let eth_tx_id = tx.id;
+        tokio::spawn(async move {
+            let response = request
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            let result = match response {
+                Ok(_) => "ok",
+                Err(err) => {
+                    vlog::warn!(
+                        "Failed reporting {} tx status updates for eth_tx {eth_tx_id} to webhook: {err}",
+                        updates.len()
+                    );
+                    "error"
+                }
+            };
+            metrics::increment_counter!(
+                "server.eth_sender.tx_status_webhook",
+                "type" => tx_type,
+                "result" => result
+            );
+        });
+    }
+}
@@ -0,0 +1,315 @@
+use async_trait::async_trait;
+
+use std::str::FromStr;
+
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory};
+use zksync_types::L1BatchNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Prefixes of keys for blobs associated with a specific L1 batch. Such keys have
+/// the `{prefix}{l1_batch_number}[_{suffix}].bin` format, e.g. `basic_circuits_123.bin`
+/// or `123_0_Main VM_BasicCircuits.bin` for the empty prefix.
+const L1_BATCH_KEY_PREFIXES: &[&str] = &[
+    "",
+    "merkel_tree_paths_",
+    "basic_circuits_",
+    "basic_circuits_inputs_",
+    "scheduler_witness_",
+    "final_node_aggregations_",
+    "aggregation_outputs_",
+    "leaf_layer_subqueues_",
+    "closed_form_inputs_",
+    "aggregations_",
+    "aux_output_witness_",
+];
+
+/// Extracts the L1 batch number from the blob key. Returns `None` for blobs not associated
+/// with a specific L1 batch.
+fn l1_batch_number_from_key(bucket: Bucket, key: &str) -> Option<L1BatchNumber> {
+    // FRI proofs are keyed by the proof job ID rather than by the L1 batch number.
+    if bucket == Bucket::ProofsFri {
+        return None;
+    }
+
+    L1_BATCH_KEY_PREFIXES.iter().find_map(|prefix| {
+        let rest = key.strip_prefix(prefix)?;
+        let digits_len = rest.find(|ch: char| !ch.is_ascii_digit())?;
+        let (number, suffix) = rest.split_at(digits_len);
+        if number.is_empty() || !(suffix.starts_with('_') || suffix == ".bin") {
+            return None;
+        }
+        number.parse().ok().map(L1BatchNumber)
+    })
+}
+
+/// Retention policy for an object store bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobRetentionPolicy {
+    pub bucket: Bucket,
+    /// Number of the latest L1 batches proven on L1 for which blobs are retained. Blobs of L1 batches
+    /// that are not proven yet are always retained.
+    pub retained_l1_batches: u32,
+}
+
+impl FromStr for BlobRetentionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (bucket, retained_l1_batches) = s.split_once(':').ok_or_else(|| {
+            format!("retention policy `{s}` is not in the `{{bucket}}:{{l1_batch_count}}` format")
+        })?;
+        Ok(Self {
+            bucket: bucket.parse()?,
+            retained_l1_batches: retained_l1_batches.parse().map_err(|err| {
+                format!("invalid L1 batch count in retention policy `{s}`: {err}")
+            })?,
+        })
+    }
+}
+
+/// Removes blobs of old L1 batches according to the retention policies. Unlike [`GcsBlobCleaner`],
+/// the sweeper lists blobs in the object store, so it removes blobs regardless of whether they
+/// are tracked in the database.
+///
+/// [`GcsBlobCleaner`]: crate::house_keeper::gcs_blob_cleaner::GcsBlobCleaner
+#[derive(Debug)]
+pub struct BlobRetentionSweeper {
+    object_store: Box<dyn ObjectStore>,
+    policies: Vec<BlobRetentionPolicy>,
+    sweeping_interval_ms: u64,
+    pool: ConnectionPool,
+}
+
+impl BlobRetentionSweeper {
+    pub async fn new(
+        store_factory: &ObjectStoreFactory,
+        pool: ConnectionPool,
+        policies: Vec<BlobRetentionPolicy>,
+        sweeping_interval_ms: u64,
+    ) -> Self {
+        Self {
+            object_store: store_factory.create_store().await,
+            policies,
+            sweeping_interval_ms,
+            pool,
+        }
+    }
+}
+
+/// Removes blobs of L1 batches that are more than `policy.retained_l1_batches` batches older than
+/// `last_proven_l1_batch` from the bucket. Keys are listed page by page, so that the bucket
+/// contents are never loaded in full. Returns the number of removed blobs.
+async fn sweep_bucket(
+    object_store: &dyn ObjectStore,
+    policy: BlobRetentionPolicy,
+    last_proven_l1_batch: L1BatchNumber,
+) -> u64 {
+    let BlobRetentionPolicy {
+        bucket,
+        retained_l1_batches,
+    } = policy;
+    let Some(first_retained_l1_batch) = last_proven_l1_batch.0.checked_sub(retained_l1_batches)
+    else {
+        return 0;
+    };
+
+    let mut removed_count = 0_u64;
+    let mut start_after: Option<String> = None;
+    loop {
+        let keys = match object_store
+            .list_raw(bucket, "", start_after.as_deref())
+            .await
+        {
+            Ok(keys) => keys,
+            Err(err) => {
+                vlog::warn!("Failed listing blobs in bucket {bucket}: {err}");
+                break;
+            }
+        };
+        let expired_keys = keys.iter().filter(|key| {
+            l1_batch_number_from_key(bucket, key)
+                .map_or(false, |number| number.0 < first_retained_l1_batch)
+        });
+        for key in expired_keys {
+            match object_store.remove_raw(bucket, key).await {
+                // The blob may have been removed concurrently, e.g. by `GcsBlobCleaner`.
+                Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => removed_count += 1,
+                Err(err) => vlog::warn!("Failed removing blob {key} from bucket {bucket}: {err}"),
+            }
+        }
+
+        let Some(last_key) = keys.into_iter().last() else {
+            break;
+        };
+        start_after = Some(last_key);
+    }
+
+    if removed_count > 0 {
+        vlog::info!(
+            "Removed {removed_count} blobs for L1 batches before #{first_retained_l1_batch} \
+             from bucket {bucket}"
+        );
+    }
+    metrics::counter!(
+        "server.house_keeper.blob_retention_sweeper.removed_blobs",
+        removed_count,
+        "bucket" => bucket.to_string()
+    );
+    removed_count
+}
+
+#[async_trait]
+impl PeriodicJob for BlobRetentionSweeper {
+    const SERVICE_NAME: &'static str = "BlobRetentionSweeper";
+
+    async fn run_routine_task(&mut self) {
+        // Blobs may be needed until the L1 batch is proven, so retention is based on proven batches.
+        let last_proven_l1_batch = self
+            .pool
+            .access_storage()
+            .await
+            .blocks_dal()
+            .get_number_of_last_block_proven_on_eth()
+            .await;
+        let Some(last_proven_l1_batch) = last_proven_l1_batch else {
+            return;
+        };
+        for &policy in &self.policies {
+            sweep_bucket(&*self.object_store, policy, last_proven_l1_batch).await;
+        }
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.sweeping_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_object_store::LIST_PAGE_SIZE;
+
+    #[test]
+    fn extracting_l1_batch_number_from_key() {
+        let batch_keys = [
+            (Bucket::ProverJobs, "basic_circuits_123.bin"),
+            (Bucket::ProverJobs, "basic_circuits_inputs_123.bin"),
+            (Bucket::WitnessInput, "merkel_tree_paths_123.bin"),
+            (Bucket::SchedulerWitnessJobs, "scheduler_witness_123.bin"),
+            (Bucket::ProverJobs, "123_0_Main VM_BasicCircuits.bin"),
+            (Bucket::ProverJobsFri, "123_0_1_BasicCircuits_0.bin"),
+            (
+                Bucket::LeafAggregationWitnessJobsFri,
+                "closed_form_inputs_123_1.bin",
+            ),
+            (Bucket::SchedulerWitnessJobsFri, "123.bin"),
+        ];
+        for (bucket, key) in batch_keys {
+            assert_eq!(
+                l1_batch_number_from_key(bucket, key),
+                Some(L1BatchNumber(123)),
+                "{bucket}/{key}"
+            );
+        }
+
+        let other_keys = [
+            (Bucket::ProverJobs, "other.bin"),
+            (Bucket::ProverJobs, "basic_circuits_.bin"),
+            (Bucket::ProverJobs, "basic_circuits_12a.bin"),
+            (Bucket::ProverJobs, "123"),
+            (Bucket::ProverJobs, "123-0.bin"),
+            // FRI proofs are keyed by the job ID.
+            (Bucket::ProofsFri, "123.bin"),
+            (Bucket::ProofsFri, "proof_123.bin"),
+        ];
+        for (bucket, key) in other_keys {
+            assert_eq!(
+                l1_batch_number_from_key(bucket, key),
+                None,
+                "{bucket}/{key}"
+            );
+        }
+    }
+
+    #[test]
+    fn parsing_retention_policy() {
+        let policy: BlobRetentionPolicy = "prover_jobs:1000".parse().unwrap();
+        assert_eq!(
+            policy,
+            BlobRetentionPolicy {
+                bucket: Bucket::ProverJobs,
+                retained_l1_batches: 1_000,
+            }
+        );
+        let policy: BlobRetentionPolicy = "proofs_fri:0".parse().unwrap();
+        assert_eq!(policy.bucket, Bucket::ProofsFri);
+        assert_eq!(policy.retained_l1_batches, 0);
+
+        for invalid_policy in [
+            "prover_jobs",
+            "unknown:10",
+            "prover_jobs:-1",
+            "prover_jobs:",
+        ] {
+            let err = invalid_policy.parse::<BlobRetentionPolicy>().unwrap_err();
+            assert!(!err.is_empty(), "{invalid_policy}");
+        }
+    }
+
+    #[tokio::test]
+    async fn sweeping_bucket() {
+        let object_store = ObjectStoreFactory::mock().create_store().await;
+        let l1_batch_count = LIST_PAGE_SIZE as u32 * 3 / 2;
+        for number in 0..l1_batch_count {
+            object_store
+                .put_raw(Bucket::ProverJobs, &format!("{number}.bin"), vec![1])
+                .await
+                .unwrap();
+        }
+        object_store
+            .put_raw(Bucket::ProverJobs, "other.bin", vec![1])
+            .await
+            .unwrap();
+        object_store
+            .put_raw(Bucket::WitnessInput, "merkel_tree_paths_0.bin", vec![1])
+            .await
+            .unwrap();
+
+        let policy = BlobRetentionPolicy {
+            bucket: Bucket::ProverJobs,
+            retained_l1_batches: 100,
+        };
+        let last_proven_l1_batch = L1BatchNumber(l1_batch_count - 1);
+        let removed_count = sweep_bucket(&*object_store, policy, last_proven_l1_batch).await;
+        let first_retained_l1_batch = last_proven_l1_batch.0 - 100;
+        assert_eq!(removed_count, u64::from(first_retained_l1_batch));
+
+        for number in [0, first_retained_l1_batch - 1] {
+            let err = object_store
+                .get_raw(Bucket::ProverJobs, &format!("{number}.bin"))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        }
+        for key in [
+            format!("{first_retained_l1_batch}.bin"),
+            format!("{}.bin", last_proven_l1_batch.0),
+            "other.bin".to_owned(),
+        ] {
+            object_store
+                .get_raw(Bucket::ProverJobs, &key)
+                .await
+                .unwrap();
+        }
+        object_store
+            .get_raw(Bucket::WitnessInput, "merkel_tree_paths_0.bin")
+            .await
+            .unwrap();
+
+        // Nothing is removed if there are not enough proven L1 batches.
+        let removed_count = sweep_bucket(&*object_store, policy, L1BatchNumber(99)).await;
+        assert_eq!(removed_count, 0);
+    }
+}
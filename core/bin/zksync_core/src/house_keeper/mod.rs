@@ -1,3 +1,4 @@
+pub mod blob_retention_sweeper;
 pub mod blocks_state_reporter;
 pub mod fri_prover_job_retry_manager;
 pub mod fri_prover_queue_monitor;
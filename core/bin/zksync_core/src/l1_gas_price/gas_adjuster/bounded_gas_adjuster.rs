@@ -41,4 +41,12 @@ impl<G: L1GasPriceProvider> L1GasPriceProvider for BoundedGasAdjuster<G> {
         }
         default_gas_price
     }
+
+    fn estimate_blob_base_fee(&self) -> u64 {
+        self.default_gas_adjuster.estimate_blob_base_fee()
+    }
+
+    fn get_priority_fee_percentiles(&self) -> Vec<u64> {
+        self.default_gas_adjuster.get_priority_fee_percentiles()
+    }
 }
@@ -6,7 +6,10 @@ use std::sync::{Arc, RwLock};
 use tokio::sync::watch::Receiver;
 
 use zksync_config::GasAdjusterConfig;
-use zksync_eth_client::{types::Error, EthInterface};
+use zksync_eth_client::{
+    types::{BlockFees, Error},
+    EthInterface,
+};
 
 use super::{L1GasPriceProvider, L1TxParamsProvider};
 
@@ -15,16 +18,38 @@ pub mod bounded_gas_adjuster;
 mod tests;
 
 /// This component keeps track of the median base_fee from the last `max_base_fee_samples` blocks.
-/// It is used to adjust the base_fee of transactions sent to L1.
+/// It is used to adjust the base_fee of transactions sent to L1. Additionally, the component tracks
+/// medians of priority fees at `priority_fee_percentiles` and of the blob base fee (EIP-4844)
+/// over the same blocks.
 #[derive(Debug)]
 pub struct GasAdjuster<E> {
     pub(super) statistics: GasStatistics,
+    /// Statistics for each of the priority fee percentiles specified in the config.
+    pub(super) priority_fee_statistics: Vec<GasStatistics>,
+    pub(super) blob_base_fee_statistics: GasStatistics,
     pub(super) config: GasAdjusterConfig,
     eth_client: E,
 }
 
 impl<E: EthInterface> GasAdjuster<E> {
+    /// Creates a new adjuster, fetching the fee history from L1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `priority_fee_percentiles` in the config are not sorted or not in the `[0, 100]` range.
     pub async fn new(eth_client: E, config: GasAdjusterConfig) -> Result<Self, Error> {
+        let percentiles = &config.priority_fee_percentiles;
+        assert!(
+            percentiles
+                .iter()
+                .all(|percentile| (0.0..=100.0).contains(percentile)),
+            "priority fee percentiles must be in the [0, 100] range: {percentiles:?}"
+        );
+        assert!(
+            percentiles.windows(2).all(|pair| pair[0] <= pair[1]),
+            "priority fee percentiles must be sorted in the ascending order: {percentiles:?}"
+        );
+
         // Subtracting 1 from the "latest" block number to prevent errors in case
         // the info about the latest block is not yet present on the node.
         // This sometimes happens on Infura.
@@ -34,10 +59,29 @@ impl<E: EthInterface> GasAdjuster<E> {
             .as_usize()
             .saturating_sub(1);
         let history = eth_client
-            .base_fee_history(current_block, config.max_base_fee_samples, "gas_adjuster")
+            .fee_history(
+                current_block,
+                config.max_base_fee_samples,
+                &config.priority_fee_percentiles,
+                "gas_adjuster",
+            )
             .await?;
+        let samples = FeeSamples::new(&history, config.priority_fee_percentiles.len());
+
+        let max_samples = config.max_base_fee_samples;
+        let priority_fee_statistics = samples
+            .priority_fees
+            .iter()
+            .map(|fees| GasStatistics::new(max_samples, current_block, fees))
+            .collect();
         Ok(Self {
-            statistics: GasStatistics::new(config.max_base_fee_samples, current_block, &history),
+            statistics: GasStatistics::new(max_samples, current_block, &samples.base_fees),
+            priority_fee_statistics,
+            blob_base_fee_statistics: GasStatistics::new(
+                max_samples,
+                current_block,
+                &samples.blob_base_fees,
+            ),
             eth_client,
             config,
         })
@@ -62,19 +106,34 @@ impl<E: EthInterface> GasAdjuster<E> {
             // Report the current price to be gathered by the statistics module.
             let history = self
                 .eth_client
-                .base_fee_history(
+                .fee_history(
                     current_block,
                     current_block - last_processed_block,
+                    &self.config.priority_fee_percentiles,
                     "gas_adjuster",
                 )
                 .await?;
+            let samples = FeeSamples::new(&history, self.config.priority_fee_percentiles.len());
 
             metrics::gauge!(
                 "server.gas_adjuster.current_base_fee_per_gas",
-                *history.last().unwrap() as f64
+                *samples.base_fees.last().unwrap() as f64
+            );
+            metrics::gauge!(
+                "server.gas_adjuster.current_blob_base_fee_per_gas",
+                *samples.blob_base_fees.last().unwrap() as f64
             );
 
-            self.statistics.add_samples(&history);
+            self.statistics.add_samples(&samples.base_fees);
+            for (statistics, fees) in self
+                .priority_fee_statistics
+                .iter()
+                .zip(&samples.priority_fees)
+            {
+                statistics.add_samples(fees);
+            }
+            self.blob_base_fee_statistics
+                .add_samples(&samples.blob_base_fees);
         }
         Ok(())
     }
@@ -107,6 +166,17 @@ impl<E: EthInterface> L1GasPriceProvider for GasAdjuster<E> {
 
         (self.config.internal_l1_pricing_multiplier * effective_gas_price as f64) as u64
     }
+
+    fn estimate_blob_base_fee(&self) -> u64 {
+        self.blob_base_fee_statistics.median()
+    }
+
+    fn get_priority_fee_percentiles(&self) -> Vec<u64> {
+        self.priority_fee_statistics
+            .iter()
+            .map(GasStatistics::median)
+            .collect()
+    }
 }
 
 impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
@@ -139,6 +209,14 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
         last_block_base_fee * 875 / 1000
     }
 
+    fn get_median_base_fee(&self) -> u64 {
+        self.statistics.median()
+    }
+
+    fn get_last_block_base_fee(&self) -> u64 {
+        self.statistics.last_added_value()
+    }
+
     // Priority fee is set to constant, sourced from config.
     // Reasoning behind this is the following:
     // High priority_fee means high demand for block space,
@@ -151,6 +229,37 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
     }
 }
 
+/// Fees for a range of L1 blocks split by the fee kind.
+#[derive(Debug)]
+struct FeeSamples {
+    base_fees: Vec<u64>,
+    /// Priority fees for each of the requested percentiles.
+    priority_fees: Vec<Vec<u64>>,
+    blob_base_fees: Vec<u64>,
+}
+
+impl FeeSamples {
+    fn new(history: &[BlockFees], percentile_count: usize) -> Self {
+        // Nodes report zero rewards for empty blocks, so we do the same for missing values.
+        let priority_fees = (0..percentile_count)
+            .map(|i| {
+                history
+                    .iter()
+                    .map(|fees| fees.rewards.get(i).copied().unwrap_or(0))
+                    .collect()
+            })
+            .collect();
+        Self {
+            base_fees: history.iter().map(|fees| fees.base_fee_per_gas).collect(),
+            priority_fees,
+            blob_base_fees: history
+                .iter()
+                .map(|fees| fees.base_fee_per_blob_gas)
+                .collect(),
+        }
+    }
+}
+
 /// Helper structure responsible for collecting the data about recent transactions,
 /// calculating the median base fee.
 #[derive(Debug, Clone, Default)]
@@ -1,4 +1,5 @@
 use super::{GasAdjuster, GasStatisticsInner};
+use crate::l1_gas_price::{L1GasPriceProvider, L1TxParamsProvider};
 use std::collections::VecDeque;
 use std::sync::Arc;
 use zksync_config::GasAdjusterConfig;
@@ -43,6 +44,7 @@ async fn kept_updated() {
             internal_enforced_l1_gas_price: None,
             poll_period: 5,
             max_l1_gas_price: None,
+            priority_fee_percentiles: vec![],
         },
     )
     .await
@@ -57,3 +59,100 @@ async fn kept_updated() {
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
     assert_eq!(adjuster.statistics.0.read().unwrap().median(), 7);
 }
+
+/// Check that we track priority fee percentiles and blob base fees along with base fees
+#[tokio::test]
+async fn priority_and_blob_fees_are_tracked() {
+    let eth_client = Arc::new(
+        MockEthereum::default()
+            .with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9])
+            .with_reward_history(vec![
+                vec![0, 0],
+                vec![1, 10],
+                vec![2, 20],
+                vec![3, 30],
+                vec![2, 40],
+                vec![1, 50],
+                vec![5, 60],
+                vec![6, 70],
+                vec![7, 80],
+                // Empty block
+                vec![],
+            ])
+            .with_blob_base_fee_history(vec![1, 1, 2, 3, 5, 8, 13, 21]),
+    );
+    eth_client.advance_block_number(5);
+
+    let adjuster = GasAdjuster::new(
+        Arc::clone(&eth_client),
+        GasAdjusterConfig {
+            default_priority_fee_per_gas: 5,
+            max_base_fee_samples: 5,
+            pricing_formula_parameter_a: 1.5,
+            pricing_formula_parameter_b: 1.0005,
+            internal_l1_pricing_multiplier: 0.8,
+            internal_enforced_l1_gas_price: None,
+            poll_period: 5,
+            max_l1_gas_price: None,
+            priority_fee_percentiles: vec![25.0, 75.0],
+        },
+    )
+    .await
+    .unwrap();
+
+    // Blocks 0..=4 are processed
+    assert_eq!(adjuster.get_priority_fee_percentiles(), [2, 20]);
+    assert_eq!(adjuster.estimate_blob_base_fee(), 2);
+    assert_eq!(adjuster.get_median_base_fee(), 6);
+
+    eth_client.advance_block_number(4);
+    adjuster.keep_updated().await.unwrap();
+
+    // Blocks 4..=8 are processed; blob base fees are missing for block 8
+    assert_eq!(adjuster.get_priority_fee_percentiles(), [5, 60]);
+    assert_eq!(adjuster.estimate_blob_base_fee(), 8);
+    assert_eq!(adjuster.get_median_base_fee(), 7);
+
+    eth_client.advance_block_number(1);
+    adjuster.keep_updated().await.unwrap();
+
+    // Blocks 5..=9 are processed; rewards are missing for block 9
+    assert_eq!(adjuster.get_priority_fee_percentiles(), [5, 60]);
+    assert_eq!(adjuster.estimate_blob_base_fee(), 8);
+}
+
+#[tokio::test]
+#[should_panic(expected = "must be sorted")]
+async fn unsorted_priority_fee_percentiles_are_rejected() {
+    let eth_client = MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5]);
+    let config = GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.5,
+        pricing_formula_parameter_b: 1.0005,
+        internal_l1_pricing_multiplier: 0.8,
+        internal_enforced_l1_gas_price: None,
+        poll_period: 5,
+        max_l1_gas_price: None,
+        priority_fee_percentiles: vec![75.0, 25.0],
+    };
+    GasAdjuster::new(eth_client, config).await.ok();
+}
+
+#[tokio::test]
+#[should_panic(expected = "must be in the [0, 100] range")]
+async fn out_of_range_priority_fee_percentiles_are_rejected() {
+    let eth_client = MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5]);
+    let config = GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.5,
+        pricing_formula_parameter_b: 1.0005,
+        internal_l1_pricing_multiplier: 0.8,
+        internal_enforced_l1_gas_price: None,
+        poll_period: 5,
+        max_l1_gas_price: None,
+        priority_fee_percentiles: vec![50.0, 150.0],
+    };
+    GasAdjuster::new(eth_client, config).await.ok();
+}
@@ -15,6 +15,18 @@ pub trait L1GasPriceProvider {
     /// Returns a best guess of a realistic value for the L1 gas price.
     /// Return value is in wei.
     fn estimate_effective_gas_price(&self) -> u64;
+
+    /// Returns a best guess of a realistic value for the L1 blob base fee (EIP-4844).
+    /// Return value is in wei; zero if blob fees are not tracked by the provider.
+    fn estimate_blob_base_fee(&self) -> u64 {
+        0
+    }
+
+    /// Returns priority fees paid in the recent L1 blocks at the percentiles tracked by the provider,
+    /// sorted by the percentile. Return values are in wei; empty if priority fees are not tracked.
+    fn get_priority_fee_percentiles(&self) -> Vec<u64> {
+        vec![]
+    }
 }
 
 /// Extended version of `L1GasPriceProvider` that can provide parameters
@@ -30,4 +42,10 @@ pub trait L1TxParamsProvider: L1GasPriceProvider {
 
     /// Returns a lower bound for the `base_fee` value for the next L1 block.
     fn get_next_block_minimal_base_fee(&self) -> u64;
+
+    /// Returns the median `base_fee` value over the recent L1 blocks.
+    fn get_median_base_fee(&self) -> u64;
+
+    /// Returns the `base_fee` value of the last observed L1 block.
+    fn get_last_block_base_fee(&self) -> u64;
 }
@@ -4,6 +4,7 @@ use std::{str::FromStr, sync::Arc, time::Instant};
 
 use api_server::execution_sandbox::VmConcurrencyLimiter;
 use futures::channel::oneshot;
+use once_cell::sync::OnceCell;
 use tokio::{sync::watch, task::JoinHandle};
 
 use house_keeper::periodic_job::PeriodicJob;
@@ -41,12 +42,14 @@ use zksync_types::{proofs::AggregationRound, L2ChainId, PackedEthSignature, H160
 use crate::api_server::healthcheck::HealthCheckHandle;
 use crate::api_server::tx_sender::TxSenderConfig;
 use crate::api_server::web3::api_health_check::ApiHealthCheck;
+use crate::api_server::web3::rate_limiter::ClientIdentification;
 use crate::api_server::web3::state::InternalApiConfig;
 use crate::api_server::{
     healthcheck,
     tx_sender::{TxSender, TxSenderBuilder},
 };
 use crate::eth_sender::{Aggregator, EthTxManager};
+use crate::house_keeper::blob_retention_sweeper::{BlobRetentionPolicy, BlobRetentionSweeper};
 use crate::house_keeper::fri_prover_job_retry_manager::FriProverJobRetryManager;
 use crate::house_keeper::fri_prover_queue_monitor::FriProverStatsReporter;
 use crate::house_keeper::fri_scheduler_circuit_queuer::SchedulerCircuitQueuer;
@@ -62,9 +65,13 @@ use crate::house_keeper::{
 };
 use crate::l1_gas_price::{GasAdjusterSingleton, L1GasPriceProvider};
 use crate::metadata_calculator::{
-    MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig, TreeHealthCheck,
+    AsyncTreeReader, MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig,
+    TreeHealthCheck,
+};
+use crate::state_keeper::{
+    create_state_keeper, miniblock_events_bus, MempoolFetcher, MempoolGuard, MiniblockEventsSender,
+    MiniblockSealer, PendingState,
 };
-use crate::state_keeper::{create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer};
 use crate::witness_generator::{
     basic_circuits::BasicWitnessGenerator, leaf_aggregation::LeafAggregationWitnessGenerator,
     node_aggregation::NodeAggregationWitnessGenerator, scheduler::SchedulerWitnessGenerator,
@@ -291,6 +298,22 @@ pub async fn initialize_components(
         Web3JsonRpcConfig::from_env().factory_deps_cache_size_mb(),
     );
 
+    // Trees are initialized after the API servers, so the latter get access to the tree reader
+    // once it's available.
+    let tree_reader = Arc::new(OnceCell::new());
+
+    // If the WS API is co-located with the state keeper, pubsub notifications are driven by sealed miniblocks
+    // instead of polling Postgres.
+    let miniblock_events = (components.contains(&Component::WsApi)
+        && components.contains(&Component::StateKeeper))
+    .then(miniblock_events_bus);
+    // If the API is co-located with the state keeper, it can serve the pending block from the state keeper's changes
+    // not yet persisted to Postgres.
+    let pending_state = ((components.contains(&Component::HttpApi)
+        || components.contains(&Component::WsApi))
+        && components.contains(&Component::StateKeeper))
+    .then(PendingState::default);
+
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
         || components.contains(&Component::ExplorerApi)
@@ -298,7 +321,11 @@ pub async fn initialize_components(
         let api_config = ApiConfig::from_env();
         let state_keeper_config = StateKeeperConfig::from_env();
         let network_config = NetworkConfig::from_env();
-        let tx_sender_config = TxSenderConfig::new(&state_keeper_config, &api_config.web3_json_rpc);
+        let tx_sender_config = TxSenderConfig::new(
+            &state_keeper_config,
+            &api_config.web3_json_rpc,
+            &MempoolConfig::from_env(),
+        );
         let internal_api_config = InternalApiConfig::new(
             &network_config,
             &api_config.web3_json_rpc,
@@ -319,6 +346,8 @@ pub async fn initialize_components(
                 bounded_gas_adjuster.clone(),
                 state_keeper_config.save_call_traces,
                 factory_deps_cache.clone(),
+                tree_reader.clone(),
+                pending_state.clone(),
             )
             .await;
             task_futures.extend(futures);
@@ -341,6 +370,9 @@ pub async fn initialize_components(
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 factory_deps_cache.clone(),
+                tree_reader.clone(),
+                miniblock_events.clone(),
+                pending_state.clone(),
             )
             .await;
             task_futures.extend(futures);
@@ -379,6 +411,8 @@ pub async fn initialize_components(
             &DBConfig::from_env(),
             &MempoolConfig::from_env(),
             bounded_gas_adjuster,
+            miniblock_events,
+            pending_state,
             stop_receiver.clone(),
         )
         .await;
@@ -413,9 +447,17 @@ pub async fn initialize_components(
         let eth_client =
             PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
         let nonce = eth_client.pending_nonce("eth_sender").await.unwrap();
+        let mut aggregator = Aggregator::new(eth_sender.sender.clone());
+        if eth_sender
+            .sender
+            .aggregated_block_commit_fee_spike_ratio
+            .is_some()
+        {
+            aggregator = aggregator.with_gas_price_criterion(gas_adjuster.get_or_init().await);
+        }
         let eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender.sender.clone(),
-            Aggregator::new(eth_sender.sender.clone()),
+            aggregator,
             contracts_config.validator_timelock_addr,
             nonce.as_u64(),
         );
@@ -470,6 +512,7 @@ pub async fn initialize_components(
         &components,
         &store_factory,
         &stop_receiver,
+        &tree_reader,
     )
     .await;
     add_witness_generator_to_task_futures(
@@ -501,6 +544,7 @@ pub async fn initialize_components(
     Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync + 'static>(
     task_futures: &mut Vec<JoinHandle<()>>,
     contracts_config: &ContractsConfig,
@@ -508,6 +552,8 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
     gas_adjuster: Arc<E>,
+    miniblock_events: Option<MiniblockEventsSender>,
+    pending_state: Option<PendingState>,
     stop_receiver: watch::Receiver<bool>,
 ) {
     let fair_l2_gas_price = state_keeper_config.fair_l2_gas_price;
@@ -518,16 +564,18 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
         .transactions_dal()
         .next_priority_id()
         .await;
-    let mempool = MempoolGuard::new(next_priority_id, mempool_config.capacity);
+    let mempool =
+        MempoolGuard::from_config(next_priority_id, mempool_config, &state_keeper_config);
 
     let miniblock_sealer_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
-    let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
+    let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::with_events_sender(
         miniblock_sealer_pool,
         state_keeper_config.miniblock_seal_queue_capacity,
+        miniblock_events,
     );
     task_futures.push(tokio::spawn(miniblock_sealer.run()));
 
-    let state_keeper = create_state_keeper(
+    let mut state_keeper = create_state_keeper(
         contracts_config,
         state_keeper_config,
         db_config,
@@ -539,6 +587,9 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
         stop_receiver.clone(),
     )
     .await;
+    if let Some(pending_state) = pending_state {
+        state_keeper = state_keeper.with_pending_state(pending_state);
+    }
     task_futures.push(tokio::spawn(state_keeper.run()));
 
     let mempool_fetcher_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
@@ -559,6 +610,7 @@ async fn add_trees_to_task_futures(
     components: &[Component],
     store_factory: &ObjectStoreFactory,
     stop_receiver: &watch::Receiver<bool>,
+    tree_reader: &OnceCell<AsyncTreeReader>,
 ) {
     let db_config = DBConfig::from_env();
     let operation_config = OperationsManagerConfig::from_env();
@@ -583,10 +635,12 @@ async fn add_trees_to_task_futures(
             } else {
                 MetadataCalculatorModeConfig::Lightweight
             };
-            let (future, tree_health_check) =
+            let (future, tree_health_check, reader) =
                 run_tree(&db_config, &operation_config, mode, stop_receiver.clone()).await;
             task_futures.push(future);
             healthchecks.push(Box::new(tree_health_check));
+            // At most one tree is run (see the check above), so the reader can only be set once.
+            tree_reader.set(reader).ok();
         }
     }
 }
@@ -596,7 +650,7 @@ async fn run_tree(
     operation_manager: &OperationsManagerConfig,
     mode: MetadataCalculatorModeConfig<'_>,
     stop_receiver: watch::Receiver<bool>,
-) -> (JoinHandle<()>, TreeHealthCheck) {
+) -> (JoinHandle<()>, TreeHealthCheck, AsyncTreeReader) {
     let started_at = Instant::now();
     let mode_str = if matches!(mode, MetadataCalculatorModeConfig::Full { .. }) {
         "full"
@@ -609,6 +663,7 @@ async fn run_tree(
     let metadata_calculator = MetadataCalculator::new(&config).await;
     let tree_health_check = metadata_calculator.tree_health_check();
     let tree_tag = metadata_calculator.tree_tag();
+    let tree_reader = metadata_calculator.tree_reader();
     let pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
     let prover_pool = ConnectionPool::new(Some(1), DbVariant::Prover).await;
     let future = tokio::spawn(metadata_calculator.run(pool, prover_pool, stop_receiver));
@@ -623,7 +678,7 @@ async fn run_tree(
         "stage" => "tree",
         "tree" => tree_tag
     );
-    (future, tree_health_check)
+    (future, tree_health_check, tree_reader)
 }
 
 async fn add_witness_generator_to_task_futures(
@@ -716,6 +771,22 @@ async fn add_house_keeper_to_task_futures(
 ) {
     let house_keeper_config = HouseKeeperConfig::from_env();
     let connection_pool = ConnectionPool::new(Some(1), DbVariant::Replica).await;
+    if let Some(interval_ms) = house_keeper_config.blob_retention_sweeping_interval_ms {
+        let policies = house_keeper_config
+            .blob_retention_policies
+            .iter()
+            .map(|policy| policy.parse::<BlobRetentionPolicy>())
+            .collect::<Result<_, _>>()
+            .expect("invalid blob retention policies");
+        let blob_retention_sweeper = BlobRetentionSweeper::new(
+            store_factory,
+            connection_pool.clone(),
+            policies,
+            interval_ms,
+        )
+        .await;
+        task_futures.push(tokio::spawn(blob_retention_sweeper.run()));
+    }
     let l1_batch_metrics_reporter = L1BatchMetricsReporter::new(
         house_keeper_config.l1_batch_metrics_reporting_interval_ms,
         connection_pool,
@@ -817,6 +888,7 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
     master_pool: ConnectionPool,
     l1_gas_price_provider: Arc<G>,
     factory_deps_cache: FactoryDepsCache,
+    pending_state: Option<PendingState>,
 ) -> TxSender<G> {
     let mut tx_sender_builder = TxSenderBuilder::new(tx_sender_config.clone(), replica_pool)
         .with_main_connection_pool(master_pool)
@@ -826,6 +898,9 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
     if let Some(transactions_per_sec_limit) = web3_json_config.transactions_per_sec_limit {
         tx_sender_builder = tx_sender_builder.with_rate_limiter(transactions_per_sec_limit);
     };
+    if let Some(pending_state) = pending_state {
+        tx_sender_builder = tx_sender_builder.with_pending_state(pending_state);
+    }
 
     let vm_concurrency_limiter = VmConcurrencyLimiter::new(web3_json_config.vm_concurrency_limit);
 
@@ -851,6 +926,8 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     gas_adjuster: Arc<G>,
     with_debug_namespace: bool,
     factory_deps_cache: FactoryDepsCache,
+    tree_reader: Arc<OnceCell<AsyncTreeReader>>,
+    pending_state: Option<PendingState>,
 ) -> (Vec<JoinHandle<()>>, ApiHealthCheck) {
     let tx_sender = build_tx_sender(
         tx_sender_config,
@@ -860,6 +937,7 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         master_connection_pool.clone(),
         gas_adjuster,
         factory_deps_cache.clone(),
+        pending_state.clone(),
     )
     .await;
 
@@ -868,7 +946,18 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
             .http(api_config.web3_json_rpc.http_port)
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_threads(api_config.web3_json_rpc.http_server_threads())
-            .with_tx_sender(tx_sender);
+            .with_tx_sender(tx_sender)
+            .with_deferred_tree_reader(tree_reader);
+    if let Some(weight_per_sec) = api_config.web3_json_rpc.rate_limit_weight_per_sec {
+        builder = builder.with_rate_limit(
+            weight_per_sec,
+            api_config.web3_json_rpc.rate_limit_method_weights(),
+            ClientIdentification {
+                api_keys: api_config.web3_json_rpc.rate_limit_api_keys(),
+                behind_trusted_proxy: api_config.web3_json_rpc.rate_limit_behind_trusted_proxy(),
+            },
+        );
+    }
 
     if with_debug_namespace {
         builder = builder.enable_debug_namespace(
@@ -878,8 +967,12 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
             },
             tx_sender_config.fair_l2_gas_price,
             api_config.web3_json_rpc.vm_execution_cache_misses_limit,
+            api_config.web3_json_rpc.replay_block_traces(),
         )
     }
+    if let Some(pending_state) = pending_state {
+        builder = builder.with_pending_state(pending_state);
+    }
 
     builder.build(stop_receiver.clone()).await
 }
@@ -895,6 +988,9 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     factory_deps_cache: FactoryDepsCache,
+    tree_reader: Arc<OnceCell<AsyncTreeReader>>,
+    miniblock_events: Option<MiniblockEventsSender>,
+    pending_state: Option<PendingState>,
 ) -> (Vec<JoinHandle<()>>, ApiHealthCheck) {
     let tx_sender = build_tx_sender(
         tx_sender_config,
@@ -904,18 +1000,37 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         master_connection_pool.clone(),
         gas_adjuster,
         factory_deps_cache.clone(),
+        pending_state.clone(),
     )
     .await;
 
-    web3::ApiBuilder::jsonrpc_backend(internal_api.clone(), replica_connection_pool)
-        .ws(api_config.web3_json_rpc.ws_port)
-        .with_filter_limit(api_config.web3_json_rpc.filters_limit())
-        .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
-        .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
-        .with_threads(api_config.web3_json_rpc.ws_server_threads())
-        .with_tx_sender(tx_sender)
-        .build(stop_receiver.clone())
-        .await
+    let mut builder =
+        web3::ApiBuilder::jsonrpc_backend(internal_api.clone(), replica_connection_pool)
+            .ws(api_config.web3_json_rpc.ws_port)
+            .with_filter_limit(api_config.web3_json_rpc.filters_limit())
+            .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
+            .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
+            .with_threads(api_config.web3_json_rpc.ws_server_threads())
+            .with_tx_sender(tx_sender)
+            .with_deferred_tree_reader(tree_reader);
+    if let Some(weight_per_sec) = api_config.web3_json_rpc.rate_limit_weight_per_sec {
+        builder = builder.with_rate_limit(
+            weight_per_sec,
+            api_config.web3_json_rpc.rate_limit_method_weights(),
+            ClientIdentification {
+                api_keys: api_config.web3_json_rpc.rate_limit_api_keys(),
+                behind_trusted_proxy: api_config.web3_json_rpc.rate_limit_behind_trusted_proxy(),
+            },
+        );
+    }
+    if let Some(pending_state) = pending_state {
+        builder = builder.with_pending_state(pending_state);
+    }
+    if let Some(miniblock_events) = miniblock_events {
+        builder = builder.with_miniblock_events(miniblock_events);
+    }
+
+    builder.build(stop_receiver.clone()).await
 }
 
 async fn circuit_breakers_for_components(
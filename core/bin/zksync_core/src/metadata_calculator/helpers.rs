@@ -3,10 +3,13 @@
 #[cfg(test)]
 use tokio::sync::mpsc;
 
-use std::{collections::BTreeMap, future::Future, mem, time::Duration};
+use std::{collections::BTreeMap, future::Future, mem, sync::Arc, time::Duration};
 
 use zksync_dal::StorageProcessor;
-use zksync_merkle_tree::domain::{TreeMetadata, ZkSyncTree};
+use zksync_merkle_tree::{
+    domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
+    TreeLogEntryWithProof,
+};
 use zksync_types::{
     block::WitnessBlockWithLogs, L1BatchNumber, StorageKey, StorageLog, WitnessStorageLog, H256,
 };
@@ -50,6 +53,10 @@ impl AsyncTree {
         self.as_ref().root_hash()
     }
 
+    pub fn reader(&self) -> AsyncTreeReader {
+        AsyncTreeReader(Arc::new(self.as_ref().reader()))
+    }
+
     pub async fn process_block(&mut self, block: Vec<WitnessStorageLog>) -> TreeMetadata {
         let mut tree = mem::take(self);
         let (tree, metadata) = tokio::task::spawn_blocking(move || {
@@ -94,6 +101,41 @@ impl AsyncTree {
     }
 }
 
+/// Read-only handle to the tree used by [`MetadataCalculator`]. Can be shared with other components
+/// running in the same process, e.g. the web3 API server.
+///
+/// The reader only observes L1 batches that were persisted to RocksDB by the calculator.
+#[derive(Debug, Clone)]
+pub struct AsyncTreeReader(Arc<ZkSyncTreeReader>);
+
+impl AsyncTreeReader {
+    /// Returns the root hash of the tree after `l1_batch_number`, or `None` if the L1 batch
+    /// is not present in the tree.
+    pub async fn root_hash(&self, l1_batch_number: L1BatchNumber) -> Option<H256> {
+        let reader = self.0.clone();
+        tokio::task::spawn_blocking(move || reader.root_hash(l1_batch_number))
+            .await
+            .unwrap()
+    }
+
+    /// Returns the root hash and entries with Merkle proofs for the specified storage `keys`
+    /// in the tree state after `l1_batch_number`, or `None` if the L1 batch is not present in the tree.
+    /// The root hash and the proofs are read from the same tree version.
+    pub async fn root_hash_with_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: Vec<StorageKey>,
+    ) -> Option<(H256, Vec<TreeLogEntryWithProof>)> {
+        let reader = self.0.clone();
+        tokio::task::spawn_blocking(move || {
+            let hashed_keys: Vec<_> = keys.iter().map(StorageKey::hashed_key_u256).collect();
+            reader.root_hash_with_proofs(l1_batch_number, &hashed_keys)
+        })
+        .await
+        .unwrap()
+    }
+}
+
 /// Component implementing the delay policy in [`MetadataCalculator`] when there are no
 /// blocks to seal.
 #[derive(Debug, Clone)]
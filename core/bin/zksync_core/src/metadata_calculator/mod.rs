@@ -22,8 +22,8 @@ mod metrics;
 mod tests;
 mod updater;
 
-pub use self::healthcheck::TreeHealthCheck;
 pub(crate) use self::helpers::get_logs_for_l1_batch;
+pub use self::{healthcheck::TreeHealthCheck, helpers::AsyncTreeReader};
 use self::{helpers::Delayer, metrics::TreeUpdateStage, updater::TreeUpdater};
 
 #[derive(Debug, Copy, Clone)]
@@ -142,6 +142,11 @@ impl MetadataCalculator {
         TreeHealthCheck::new(receiver, self.updater.mode())
     }
 
+    /// Returns a read-only handle to the tree maintained by this calculator.
+    pub fn tree_reader(&self) -> AsyncTreeReader {
+        self.updater.tree_reader()
+    }
+
     /// Returns the tag for this calculator usable in metrics reporting.
     pub fn tree_tag(&self) -> &'static str {
         self.updater.mode().as_tag()
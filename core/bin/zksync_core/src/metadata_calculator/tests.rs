@@ -16,6 +16,7 @@ use zksync_config::DBConfig;
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_health_check::{CheckHealth, CheckHealthStatus};
+use zksync_merkle_tree::TreeLogEntry;
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_types::{
     block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
@@ -72,6 +73,40 @@ async fn basic_workflow(pool: ConnectionPool, prover_pool: ConnectionPool) {
     assert_eq!(calculator.updater.tree().block_number(), 2);
 }
 
+#[db_test]
+async fn tree_reader_provides_proofs(pool: ConnectionPool, prover_pool: ConnectionPool) {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    let tree_reader = calculator.tree_reader();
+    reset_db_state(&pool, 1).await;
+    let root_hash = run_calculator(calculator, pool, prover_pool).await;
+
+    assert_eq!(
+        tree_reader.root_hash(L1BatchNumber(1)).await,
+        Some(root_hash)
+    );
+    assert_eq!(tree_reader.root_hash(L1BatchNumber(2)).await, None);
+
+    let logs = gen_storage_logs(0..100, 1).pop().unwrap();
+    let keys = logs.iter().map(|log| log.key).collect();
+    let (proof_root_hash, entries) = tree_reader
+        .root_hash_with_proofs(L1BatchNumber(1), keys)
+        .await
+        .expect("L1 batch #1 is not present in the tree");
+    assert_eq!(proof_root_hash, root_hash);
+    assert_eq!(entries.len(), logs.len());
+    for (entry, log) in entries.iter().zip(&logs) {
+        assert_matches!(entry.base, TreeLogEntry::Read { value, .. } if value == log.value);
+        assert_eq!(entry.root_hash, root_hash);
+    }
+
+    let missing_entries = tree_reader
+        .root_hash_with_proofs(L1BatchNumber(2), vec![logs[0].key])
+        .await;
+    assert!(missing_entries.is_none());
+}
+
 #[db_test]
 async fn status_receiver_has_correct_states(pool: ConnectionPool, prover_pool: ConnectionPool) {
     let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
@@ -12,7 +12,7 @@ use zksync_types::{block::WitnessBlockWithLogs, L1BatchNumber};
 
 use super::{
     get_logs_for_l1_batch,
-    helpers::{AsyncTree, Delayer},
+    helpers::{AsyncTree, AsyncTreeReader, Delayer},
     metrics::TreeUpdateStage,
     MetadataCalculator, MetadataCalculatorMode, MetadataCalculatorStatus,
 };
@@ -62,6 +62,10 @@ impl TreeUpdater {
         }
     }
 
+    pub fn tree_reader(&self) -> AsyncTreeReader {
+        self.tree.reader()
+    }
+
     #[cfg(test)]
     pub fn tree(&self) -> &AsyncTree {
         &self.tree
@@ -215,6 +215,7 @@ impl<G: L1GasPriceProvider + 'static + Send + Sync> StateKeeperIO for MempoolIO<
                 block_result,
                 block_context,
                 self.l2_erc20_bridge_addr,
+                self.miniblock_sealer_handle.events_sender(),
             )
             .await;
         self.current_miniblock_number += 1; // Due to fictive miniblock being sealed.
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
 
 use std::{
     fmt,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -11,7 +12,7 @@ use vm::zk_evm::block_properties::BlockProperties;
 use vm::VmBlockResult;
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::ConnectionPool;
-use zksync_types::{L1BatchNumber, MiniblockNumber, Transaction};
+use zksync_types::{api, web3::types::BlockHeader, L1BatchNumber, MiniblockNumber, Transaction};
 
 pub(crate) mod common;
 pub(crate) mod mempool;
@@ -98,6 +99,28 @@ impl fmt::Debug for dyn StateKeeperIO {
     }
 }
 
+/// Information about a sealed miniblock broadcast to in-process listeners, such as API servers
+/// co-located with the state keeper. Allows listeners to get new data as soon as it's persisted
+/// instead of polling Postgres.
+#[derive(Debug, Clone)]
+pub(crate) struct SealedMiniblockEvent {
+    /// Header of the miniblock in the same form as returned by the Web3 API.
+    pub header: BlockHeader,
+    /// Logs emitted in the miniblock.
+    pub logs: Vec<api::Log>,
+}
+
+/// Sending half of the in-process bus with [`SealedMiniblockEvent`]s. Listeners subscribe to the bus
+/// using [`broadcast::Sender::subscribe()`].
+pub(crate) type MiniblockEventsSender = broadcast::Sender<Arc<SealedMiniblockEvent>>;
+
+/// Creates a bus for [`SealedMiniblockEvent`]s. The bus retains a limited number of latest events;
+/// listeners falling further behind are notified that they have lagged.
+pub(crate) fn miniblock_events_bus() -> MiniblockEventsSender {
+    const CAPACITY: usize = 256;
+    broadcast::channel(CAPACITY).0
+}
+
 /// A command together with the return address allowing to track command processing completion.
 #[derive(Debug)]
 struct Completable<T> {
@@ -112,6 +135,7 @@ pub(crate) struct MiniblockSealerHandle {
     latest_completion_receiver: Option<oneshot::Receiver<()>>,
     // If true, `submit()` will wait for the operation to complete.
     is_sync: bool,
+    events_sender: Option<MiniblockEventsSender>,
 }
 
 impl MiniblockSealerHandle {
@@ -193,6 +217,13 @@ impl MiniblockSealerHandle {
             );
         }
     }
+
+    /// Returns the bus that sealed miniblocks are reported to, if any. Miniblocks sealed outside
+    /// the sealer (i.e., fictive miniblocks sealed together with an L1 batch) should be reported
+    /// to this bus as well.
+    pub fn events_sender(&self) -> Option<&MiniblockEventsSender> {
+        self.events_sender.as_ref()
+    }
 }
 
 /// Component responsible for sealing miniblocks (i.e., storing their data to Postgres).
@@ -203,14 +234,24 @@ pub(crate) struct MiniblockSealer {
     // Weak sender handle to get queue capacity stats.
     commands_sender: mpsc::WeakSender<Completable<MiniblockSealCommand>>,
     commands_receiver: mpsc::Receiver<Completable<MiniblockSealCommand>>,
+    events_sender: Option<MiniblockEventsSender>,
 }
 
 impl MiniblockSealer {
     /// Creates a sealer that will use the provided Postgres connection and will have the specified
     /// `command_capacity` for unprocessed sealing commands.
     pub(crate) fn new(
+        pool: ConnectionPool,
+        command_capacity: usize,
+    ) -> (Self, MiniblockSealerHandle) {
+        Self::with_events_sender(pool, command_capacity, None)
+    }
+
+    /// Same as [`Self::new()`], but additionally reports each sealed miniblock to the provided bus.
+    pub(crate) fn with_events_sender(
         pool: ConnectionPool,
         mut command_capacity: usize,
+        events_sender: Option<MiniblockEventsSender>,
     ) -> (Self, MiniblockSealerHandle) {
         let is_sync = command_capacity == 0;
         command_capacity = command_capacity.max(1);
@@ -221,11 +262,13 @@ impl MiniblockSealer {
             is_sync,
             commands_sender: commands_sender.downgrade(),
             commands_receiver,
+            events_sender: events_sender.clone(),
         };
         let handle = MiniblockSealerHandle {
             commands_sender,
             latest_completion_receiver: None,
             is_sync,
+            events_sender,
         };
         (this, handle)
     }
@@ -250,6 +293,9 @@ impl MiniblockSealer {
         while let Some(completable) = self.next_command().await {
             let mut conn = self.pool.access_storage_tagged("state_keeper").await;
             completable.command.seal(&mut conn).await;
+            if let Some(events_sender) = &self.events_sender {
+                completable.command.report_sealed(events_sender, false);
+            }
             if let Some(delta) = miniblock_seal_delta {
                 metrics::histogram!("server.state_keeper.miniblock.seal_delta", delta.elapsed());
             }
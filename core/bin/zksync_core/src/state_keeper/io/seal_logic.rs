@@ -5,6 +5,7 @@ use itertools::Itertools;
 
 use std::{
     collections::HashMap,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -14,9 +15,10 @@ use vm::{
     },
     VmBlockResult,
 };
-use zksync_config::constants::ACCOUNT_CODE_STORAGE_ADDRESS;
+use zksync_config::constants::{ACCOUNT_CODE_STORAGE_ADDRESS, EMPTY_UNCLES_HASH};
 use zksync_dal::StorageProcessor;
 use zksync_types::{
+    api,
     block::{L1BatchHeader, MiniblockHeader},
     event::{extract_added_tokens, extract_long_l2_to_l1_messages},
     l2_to_l1_log::L2ToL1Log,
@@ -24,13 +26,15 @@ use zksync_types::{
         tx_execution_info::DeduplicatedWritesMetrics, IncludedTxLocation,
         TransactionExecutionResult,
     },
+    web3::types::{BlockHeader, Bytes},
     zk_evm::aux_structures::LogQuery,
     zkevm_test_harness::witness::sort_storage_access::sort_storage_access_queries,
     Address, ExecuteTransactionCommon, L1BatchNumber, MiniblockNumber, StorageKey, StorageLog,
-    StorageLogQuery, StorageValue, Transaction, VmEvent, H256, U256,
+    StorageLogQuery, StorageValue, Transaction, VmEvent, H2048, H256, U256, U64,
 };
 use zksync_utils::{miniblock_hash, time::millis_since_epoch};
 
+use super::{MiniblockEventsSender, SealedMiniblockEvent};
 use crate::state_keeper::{
     extractors,
     updates::{L1BatchUpdates, MiniblockSealCommand, UpdatesManager},
@@ -123,6 +127,9 @@ impl UpdatesManager {
     /// Persists an L1 batch in the storage.
     /// This action includes a creation of an empty "fictive" miniblock that contains
     /// the events generated during the bootloader "tip phase".
+    ///
+    /// If `events_sender` is provided, the fictive miniblock is reported to it once the L1 batch is persisted.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn seal_l1_batch(
         mut self,
         storage: &mut StorageProcessor<'_>,
@@ -131,6 +138,7 @@ impl UpdatesManager {
         block_result: VmBlockResult,
         block_context: DerivedBlockContext,
         l2_erc20_bridge_addr: Address,
+        events_sender: Option<&MiniblockEventsSender>,
     ) {
         let started_at = Instant::now();
         let mut progress = SealProgress::for_l1_batch();
@@ -257,6 +265,10 @@ impl UpdatesManager {
         transaction.commit().await;
         progress.end_stage("commit_l1_batch", None);
 
+        if let Some(events_sender) = events_sender {
+            miniblock_command.report_sealed(events_sender, true);
+        }
+
         let writes_metrics = self.storage_writes_deduplicator.metrics();
         // Sanity check metrics.
         assert_eq!(
@@ -474,6 +486,70 @@ impl MiniblockSealCommand {
         self.report_miniblock_metrics(started_at);
     }
 
+    /// Reports the sealed miniblock to the in-process bus. Should be called only after the miniblock
+    /// is committed to Postgres, so that listeners can rely on its data being available there.
+    pub(crate) fn report_sealed(&self, events_sender: &MiniblockEventsSender, is_fictive: bool) {
+        if events_sender.receiver_count() == 0 {
+            return; // No one listens to the bus; no need to construct the event.
+        }
+        let event = self.sealed_event(is_fictive);
+        events_sender.send(Arc::new(event)).ok();
+        // ^ Listeners may have unsubscribed since the check above, which is fine.
+    }
+
+    fn sealed_event(&self, is_fictive: bool) -> SealedMiniblockEvent {
+        let block_hash = miniblock_hash(self.miniblock_number);
+        let block_number = U64::from(self.miniblock_number.0);
+        // Regular miniblocks are assigned to an L1 batch only when the batch is sealed; the fictive
+        // miniblock is sealed together with its batch.
+        let l1_batch_number = is_fictive.then(|| U64::from(self.l1_batch_number.0));
+
+        let header = BlockHeader {
+            hash: Some(block_hash),
+            parent_hash: H256::zero(),
+            uncles_hash: EMPTY_UNCLES_HASH,
+            author: Address::zero(),
+            state_root: H256::zero(),
+            transactions_root: H256::zero(),
+            receipts_root: H256::zero(),
+            number: Some(block_number),
+            gas_used: U256::zero(),
+            gas_limit: U256::zero(),
+            base_fee_per_gas: None,
+            extra_data: Bytes::default(),
+            logs_bloom: H2048::default(),
+            timestamp: U256::from(self.miniblock.timestamp),
+            difficulty: U256::zero(),
+            mix_hash: None,
+            nonce: None,
+        };
+
+        // Indices must match the ones persisted by `EventsDal::save_events()`.
+        let mut event_index_in_block = 0_u32;
+        let mut logs = Vec::with_capacity(self.miniblock.events.len());
+        for (tx_location, events) in self.extract_events(is_fictive) {
+            for event in events {
+                logs.push(api::Log {
+                    address: event.address,
+                    topics: event.indexed_topics.clone(),
+                    data: Bytes(event.value.clone()),
+                    block_hash: Some(block_hash),
+                    block_number: Some(block_number),
+                    l1_batch_number,
+                    transaction_hash: Some(tx_location.tx_hash),
+                    transaction_index: Some(tx_location.tx_index_in_miniblock.into()),
+                    log_index: Some(event_index_in_block.into()),
+                    transaction_log_index: Some(event_index_in_block.into()),
+                    log_type: None,
+                    removed: Some(false),
+                });
+                event_index_in_block += 1;
+            }
+        }
+
+        SealedMiniblockEvent { header, logs }
+    }
+
     /// Performs several sanity checks to make sure that the miniblock is valid.
     fn assert_valid_miniblock(&self, is_fictive: bool) {
         assert_eq!(self.miniblock.executed_transactions.is_empty(), is_fictive);
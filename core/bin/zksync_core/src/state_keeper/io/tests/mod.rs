@@ -14,7 +14,7 @@ use zksync_types::{
 use zksync_utils::time::millis_since_epoch;
 
 use crate::state_keeper::{
-    io::{MiniblockSealer, StateKeeperIO},
+    io::{miniblock_events_bus, MiniblockSealer, StateKeeperIO},
     mempool_actor::l2_tx_filter,
     tests::{
         create_block_metadata, create_execution_result, create_transaction, create_updates_manager,
@@ -298,6 +298,71 @@ async fn processing_events_when_sealing_miniblock(pool: ConnectionPool) {
     }
 }
 
+#[db_test]
+async fn reporting_sealed_miniblock_events(pool: ConnectionPool) {
+    let l1_batch_number = L1BatchNumber(2);
+    let mut miniblock = MiniblockUpdates::new(0);
+    for i in 0_u8..3 {
+        let tx = create_transaction(10, 100);
+        let mut execution_result = create_execution_result(u16::from(i), []);
+        execution_result.result.logs.events = (0..=i)
+            .map(|j| VmEvent {
+                location: (l1_batch_number, u32::from(i)),
+                value: vec![i, j],
+                ..VmEvent::default()
+            })
+            .collect();
+        miniblock.extend_from_executed_transaction(
+            tx,
+            execution_result,
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![],
+        );
+    }
+
+    let miniblock_number = MiniblockNumber(3);
+    let seal_command = MiniblockSealCommand {
+        l1_batch_number,
+        miniblock_number,
+        miniblock,
+        first_tx_index: 0,
+        l1_gas_price: 100,
+        fair_l2_gas_price: 100,
+        base_fee_per_gas: 10,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        l2_erc20_bridge_addr: Address::default(),
+    };
+    let mut conn = pool.access_storage_tagged("state_keeper").await;
+    seal_command.seal(&mut conn).await;
+
+    let events_sender = miniblock_events_bus();
+    // Events are not constructed if there are no listeners.
+    seal_command.report_sealed(&events_sender, false);
+    let mut events_receiver = events_sender.subscribe();
+    seal_command.report_sealed(&events_sender, false);
+    let event = events_receiver.try_recv().unwrap();
+    events_receiver.try_recv().unwrap_err();
+
+    assert_eq!(event.header.number, Some(miniblock_number.0.into()));
+    let persisted_header = conn
+        .blocks_web3_dal()
+        .get_block_headers_after(miniblock_number - 1)
+        .await
+        .unwrap();
+    assert_eq!(event.header.hash, persisted_header[0].hash);
+    assert_eq!(event.header.timestamp, persisted_header[0].timestamp);
+
+    // Logs in the event must be identical to the ones persisted in Postgres.
+    let persisted_logs = conn
+        .events_web3_dal()
+        .get_all_logs(miniblock_number - 1)
+        .await
+        .unwrap();
+    assert_eq!(persisted_logs.len(), 6);
+    assert_eq!(event.logs, persisted_logs);
+}
+
 async fn test_miniblock_and_l1_batch_processing(
     pool: ConnectionPool,
     miniblock_sealer_capacity: usize,
@@ -7,6 +7,7 @@ use zksync_config::GasAdjusterConfig;
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::ConnectionPool;
 use zksync_eth_client::clients::mock::MockEthereum;
+use zksync_mempool::FifoOrdering;
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     Address, L1BatchNumber, L2ChainId, MiniblockNumber, PriorityOpId, H256,
@@ -46,6 +47,7 @@ impl Tester {
             internal_enforced_l1_gas_price: None,
             poll_period: 10,
             max_l1_gas_price: None,
+            priority_fee_percentiles: vec![],
         };
 
         GasAdjuster::new(eth_client, gas_adjuster_config)
@@ -64,7 +66,7 @@ impl Tester {
         miniblock_sealer_capacity: usize,
     ) -> (MempoolIO<GasAdjuster<MockEthereum>>, MempoolGuard) {
         let gas_adjuster = Arc::new(self.create_gas_adjuster().await);
-        let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let mempool = MempoolGuard::new(PriorityOpId(0), 100, Box::new(FifoOrdering));
         let (miniblock_sealer, miniblock_sealer_handle) =
             MiniblockSealer::new(pool.clone(), miniblock_sealer_capacity);
         tokio::spawn(miniblock_sealer.run());
@@ -13,7 +13,7 @@ use crate::state_keeper::{
     extractors,
     io::{L1BatchParams, PendingBatchData, StateKeeperIO},
     seal_criteria::{SealData, SealManager, SealResolution},
-    types::ExecutionMetricsForCriteria,
+    types::{ExecutionMetricsForCriteria, PendingState},
     updates::UpdatesManager,
 };
 
@@ -40,6 +40,7 @@ pub struct ZkSyncStateKeeper {
     io: Box<dyn StateKeeperIO>,
     batch_executor_base: Box<dyn L1BatchExecutorBuilder>,
     sealer: SealManager,
+    pending_state: Option<PendingState>,
 }
 
 impl ZkSyncStateKeeper {
@@ -54,9 +55,17 @@ impl ZkSyncStateKeeper {
             io,
             batch_executor_base,
             sealer,
+            pending_state: None,
         }
     }
 
+    /// Makes the state keeper record changes made by executed transactions to the provided
+    /// [`PendingState`] until they are persisted.
+    pub(crate) fn with_pending_state(mut self, pending_state: PendingState) -> Self {
+        self.pending_state = Some(pending_state);
+        self
+    }
+
     pub async fn run(mut self) {
         match self.run_inner().await {
             Ok(()) => {
@@ -140,6 +149,10 @@ impl ZkSyncStateKeeper {
                     l1_batch_params.context_mode.inner_block_context(),
                 )
                 .await;
+            // All changes in the L1 batch are persisted at this point.
+            if let Some(pending_state) = &self.pending_state {
+                pending_state.clear();
+            }
             if let Some(delta) = l1_batch_seal_delta {
                 metrics::histogram!("server.state_keeper.l1_batch.seal_delta", delta.elapsed());
             }
@@ -332,6 +345,9 @@ impl ZkSyncStateKeeper {
                         l1_gas: tx_l1_gas_this_tx,
                         execution_metrics: tx_execution_metrics,
                     } = tx_metrics;
+                    if let Some(pending_state) = &self.pending_state {
+                        pending_state.apply_transaction(&tx, &tx_result.result.logs.storage_logs);
+                    }
                     updates_manager.extend_from_executed_transaction(
                         tx,
                         *tx_result,
@@ -1,5 +1,8 @@
 use super::types::MempoolGuard;
 use crate::l1_gas_price::L1GasPriceProvider;
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
@@ -7,8 +10,9 @@ use tokio::sync::watch;
 use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
 use zksync_config::configs::chain::MempoolConfig;
 
-use zksync_dal::ConnectionPool;
-use zksync_mempool::L2TxFilter;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_mempool::{L2TxFilter, MempoolSnapshot};
+use zksync_types::Transaction;
 
 /// Creates a mempool filter for L2 transactions based on the current L1 gas price.
 /// The filter is used to filter out transactions from the mempool that do not cover expenses
@@ -34,6 +38,8 @@ pub struct MempoolFetcher<G> {
     l1_gas_price_provider: Arc<G>,
     sync_interval: Duration,
     sync_batch_size: usize,
+    snapshot_path: Option<PathBuf>,
+    snapshot_interval: Duration,
 }
 
 impl<G: L1GasPriceProvider> MempoolFetcher<G> {
@@ -47,6 +53,8 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
             l1_gas_price_provider,
             sync_interval: config.sync_interval(),
             sync_batch_size: config.sync_batch_size,
+            snapshot_path: config.snapshot_path.as_ref().map(PathBuf::from),
+            snapshot_interval: config.snapshot_interval(),
         }
     }
 
@@ -67,17 +75,30 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
                     .await;
                 vlog::info!("Number of stuck txs was removed: {}", removed_txs);
             }
-            storage.transactions_dal().reset_mempool().await;
+            if !self.restore_snapshot(&mut storage).await {
+                storage.transactions_dal().reset_mempool().await;
+            }
         }
 
+        let mut last_snapshot_at = Instant::now();
         loop {
             if *stop_receiver.borrow() {
                 vlog::info!("Stop signal received, mempool is shutting down");
+                self.save_snapshot().await;
                 break;
             }
             let started_at = Instant::now();
             let mut storage = pool.access_storage_tagged("state_keeper").await;
             let mempool_info = self.mempool.get_mempool_info();
+            let rejected_txs: Vec<_> = mempool_info
+                .evicted_transactions
+                .into_iter()
+                .map(|evicted| (evicted.hash, evicted.reason.to_string()))
+                .collect();
+            storage
+                .transactions_dal()
+                .mark_txs_as_rejected(&rejected_txs)
+                .await;
             let l2_tx_filter = l2_tx_filter(self.l1_gas_price_provider.as_ref(), fair_l2_gas_price);
 
             let (transactions, nonces) = storage
@@ -93,9 +114,98 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
             let all_transactions_loaded = transactions.len() < self.sync_batch_size;
             self.mempool.insert(transactions, nonces);
             metrics::histogram!("server.state_keeper.mempool_sync", started_at.elapsed());
+            if last_snapshot_at.elapsed() >= self.snapshot_interval {
+                self.save_snapshot().await;
+                last_snapshot_at = Instant::now();
+            }
             if all_transactions_loaded {
                 tokio::time::sleep(self.sync_interval).await;
             }
         }
     }
+
+    /// Restores the mempool from the snapshot persisted before the restart and reconciles it
+    /// with the data in Postgres. Returns `false` if there is no snapshot to restore from.
+    async fn restore_snapshot(&mut self, storage: &mut StorageProcessor<'_>) -> bool {
+        let Some(path) = self.snapshot_path.clone() else {
+            return false;
+        };
+        let started_at = Instant::now();
+        let snapshot = tokio::task::spawn_blocking({
+            let path = path.clone();
+            move || read_snapshot(&path)
+        })
+        .await
+        .unwrap();
+        let mut snapshot = match snapshot {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => {
+                vlog::info!(
+                    "No mempool snapshot at {path:?}, mempool will be loaded from Postgres"
+                );
+                return false;
+            }
+            Err(err) => {
+                vlog::warn!(
+                    "Failed reading mempool snapshot at {path:?}: {err}; mempool will be loaded from Postgres"
+                );
+                return false;
+            }
+        };
+
+        let mut transactions_dal = storage.transactions_dal();
+        let pending_transactions = transactions_dal
+            .restore_mempool(&snapshot.tx_hashes())
+            .await;
+        let pending_tx_hashes: HashSet<_> =
+            pending_transactions.iter().map(Transaction::hash).collect();
+        let next_priority_id = transactions_dal.next_priority_id().await;
+        let nonces = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&snapshot.accounts())
+            .await
+            .unwrap();
+        snapshot.reconcile(next_priority_id, &pending_tx_hashes, &nonces);
+        self.mempool.restore(snapshot, pending_transactions);
+
+        vlog::info!(
+            "Restored mempool from snapshot at {path:?} with {} pending transactions in {:?}",
+            pending_tx_hashes.len(),
+            started_at.elapsed()
+        );
+        metrics::histogram!("server.state_keeper.mempool_restore", started_at.elapsed());
+        true
+    }
+
+    async fn save_snapshot(&self) {
+        let Some(path) = self.snapshot_path.clone() else {
+            return;
+        };
+        let started_at = Instant::now();
+        let snapshot = self.mempool.snapshot();
+        let result = tokio::task::spawn_blocking(move || write_snapshot(&path, &snapshot))
+            .await
+            .unwrap();
+        if let Err(err) = result {
+            vlog::warn!("Failed persisting mempool snapshot: {err}");
+        }
+        metrics::histogram!("server.state_keeper.mempool_snapshot", started_at.elapsed());
+    }
+}
+
+fn read_snapshot(path: &Path) -> io::Result<Option<MempoolSnapshot>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    Ok(Some(serde_json::from_slice(&bytes)?))
+}
+
+fn write_snapshot(path: &Path, snapshot: &MempoolSnapshot) -> io::Result<()> {
+    let bytes = serde_json::to_vec(snapshot)?;
+    // Write to a temporary file first, so that the snapshot is replaced atomically.
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, bytes)?;
+    std::fs::rename(&tmp_path, path)
 }
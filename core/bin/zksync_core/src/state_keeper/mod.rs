@@ -23,7 +23,11 @@ pub(crate) mod updates;
 pub use self::{
     batch_executor::MainBatchExecutorBuilder, keeper::ZkSyncStateKeeper, seal_criteria::SealManager,
 };
-pub(crate) use self::{io::MiniblockSealer, mempool_actor::MempoolFetcher, types::MempoolGuard};
+pub(crate) use self::{
+    io::{miniblock_events_bus, MiniblockEventsSender, MiniblockSealer, SealedMiniblockEvent},
+    mempool_actor::MempoolFetcher,
+    types::{MempoolGuard, PendingState, PendingStorage},
+};
 
 use self::io::{MempoolIO, MiniblockSealerHandle};
 use crate::l1_gas_price::L1GasPriceProvider;
@@ -1,22 +1,65 @@
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, RwLock},
 };
 
-use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
+use zksync_config::configs::chain::{MempoolConfig, MempoolOrderingPolicy, StateKeeperConfig};
+use zksync_mempool::{
+    FifoOrdering, HybridOrdering, L2TxFilter, MempoolInfo, MempoolOrdering, MempoolSnapshot,
+    MempoolStore, PriorityFeeOrdering,
+};
+use zksync_state::ReadStorage;
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, StorageKey,
+    StorageLog, StorageLogQuery, StorageValue, Transaction, H256,
 };
+use zksync_utils::bytecode::hash_bytecode;
 
 #[derive(Debug, Clone)]
 pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
 
 impl MempoolGuard {
-    pub fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
-        let store = MempoolStore::new(next_priority_id, capacity);
+    pub fn new(
+        next_priority_id: PriorityOpId,
+        capacity: u64,
+        ordering: Box<dyn MempoolOrdering>,
+    ) -> Self {
+        let store = MempoolStore::with_ordering(next_priority_id, capacity, ordering);
+        Self(Arc::new(Mutex::new(store)))
+    }
+
+    /// Creates a mempool with the ordering and limits specified in the configs.
+    pub fn from_config(
+        next_priority_id: PriorityOpId,
+        mempool_config: &MempoolConfig,
+        state_keeper_config: &StateKeeperConfig,
+    ) -> Self {
+        let mut store = MempoolStore::with_ordering(
+            next_priority_id,
+            mempool_config.capacity,
+            Self::ordering(state_keeper_config),
+        );
+        if let Some(max_txs_per_account) = mempool_config.max_txs_per_account {
+            store = store.with_max_txs_per_account(max_txs_per_account);
+        }
+        if let Some(bump_percent) = mempool_config.replacement_fee_bump_percent {
+            store = store.with_replacement_fee_bump_percent(bump_percent);
+        }
         Self(Arc::new(Mutex::new(store)))
     }
 
+    /// Creates the ordering policy for L2 transactions in the mempool based on the state keeper config.
+    pub fn ordering(config: &StateKeeperConfig) -> Box<dyn MempoolOrdering> {
+        match config.mempool_ordering {
+            MempoolOrderingPolicy::Fifo => Box::new(FifoOrdering),
+            MempoolOrderingPolicy::PriorityFee => Box::new(PriorityFeeOrdering),
+            MempoolOrderingPolicy::Hybrid => Box::new(HybridOrdering::new(
+                config.mempool_boost_ms_per_gwei(),
+                config.mempool_max_boost_ms(),
+            )),
+        }
+    }
+
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
         self.0
             .lock()
@@ -51,6 +94,117 @@ impl MempoolGuard {
             .expect("failed to acquire mempool lock")
             .get_mempool_info()
     }
+
+    pub fn snapshot(&self) -> MempoolSnapshot {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .snapshot()
+    }
+
+    pub fn restore(&mut self, snapshot: MempoolSnapshot, transactions: Vec<Transaction>) {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .restore(snapshot, transactions);
+    }
+}
+
+/// Changes made by the transactions executed by the state keeper in the pending L1 batch. These changes
+/// may not be persisted in Postgres yet (e.g., if they belong to the open miniblock). Shared with
+/// the API servers co-located with the state keeper, so that they can serve requests for the pending block.
+///
+/// Values are the latest ones written by the state keeper, so they always take precedence over
+/// the values in Postgres. The changes are cleared once the L1 batch is sealed, since at this point
+/// all of them are persisted.
+///
+/// Changes are copy-on-write: the API sandbox takes an immutable [snapshot](Self::snapshot()) without copying
+/// the changes, and the state keeper only copies them if it modifies the changes while a snapshot is in use.
+#[derive(Debug, Clone, Default)]
+pub struct PendingState(Arc<RwLock<Arc<PendingChanges>>>);
+
+/// Immutable snapshot of [`PendingState`].
+#[derive(Debug, Clone, Default)]
+pub struct PendingChanges {
+    storage: HashMap<StorageKey, StorageValue>,
+    factory_deps: HashMap<H256, Vec<u8>>,
+}
+
+impl PendingState {
+    /// Records changes made by a transaction included into the open miniblock.
+    pub fn apply_transaction(&self, tx: &Transaction, storage_logs: &[StorageLogQuery]) {
+        let writes = storage_logs
+            .iter()
+            .filter(|log| log.log_query.rw_flag)
+            .map(StorageLog::from_log_query);
+        let factory_deps = tx.execute.factory_deps.as_deref().unwrap_or_default();
+
+        let mut changes = self.0.write().expect("pending state lock is poisoned");
+        let changes = Arc::make_mut(&mut changes);
+        changes
+            .storage
+            .extend(writes.map(|log| (log.key, log.value)));
+        changes.factory_deps.extend(
+            factory_deps
+                .iter()
+                .map(|bytecode| (hash_bytecode(bytecode), bytecode.clone())),
+        );
+    }
+
+    /// Discards all changes. Should be called once all changes are persisted.
+    pub fn clear(&self) {
+        *self.0.write().expect("pending state lock is poisoned") = Arc::default();
+    }
+
+    /// Returns the pending value at the specified `key`, or `None` if the key was not modified
+    /// in the pending L1 batch.
+    pub fn read_value(&self, key: &StorageKey) -> Option<StorageValue> {
+        let changes = self.0.read().expect("pending state lock is poisoned");
+        changes.storage.get(key).copied()
+    }
+
+    /// Returns the current changes. The snapshot is not affected by the subsequent changes.
+    pub fn snapshot(&self) -> Arc<PendingChanges> {
+        let changes = self.0.read().expect("pending state lock is poisoned");
+        Arc::clone(&changes)
+    }
+}
+
+/// Storage with [`PendingChanges`] applied on top of the underlying storage.
+#[derive(Debug)]
+pub struct PendingStorage<S> {
+    storage_handle: S,
+    changes: Arc<PendingChanges>,
+}
+
+impl<S: ReadStorage> PendingStorage<S> {
+    pub fn new(storage_handle: S, changes: Arc<PendingChanges>) -> Self {
+        Self {
+            storage_handle,
+            changes,
+        }
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for PendingStorage<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        match self.changes.storage.get(key) {
+            Some(value) => *value,
+            None => self.storage_handle.read_value(key),
+        }
+    }
+
+    /// Pending changes do not influence this check; it is always delegated to the underlying storage.
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.storage_handle.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(bytecode) = self.changes.factory_deps.get(&hash) {
+            return Some(bytecode.clone());
+        }
+        self.storage_handle.load_factory_dep(hash)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -58,3 +212,69 @@ pub struct ExecutionMetricsForCriteria {
     pub l1_gas: BlockGasCount,
     pub execution_metrics: ExecutionMetrics,
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_state::InMemoryStorage;
+    use zksync_types::{AccountTreeId, U256};
+    use zksync_utils::u256_to_h256;
+
+    use super::*;
+    use crate::state_keeper::tests::{create_execution_result, create_transaction, Query};
+
+    #[test]
+    fn pending_state_basics() {
+        let pending_state = PendingState::default();
+        let tx = create_transaction(10, 100);
+        let result = create_execution_result(
+            0,
+            [
+                (U256::from(1), Query::Read(U256::from(1))),
+                (U256::from(2), Query::InitialWrite(U256::from(2))),
+                (
+                    U256::from(3),
+                    Query::RepeatedWrite(U256::from(3), U256::from(4)),
+                ),
+            ],
+        );
+        pending_state.apply_transaction(&tx, &result.result.logs.storage_logs);
+
+        let key = |idx: u64| StorageKey::new(AccountTreeId::default(), H256::from_low_u64_be(idx));
+        assert_eq!(pending_state.read_value(&key(1)), None);
+        assert_eq!(
+            pending_state.read_value(&key(2)),
+            Some(u256_to_h256(U256::from(2)))
+        );
+        assert_eq!(
+            pending_state.read_value(&key(3)),
+            Some(u256_to_h256(U256::from(4)))
+        );
+
+        let mut raw_storage = InMemoryStorage::default();
+        raw_storage.set_value(key(1), H256::repeat_byte(1));
+        raw_storage.set_value(key(3), H256::repeat_byte(3));
+        let mut storage = PendingStorage::new(&raw_storage, pending_state.snapshot());
+        assert_eq!(storage.read_value(&key(1)), H256::repeat_byte(1));
+        assert_eq!(storage.read_value(&key(2)), u256_to_h256(U256::from(2)));
+        assert_eq!(storage.read_value(&key(3)), u256_to_h256(U256::from(4)));
+
+        // The snapshot taken by `storage` is not affected by the subsequent changes.
+        let result = create_execution_result(
+            1,
+            [(
+                U256::from(1),
+                Query::RepeatedWrite(U256::from(1), U256::from(5)),
+            )],
+        );
+        pending_state.apply_transaction(&tx, &result.result.logs.storage_logs);
+        assert_eq!(
+            pending_state.read_value(&key(1)),
+            Some(u256_to_h256(U256::from(5)))
+        );
+        assert_eq!(storage.read_value(&key(1)), H256::repeat_byte(1));
+
+        pending_state.clear();
+        assert_eq!(pending_state.read_value(&key(2)), None);
+        assert_eq!(storage.read_value(&key(2)), u256_to_h256(U256::from(2)));
+    }
+}
@@ -395,6 +395,7 @@ impl StateKeeperIO for ExternalIO {
                 block_result,
                 block_context,
                 self.l2_erc20_bridge_addr,
+                None,
             )
             .await;
 
@@ -5,7 +5,7 @@ use async_trait::async_trait;
 
 use zksync_config::configs::{chain::CircuitBreakerConfig, ContractsConfig};
 use zksync_eth_client::{
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    types::{BlockFees, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     BoundEthInterface, EthInterface,
 };
 use zksync_types::web3::types::Block;
@@ -156,6 +156,16 @@ impl EthInterface for ETHDirectClientMock {
         Ok(Default::default())
     }
 
+    async fn fee_history(
+        &self,
+        _from_block: usize,
+        _block_count: usize,
+        _reward_percentiles: &[f64],
+        _component: &'static str,
+    ) -> Result<Vec<BlockFees>, Error> {
+        Ok(Default::default())
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         _component: &'static str,
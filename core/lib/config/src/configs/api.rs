@@ -1,6 +1,7 @@
 /// External uses
 use serde::Deserialize;
 /// Built-in uses
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::time::Duration;
 // Local uses
@@ -84,6 +85,22 @@ pub struct Web3JsonRpcConfig {
     /// Override value for the amount of threads used for WebSocket RPC server.
     /// If not set, the value from `threads_per_server` is used.
     pub ws_threads: Option<u32>,
+    /// Max total weight of the calls a single client (identified by its API key or IP address) can make per second.
+    /// If not set, per-client rate limiting is disabled.
+    pub rate_limit_weight_per_sec: Option<u32>,
+    /// Weights of the methods used for per-client rate limiting, in the `method=weight` format
+    /// (e.g., `eth_call=10`). Methods not listed here have the weight of 1.
+    pub rate_limit_method_weights: Option<Vec<String>>,
+    /// API keys (passed in the `x-api-key` header) identifying clients for per-client rate limiting.
+    /// Keys not listed here are ignored, so that clients cannot evade the limit by sending random keys.
+    pub rate_limit_api_keys: Option<Vec<String>>,
+    /// Whether the server is only reachable via a trusted reverse proxy overwriting `X-Forwarded-For` / `X-Real-IP`
+    /// headers. Only in this case the headers are used to identify clients for per-client rate limiting,
+    /// since otherwise they can be spoofed by the clients.
+    pub rate_limit_behind_trusted_proxy: Option<bool>,
+    /// Whether `debug_traceBlock*` methods re-execute miniblocks with no stored call traces. Re-execution is expensive,
+    /// so it is disabled by default.
+    pub replay_block_traces: Option<bool>,
 }
 
 impl Web3JsonRpcConfig {
@@ -135,6 +152,35 @@ impl Web3JsonRpcConfig {
     pub fn ws_server_threads(&self) -> usize {
         self.ws_threads.unwrap_or(self.threads_per_server) as usize
     }
+
+    pub fn rate_limit_method_weights(&self) -> HashMap<String, u32> {
+        let weights = self.rate_limit_method_weights.iter().flatten();
+        weights
+            .map(|entry| {
+                let (method, weight) = entry.split_once('=').unwrap_or_else(|| {
+                    panic!("Invalid method weight `{entry}`, expected `method=weight`")
+                });
+                let weight = weight
+                    .trim()
+                    .parse()
+                    .unwrap_or_else(|err| panic!("Invalid weight for method `{method}`: {err}"));
+                (method.trim().to_owned(), weight)
+            })
+            .collect()
+    }
+
+    pub fn rate_limit_api_keys(&self) -> HashSet<String> {
+        let api_keys = self.rate_limit_api_keys.iter().flatten();
+        api_keys.map(|key| key.trim().to_owned()).collect()
+    }
+
+    pub fn rate_limit_behind_trusted_proxy(&self) -> bool {
+        self.rate_limit_behind_trusted_proxy.unwrap_or(false)
+    }
+
+    pub fn replay_block_traces(&self) -> bool {
+        self.replay_block_traces.unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -232,6 +278,14 @@ mod tests {
                 factory_deps_cache_size_mb: Some(128),
                 http_threads: Some(128),
                 ws_threads: Some(256),
+                rate_limit_weight_per_sec: Some(100),
+                rate_limit_method_weights: Some(vec![
+                    "eth_call=10".to_owned(),
+                    "eth_getLogs=20".to_owned(),
+                ]),
+                rate_limit_api_keys: Some(vec!["key-1".to_owned(), "key-2".to_owned()]),
+                rate_limit_behind_trusted_proxy: Some(true),
+                replay_block_traces: Some(true),
             },
             explorer: ExplorerApiConfig {
                 port: 3070,
@@ -274,6 +328,11 @@ API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
 API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
 API_WEB3_JSON_RPC_HTTP_THREADS=128
 API_WEB3_JSON_RPC_WS_THREADS=256
+API_WEB3_JSON_RPC_RATE_LIMIT_WEIGHT_PER_SEC=100
+API_WEB3_JSON_RPC_RATE_LIMIT_METHOD_WEIGHTS=eth_call=10,eth_getLogs=20
+API_WEB3_JSON_RPC_RATE_LIMIT_API_KEYS=key-1,key-2
+API_WEB3_JSON_RPC_RATE_LIMIT_BEHIND_TRUSTED_PROXY=true
+API_WEB3_JSON_RPC_REPLAY_BLOCK_TRACES=true
 API_EXPLORER_PORT="3070"
 API_EXPLORER_URL="http://127.0.0.1:3070"
 API_EXPLORER_NETWORK_STATS_POLLING_INTERVAL="1000"
@@ -301,6 +360,14 @@ API_HEALTHCHECK_PORT=8081
             config.web3_json_rpc.pubsub_interval(),
             Duration::from_millis(200)
         );
+        assert_eq!(
+            config.web3_json_rpc.rate_limit_method_weights(),
+            HashMap::from([("eth_call".to_owned(), 10), ("eth_getLogs".to_owned(), 20)])
+        );
+        assert_eq!(
+            config.web3_json_rpc.rate_limit_api_keys(),
+            HashSet::from(["key-1".to_owned(), "key-2".to_owned()])
+        );
         assert_eq!(
             config.explorer.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.explorer.port)
@@ -99,6 +99,29 @@ pub struct StateKeeperConfig {
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
     pub save_call_traces: bool,
+
+    /// Policy used to order L2 transactions in the mempool.
+    #[serde(default)]
+    pub mempool_ordering: MempoolOrderingPolicy,
+    /// Time boost (in ms) given to a transaction per 1 gwei of its priority fee.
+    /// Only used by the hybrid ordering policy.
+    pub mempool_boost_ms_per_gwei: Option<u64>,
+    /// Max time boost (in ms) a transaction can get from its priority fee.
+    /// Only used by the hybrid ordering policy.
+    pub mempool_max_boost_ms: Option<u64>,
+}
+
+/// Policy used by the mempool to order L2 transactions for execution.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MempoolOrderingPolicy {
+    /// Transactions are executed in the order they were received in.
+    #[default]
+    Fifo,
+    /// Transactions with the highest priority fee are executed first.
+    PriorityFee,
+    /// Transactions are executed in the order they were received in, with the priority fee
+    /// moving a transaction forward in the queue by a capped amount of time.
+    Hybrid,
 }
 
 impl StateKeeperConfig {
@@ -106,6 +129,14 @@ impl StateKeeperConfig {
         envy_load("state_keeper", "CHAIN_STATE_KEEPER_")
     }
 
+    pub fn mempool_boost_ms_per_gwei(&self) -> u64 {
+        self.mempool_boost_ms_per_gwei.unwrap_or(10)
+    }
+
+    pub fn mempool_max_boost_ms(&self) -> u64 {
+        self.mempool_max_boost_ms.unwrap_or(60_000)
+    }
+
     pub fn base_system_contracts_hashes(&self) -> BaseSystemContractsHashes {
         BaseSystemContractsHashes {
             bootloader: self.bootloader_hash,
@@ -159,6 +190,16 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Max number of pending L2 transactions per initiator account. Not limited if not specified.
+    pub max_txs_per_account: Option<usize>,
+    /// Min percentage by which a transaction replacing another one with the same nonce must bump
+    /// both fee values. Replacements are not checked if not specified.
+    pub replacement_fee_bump_percent: Option<u64>,
+    /// Path to the file the mempool state is periodically persisted to, so that it can be restored
+    /// after a restart. The mempool is not persisted if not specified.
+    pub snapshot_path: Option<String>,
+    /// Interval between persisting mempool snapshots. Only used if `snapshot_path` is specified.
+    pub snapshot_interval_ms: Option<u64>,
 }
 
 impl MempoolConfig {
@@ -174,6 +215,10 @@ impl MempoolConfig {
         Duration::from_millis(self.delay_interval)
     }
 
+    pub fn snapshot_interval(&self) -> Duration {
+        Duration::from_millis(self.snapshot_interval_ms.unwrap_or(10_000))
+    }
+
     pub fn from_env() -> Self {
         envy_load("mempool", "CHAIN_MEMPOOL_")
     }
@@ -210,6 +255,9 @@ mod tests {
                 default_aa_hash: H256::from(&[254; 32]),
                 validation_computational_gas_limit: 10_000_000,
                 save_call_traces: false,
+                mempool_ordering: MempoolOrderingPolicy::Hybrid,
+                mempool_boost_ms_per_gwei: Some(20),
+                mempool_max_boost_ms: None,
             },
             operations_manager: OperationsManagerConfig {
                 delay_interval: 100,
@@ -221,6 +269,10 @@ mod tests {
                 stuck_tx_timeout: 10,
                 remove_stuck_txs: true,
                 delay_interval: 100,
+                max_txs_per_account: Some(64),
+                replacement_fee_bump_percent: Some(10),
+                snapshot_path: Some("./db/mempool_snapshot.json".to_owned()),
+                snapshot_interval_ms: Some(5000),
             },
             circuit_breaker: CircuitBreakerConfig {
                 sync_interval_ms: 1000,
@@ -254,6 +306,8 @@ CHAIN_STATE_KEEPER_BOOTLOADER_HASH="0xfefefefefefefefefefefefefefefefefefefefefe
 CHAIN_STATE_KEEPER_DEFAULT_AA_HASH="0xfefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefe"
 CHAIN_STATE_KEEPER_VALIDATION_COMPUTATIONAL_GAS_LIMIT="10000000"
 CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
+CHAIN_STATE_KEEPER_MEMPOOL_ORDERING="Hybrid"
+CHAIN_STATE_KEEPER_MEMPOOL_BOOST_MS_PER_GWEI="20"
 CHAIN_OPERATIONS_MANAGER_DELAY_INTERVAL="100"
 CHAIN_MEMPOOL_SYNC_INTERVAL_MS="10"
 CHAIN_MEMPOOL_SYNC_BATCH_SIZE="1000"
@@ -261,6 +315,10 @@ CHAIN_MEMPOOL_STUCK_TX_TIMEOUT="10"
 CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
 CHAIN_MEMPOOL_DELAY_INTERVAL="100"
 CHAIN_MEMPOOL_CAPACITY="1000000"
+CHAIN_MEMPOOL_MAX_TXS_PER_ACCOUNT="64"
+CHAIN_MEMPOOL_REPLACEMENT_FEE_BUMP_PERCENT="10"
+CHAIN_MEMPOOL_SNAPSHOT_PATH="./db/mempool_snapshot.json"
+CHAIN_MEMPOOL_SNAPSHOT_INTERVAL_MS="5000"
 CHAIN_CIRCUIT_BREAKER_SYNC_INTERVAL_MS="1000"
 CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
 CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
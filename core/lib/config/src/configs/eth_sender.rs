@@ -3,7 +3,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Workspace uses
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, H256};
 // Local uses
 use super::envy_load;
 
@@ -55,6 +55,13 @@ pub struct SenderConfig {
     pub aggregated_block_prove_deadline: u64,
     pub aggregated_block_execute_deadline: u64,
     pub timestamp_criteria_max_allowed_lag: usize,
+    /// If set, committing L1 batches is postponed while the L1 base fee spikes, i.e. while the base fee
+    /// of the last L1 block exceeds the median base fee more than this number of times.
+    pub aggregated_block_commit_fee_spike_ratio: Option<f64>,
+    /// Max number of seconds committing of L1 batches can be postponed for because of a base fee spike.
+    /// Only used if `aggregated_block_commit_fee_spike_ratio` is set. If not set, defaults to
+    /// [`Self::DEFAULT_MAX_FEE_SPIKE_DELAY`].
+    pub aggregated_block_commit_max_fee_spike_delay: Option<u64>,
 
     /// L1 batches will only be executed on L1 contract after they are at least this number of seconds old.
     /// Note that this number must be slightly higher than the one set on the contract,
@@ -62,9 +69,28 @@ pub struct SenderConfig {
     pub l1_batch_min_age_before_execute_seconds: Option<u64>,
     // Max acceptable fee for sending tx it acts as a safeguard to prevent sending tx with very high fees.
     pub max_acceptable_priority_fee_in_gwei: u64,
+
+    /// If set, confirmed commit / prove / execute operations are reported to this URL for transactions
+    /// listed in `tx_status_webhook_tx_hashes` or initiated by `tx_status_webhook_addresses`.
+    pub tx_status_webhook_url: Option<String>,
+    /// Accounts whose transactions are reported to `tx_status_webhook_url`.
+    #[serde(default)]
+    pub tx_status_webhook_addresses: Vec<Address>,
+    /// Transactions reported to `tx_status_webhook_url`.
+    #[serde(default)]
+    pub tx_status_webhook_tx_hashes: Vec<H256>,
 }
 
 impl SenderConfig {
+    /// Default value of `aggregated_block_commit_max_fee_spike_delay` (10 minutes).
+    pub const DEFAULT_MAX_FEE_SPIKE_DELAY: u64 = 600;
+
+    /// Returns `self.aggregated_block_commit_max_fee_spike_delay` or its default value if it's not set.
+    pub fn aggregated_block_commit_max_fee_spike_delay(&self) -> u64 {
+        self.aggregated_block_commit_max_fee_spike_delay
+            .unwrap_or(Self::DEFAULT_MAX_FEE_SPIKE_DELAY)
+    }
+
     /// Converts `self.tx_poll_period` into `Duration`.
     pub fn tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.tx_poll_period)
@@ -86,7 +112,7 @@ impl SenderConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct GasAdjusterConfig {
     /// Priority Fee to be used by GasAdjuster
     pub default_priority_fee_per_gas: u64,
@@ -104,6 +130,12 @@ pub struct GasAdjusterConfig {
     pub poll_period: u64,
     /// Max number of l1 gas price that is allowed to be used in state keeper.
     pub max_l1_gas_price: Option<u64>,
+    /// Percentiles (in the `[0, 100]` range, sorted ascending) of priority fees paid in recent L1 blocks
+    /// tracked by GasAdjuster. If set, the priority fee for L1 transactions is chosen from these percentiles,
+    /// using higher percentiles the longer a transaction stays in the mempool; `default_priority_fee_per_gas`
+    /// is used as the lower bound. Otherwise, `default_priority_fee_per_gas` is used.
+    #[serde(default)]
+    pub priority_fee_percentiles: Vec<f64>,
 }
 
 impl GasAdjusterConfig {
@@ -124,7 +156,7 @@ impl GasAdjusterConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::configs::test_utils::{hash, set_env};
+    use crate::configs::test_utils::{addr, hash, set_env};
 
     fn expected_config() -> ETHSenderConfig {
         ETHSenderConfig {
@@ -137,6 +169,8 @@ mod tests {
                 max_eth_tx_data_size: 120_000,
 
                 timestamp_criteria_max_allowed_lag: 30,
+                aggregated_block_commit_fee_spike_ratio: Some(2.5),
+                aggregated_block_commit_max_fee_spike_delay: Some(600),
                 max_aggregated_blocks_to_commit: 3,
                 max_aggregated_blocks_to_execute: 4,
                 wait_confirmations: Some(1),
@@ -146,6 +180,13 @@ mod tests {
                 proof_sending_mode: ProofSendingMode::SkipEveryProof,
                 l1_batch_min_age_before_execute_seconds: Some(1000),
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
+                tx_status_webhook_url: Some("http://127.0.0.1:8080/tx-status".to_owned()),
+                tx_status_webhook_addresses: vec![addr(
+                    "0x0000000000000000000000000000000000000001",
+                )],
+                tx_status_webhook_tx_hashes: vec![hash(
+                    "0x1111111111111111111111111111111111111111111111111111111111111111",
+                )],
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -156,6 +197,7 @@ mod tests {
                 internal_enforced_l1_gas_price: None,
                 poll_period: 15,
                 max_l1_gas_price: Some(100000000),
+                priority_fee_percentiles: vec![10.0, 50.0, 90.0],
             },
         }
     }
@@ -176,6 +218,7 @@ ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_B="1.0005"
 ETH_SENDER_GAS_ADJUSTER_INTERNAL_L1_PRICING_MULTIPLIER="0.8"
 ETH_SENDER_GAS_ADJUSTER_POLL_PERIOD="15"
 ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE="100000000"
+ETH_SENDER_GAS_ADJUSTER_PRIORITY_FEE_PERCENTILES="10,50,90"
 ETH_SENDER_WAIT_FOR_PROOFS="false"
 ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
 ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
@@ -184,10 +227,15 @@ ETH_SENDER_SENDER_AGGREGATED_BLOCK_COMMIT_DEADLINE="30"
 ETH_SENDER_SENDER_AGGREGATED_BLOCK_PROVE_DEADLINE="3000"
 ETH_SENDER_SENDER_AGGREGATED_BLOCK_EXECUTE_DEADLINE="4000"
 ETH_SENDER_SENDER_TIMESTAMP_CRITERIA_MAX_ALLOWED_LAG="30"
+ETH_SENDER_SENDER_AGGREGATED_BLOCK_COMMIT_FEE_SPIKE_RATIO="2.5"
+ETH_SENDER_SENDER_AGGREGATED_BLOCK_COMMIT_MAX_FEE_SPIKE_DELAY="600"
 ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
 ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
 ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
 ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
+ETH_SENDER_SENDER_TX_STATUS_WEBHOOK_URL="http://127.0.0.1:8080/tx-status"
+ETH_SENDER_SENDER_TX_STATUS_WEBHOOK_ADDRESSES="0x0000000000000000000000000000000000000001"
+ETH_SENDER_SENDER_TX_STATUS_WEBHOOK_TX_HASHES="0x1111111111111111111111111111111111111111111111111111111111111111"
         "#;
         set_env(config);
 
@@ -17,6 +17,13 @@ pub struct HouseKeeperConfig {
     pub fri_witness_generator_job_retrying_interval_ms: u64,
     pub prover_db_pool_size: u32,
     pub fri_prover_stats_reporting_interval_ms: u64,
+    /// Interval between runs of the blob retention sweeper. If not set, the sweeper is disabled.
+    pub blob_retention_sweeping_interval_ms: Option<u64>,
+    /// Retention policies for object store buckets in the `{bucket}:{l1_batch_count}` format,
+    /// e.g. `prover_jobs:1000`. Blobs in a bucket are removed once they belong to L1 batches
+    /// that are more than `l1_batch_count` batches older than the last batch proven on L1.
+    #[serde(default)]
+    pub blob_retention_policies: Vec<String>,
 }
 
 impl HouseKeeperConfig {
@@ -45,6 +52,11 @@ mod tests {
             fri_witness_generator_job_retrying_interval_ms: 30_000,
             prover_db_pool_size: 2,
             fri_prover_stats_reporting_interval_ms: 30_000,
+            blob_retention_sweeping_interval_ms: Some(3_600_000),
+            blob_retention_policies: vec![
+                "prover_jobs:1000".to_owned(),
+                "witness_inputs:500".to_owned(),
+            ],
         }
     }
 
@@ -63,6 +75,8 @@ HOUSE_KEEPER_FRI_PROVER_JOB_RETRYING_INTERVAL_MS="30000"
 HOUSE_KEEPER_FRI_WITNESS_GENERATOR_JOB_RETRYING_INTERVAL_MS="30000"
 HOUSE_KEEPER_PROVER_DB_POOL_SIZE="2"
 HOUSE_KEEPER_FRI_PROVER_STATS_REPORTING_INTERVAL_MS="30000"
+HOUSE_KEEPER_BLOB_RETENTION_SWEEPING_INTERVAL_MS="3600000"
+HOUSE_KEEPER_BLOB_RETENTION_POLICIES="prover_jobs:1000,witness_inputs:500"
         "#;
         set_env(config);
         let actual = HouseKeeperConfig::from_env();
@@ -6,6 +6,23 @@ pub enum ObjectStoreMode {
     GCS,
     GCSWithCredentialFile,
     FileBacked,
+    /// AWS S3 or an S3-compatible storage (e.g., MinIO). Credentials are taken from the standard
+    /// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and (optionally) `AWS_SESSION_TOKEN` variables;
+    /// they are checked when the object store factory is created.
+    S3,
+}
+
+/// Format used to write blobs to the object store. Blobs in any format can be read regardless
+/// of this setting, so it can be changed without migrating existing blobs.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlobEncoding {
+    /// Blobs are stored as is.
+    #[default]
+    Raw,
+    /// Blobs are prefixed with a header containing a checksum of the contents.
+    Checksummed,
+    /// Same as `Checksummed`, but the blob contents are compressed using zstd.
+    Zstd,
 }
 
 /// Configuration for the object store
@@ -16,6 +33,26 @@ pub struct ObjectStoreConfig {
     pub file_backed_base_path: String,
     pub gcs_credential_file_path: String,
     pub max_retries: u16,
+    /// Endpoint of the S3-compatible storage. If not specified, the AWS endpoint for `s3_region` is used.
+    pub s3_endpoint: Option<String>,
+    /// Region of the S3 bucket; `us-east-1` if not specified.
+    pub s3_region: Option<String>,
+    /// Whether to use path-style addressing (`{endpoint}/{bucket}/{key}`) instead of virtual-hosted
+    /// style (`{bucket}.{endpoint}/{key}`). Usually required for MinIO and other self-hosted storages.
+    #[serde(default)]
+    pub s3_path_style: bool,
+    /// Format used to write blobs.
+    #[serde(default)]
+    pub blob_encoding: BlobEncoding,
+    /// Directory for the local LRU cache of blobs. If not specified, blobs are not cached.
+    /// Blobs cached in the directory by previous runs are reused.
+    pub local_cache_path: Option<String>,
+    /// Capacity of the local cache in megabytes; 1024 if not specified.
+    pub local_cache_capacity_mb: Option<u64>,
+    /// Bucket to which all writes and removals are mirrored (e.g., during migration between buckets).
+    /// Reads fall back to this bucket if an object is not found in the main bucket.
+    /// The mirror uses the same store mode and credentials as the main bucket.
+    pub mirror_bucket_base_url: Option<String>,
 }
 
 impl ObjectStoreConfig {
@@ -26,6 +63,16 @@ impl ObjectStoreConfig {
     pub fn public_from_env() -> Self {
         envy_load("public_object_store", "PUBLIC_OBJECT_STORE_")
     }
+
+    /// Returns the capacity of the local cache in bytes.
+    pub fn local_cache_capacity_bytes(&self) -> u64 {
+        self.local_cache_capacity_mb.unwrap_or(1_024) * 1_024 * 1_024
+    }
+
+    /// Returns the S3 region, defaulting to `us-east-1`.
+    pub fn s3_region(&self) -> &str {
+        self.s3_region.as_deref().unwrap_or("us-east-1")
+    }
 }
 
 #[cfg(test)]
@@ -40,6 +87,13 @@ mod tests {
             file_backed_base_path: "artifacts".to_string(),
             gcs_credential_file_path: "/path/to/credentials.json".to_string(),
             max_retries: 5,
+            s3_endpoint: Some("http://127.0.0.1:9000".to_string()),
+            s3_region: Some("eu-central-1".to_string()),
+            s3_path_style: true,
+            blob_encoding: BlobEncoding::Zstd,
+            local_cache_path: Some("/tmp/object_store_cache".to_string()),
+            local_cache_capacity_mb: Some(512),
+            mirror_bucket_base_url: Some(format!("{bucket_base_url}_mirror")),
         }
     }
 
@@ -51,6 +105,13 @@ OBJECT_STORE_MODE="FileBacked"
 OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
 OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
 OBJECT_STORE_MAX_RETRIES="5"
+OBJECT_STORE_S3_ENDPOINT="http://127.0.0.1:9000"
+OBJECT_STORE_S3_REGION="eu-central-1"
+OBJECT_STORE_S3_PATH_STYLE="true"
+OBJECT_STORE_BLOB_ENCODING="Zstd"
+OBJECT_STORE_LOCAL_CACHE_PATH="/tmp/object_store_cache"
+OBJECT_STORE_LOCAL_CACHE_CAPACITY_MB="512"
+OBJECT_STORE_MIRROR_BUCKET_BASE_URL="/base/url_mirror"
         "#;
         set_env(config);
         let actual = ObjectStoreConfig::from_env();
@@ -65,6 +126,13 @@ PUBLIC_OBJECT_STORE_MODE="FileBacked"
 PUBLIC_OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
 PUBLIC_OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
 PUBLIC_OBJECT_STORE_MAX_RETRIES="5"
+PUBLIC_OBJECT_STORE_S3_ENDPOINT="http://127.0.0.1:9000"
+PUBLIC_OBJECT_STORE_S3_REGION="eu-central-1"
+PUBLIC_OBJECT_STORE_S3_PATH_STYLE="true"
+PUBLIC_OBJECT_STORE_BLOB_ENCODING="Zstd"
+PUBLIC_OBJECT_STORE_LOCAL_CACHE_PATH="/tmp/object_store_cache"
+PUBLIC_OBJECT_STORE_LOCAL_CACHE_CAPACITY_MB="512"
+PUBLIC_OBJECT_STORE_MIRROR_BUCKET_BASE_URL="/public_base_url_mirror"
         "#;
         set_env(config);
         let actual = ObjectStoreConfig::public_from_env();
@@ -1,7 +1,7 @@
 use bigdecimal::BigDecimal;
 use sqlx::Row;
 
-use std::time::Instant;
+use std::{ops::RangeInclusive, str::FromStr, time::Instant};
 
 use zksync_config::constants::EMPTY_UNCLES_HASH;
 use zksync_types::{
@@ -10,7 +10,7 @@ use zksync_types::{
     vm_trace::Call,
     web3::types::{BlockHeader, U64},
     zk_evm::zkevm_opcode_defs::system_params,
-    Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, H160, H2048, H256, U256,
+    Address, Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, H160, H2048, H256, U256,
 };
 use zksync_utils::{bigdecimal_to_u256, miniblock_hash};
 
@@ -20,7 +20,18 @@ use crate::models::{
 };
 use crate::{SqlxError, StorageProcessor};
 
-const BLOCK_GAS_LIMIT: u32 = system_params::VM_INITIAL_FRAME_ERGS;
+/// Gas limit reported for miniblocks in the web3 API.
+pub const BLOCK_GAS_LIMIT: u32 = system_params::VM_INITIAL_FRAME_ERGS;
+
+/// Returns the value of `stage` used in queries to select the `l1_batches` column referencing
+/// the L1 transaction that moves a batch to the stage.
+fn stage_param(stage: api::L1BatchStage) -> &'static str {
+    match stage {
+        api::L1BatchStage::Committed => "committed",
+        api::L1BatchStage::Proven => "proven",
+        api::L1BatchStage::Executed => "executed",
+    }
+}
 
 #[derive(Debug)]
 pub struct BlocksWeb3Dal<'a, 'c> {
@@ -215,6 +226,85 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(blocks.collect())
     }
 
+    /// Returns the base fee and the total gas used for each miniblock in the `[from_block, to_block]` range,
+    /// ordered by the miniblock number.
+    pub async fn get_fee_history(
+        &mut self,
+        from_block: MiniblockNumber,
+        to_block: MiniblockNumber,
+    ) -> Result<Vec<(MiniblockNumber, U256, U256)>, SqlxError> {
+        let started_at = Instant::now();
+        let rows = sqlx::query!(
+            "SELECT miniblocks.number, miniblocks.base_fee_per_gas, \
+                COALESCE(SUM(transactions.gas_limit - transactions.refunded_gas), 0) AS \"gas_used!\" \
+            FROM miniblocks \
+            LEFT JOIN transactions ON transactions.miniblock_number = miniblocks.number \
+            WHERE miniblocks.number BETWEEN $1 AND $2 \
+            GROUP BY miniblocks.number \
+            ORDER BY miniblocks.number ASC",
+            from_block.0 as i64,
+            to_block.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let fee_history = rows
+            .into_iter()
+            .map(|row| {
+                (
+                    MiniblockNumber(row.number as u32),
+                    bigdecimal_to_u256(row.base_fee_per_gas),
+                    bigdecimal_to_u256(row.gas_used),
+                )
+            })
+            .collect();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_fee_history");
+        Ok(fee_history)
+    }
+
+    /// Returns the gas used and the effective priority fee per gas for each transaction included
+    /// into a miniblock in the `[from_block, to_block]` range. The result is ordered by the miniblock number
+    /// and, within each miniblock, by the priority fee.
+    pub async fn get_tx_priority_fees(
+        &mut self,
+        from_block: MiniblockNumber,
+        to_block: MiniblockNumber,
+    ) -> Result<Vec<(MiniblockNumber, U256, U256)>, SqlxError> {
+        let started_at = Instant::now();
+        let rows = sqlx::query!(
+            "SELECT transactions.miniblock_number AS \"miniblock_number!\", \
+                COALESCE(transactions.gas_limit - transactions.refunded_gas, 0) AS \"gas_used!\", \
+                GREATEST( \
+                    LEAST( \
+                        COALESCE(transactions.max_priority_fee_per_gas, 0), \
+                        COALESCE(transactions.max_fee_per_gas, 0) - miniblocks.base_fee_per_gas \
+                    ), \
+                    0 \
+                ) AS \"priority_fee!\" \
+            FROM transactions \
+            INNER JOIN miniblocks ON miniblocks.number = transactions.miniblock_number \
+            WHERE transactions.miniblock_number BETWEEN $1 AND $2 \
+            ORDER BY transactions.miniblock_number ASC, \"priority_fee!\" ASC",
+            from_block.0 as i64,
+            to_block.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let priority_fees = rows
+            .into_iter()
+            .map(|row| {
+                (
+                    MiniblockNumber(row.miniblock_number as u32),
+                    bigdecimal_to_u256(row.gas_used),
+                    bigdecimal_to_u256(row.priority_fee),
+                )
+            })
+            .collect();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_tx_priority_fees");
+        Ok(priority_fees)
+    }
+
     pub async fn resolve_block_id(
         &mut self,
         block_id: api::BlockId,
@@ -336,6 +426,109 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(result)
     }
 
+    /// Returns transactions from L1 batches in the specified range that have reached `stage`
+    /// (i.e., the corresponding L1 transaction is confirmed) and either have one of `tx_hashes`
+    /// or are initiated by one of `initiators`. The result is ordered by the L1 batch number
+    /// and the index of the transaction in the batch.
+    pub async fn get_tx_status_updates(
+        &mut self,
+        l1_batches: RangeInclusive<L1BatchNumber>,
+        stage: api::L1BatchStage,
+        tx_hashes: &[H256],
+        initiators: &[Address],
+    ) -> Result<Vec<api::TxStatusUpdate>, SqlxError> {
+        let started_at = Instant::now();
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(|hash| hash.0.to_vec()).collect();
+        let initiators: Vec<_> = initiators
+            .iter()
+            .map(|address| address.0.to_vec())
+            .collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT transactions.hash, transactions.initiator_address,
+                transactions.l1_batch_number as "l1_batch_number!", eth_txs_history.tx_hash AS eth_tx_hash
+            FROM transactions
+            INNER JOIN l1_batches ON l1_batches.number = transactions.l1_batch_number
+            INNER JOIN eth_txs_history
+                ON (eth_txs_history.eth_tx_id = CASE $5::text
+                        WHEN 'committed' THEN l1_batches.eth_commit_tx_id
+                        WHEN 'proven' THEN l1_batches.eth_prove_tx_id
+                        WHEN 'executed' THEN l1_batches.eth_execute_tx_id
+                    END
+                    AND eth_txs_history.confirmed_at IS NOT NULL)
+            WHERE transactions.l1_batch_number BETWEEN $1 AND $2
+                AND (transactions.hash = ANY($3) OR transactions.initiator_address = ANY($4))
+            ORDER BY transactions.l1_batch_number ASC, transactions.l1_batch_tx_index ASC
+            "#,
+            l1_batches.start().0 as i64,
+            l1_batches.end().0 as i64,
+            &tx_hashes,
+            &initiators,
+            stage_param(stage)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let updates = rows
+            .into_iter()
+            .map(|row| api::TxStatusUpdate {
+                transaction_hash: H256::from_slice(&row.hash),
+                initiator_address: Address::from_slice(&row.initiator_address),
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                status: stage,
+                eth_tx_hash: H256::from_str(&row.eth_tx_hash).unwrap(),
+            })
+            .collect();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_tx_status_updates");
+        Ok(updates)
+    }
+
+    /// Returns updates for L1 batches in the specified range that have reached `stage`, i.e., the corresponding
+    /// L1 transaction is confirmed. If `stage` is `None`, returns updates for all sealed batches in the range.
+    /// The result is ordered by the L1 batch number.
+    pub async fn get_l1_batch_status_updates(
+        &mut self,
+        l1_batches: RangeInclusive<L1BatchNumber>,
+        stage: Option<api::L1BatchStage>,
+    ) -> Result<Vec<api::L1BatchStatusUpdate>, SqlxError> {
+        let started_at = Instant::now();
+        // If `stage` is not specified, no L1 transactions are joined.
+        let rows = sqlx::query!(
+            r#"
+            SELECT l1_batches.number, l1_batches.timestamp, eth_txs_history.tx_hash AS "eth_tx_hash?"
+            FROM l1_batches
+            LEFT JOIN eth_txs_history
+                ON (eth_txs_history.eth_tx_id = CASE $3::text
+                        WHEN 'committed' THEN l1_batches.eth_commit_tx_id
+                        WHEN 'proven' THEN l1_batches.eth_prove_tx_id
+                        WHEN 'executed' THEN l1_batches.eth_execute_tx_id
+                    END
+                    AND eth_txs_history.confirmed_at IS NOT NULL)
+            WHERE l1_batches.number BETWEEN $1 AND $2
+                AND ($3::text IS NULL OR eth_txs_history.tx_hash IS NOT NULL)
+            ORDER BY l1_batches.number ASC
+            "#,
+            l1_batches.start().0 as i64,
+            l1_batches.end().0 as i64,
+            stage.map(stage_param)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let status = stage.map_or(api::L1BatchStatus::Sealed, api::L1BatchStatus::from);
+        let updates = rows
+            .into_iter()
+            .map(|row| api::L1BatchStatusUpdate {
+                l1_batch_number: L1BatchNumber(row.number as u32),
+                status,
+                timestamp: row.timestamp as u64,
+                eth_tx_hash: row.eth_tx_hash.map(|hash| H256::from_str(&hash).unwrap()),
+            })
+            .collect();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_l1_batch_status_updates");
+        Ok(updates)
+    }
+
     pub async fn get_trace_for_miniblock(&mut self, block_number: MiniblockNumber) -> Vec<Call> {
         sqlx::query_as!(
             CallTrace,
@@ -355,10 +548,20 @@ impl BlocksWeb3Dal<'_, '_> {
 #[cfg(test)]
 mod tests {
     use db_test_macro::db_test;
-    use zksync_types::{block::MiniblockHeader, MiniblockNumber};
+    use sqlx::types::chrono::Utc;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType,
+        block::{L1BatchHeader, MiniblockHeader},
+        fee::TransactionExecutionMetrics,
+        MiniblockNumber,
+    };
 
     use super::*;
-    use crate::{tests::create_miniblock_header, ConnectionPool};
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
 
     #[db_test(dal_crate)]
     async fn getting_web3_block_and_tx_count(connection_pool: ConnectionPool) {
@@ -497,4 +700,197 @@ mod tests {
             .await;
         assert_eq!(miniblock_number.unwrap(), None);
     }
+
+    #[db_test(dal_crate)]
+    async fn getting_fee_history_for_empty_miniblocks(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..3 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+        }
+
+        let fee_history = conn
+            .blocks_web3_dal()
+            .get_fee_history(MiniblockNumber(1), MiniblockNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            fee_history,
+            [
+                (MiniblockNumber(1), 100.into(), U256::zero()),
+                (MiniblockNumber(2), 100.into(), U256::zero()),
+            ]
+        );
+
+        let priority_fees = conn
+            .blocks_web3_dal()
+            .get_tx_priority_fees(MiniblockNumber(0), MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert!(priority_fees.is_empty());
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_tx_status_updates(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&header, Default::default())
+            .await;
+
+        let txs = [mock_l2_transaction(), mock_l2_transaction()];
+        for tx in &txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        let execution_results: Vec<_> = txs.iter().cloned().map(mock_execution_result).collect();
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &execution_results)
+            .await;
+
+        let all_batches = L1BatchNumber(0)..=L1BatchNumber(1);
+        let tx_hashes = [txs[0].hash(), txs[1].hash()];
+        let updates = conn
+            .blocks_web3_dal()
+            .get_tx_status_updates(
+                all_batches.clone(),
+                api::L1BatchStage::Committed,
+                &tx_hashes,
+                &[],
+            )
+            .await
+            .unwrap();
+        assert!(updates.is_empty()); // The batch is not committed yet
+
+        let commit_tx_hash = H256::repeat_byte(1);
+        conn.eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::CommitBlocks,
+                commit_tx_hash,
+                Utc::now(),
+            )
+            .await;
+
+        let updates = conn
+            .blocks_web3_dal()
+            .get_tx_status_updates(
+                all_batches.clone(),
+                api::L1BatchStage::Committed,
+                &tx_hashes[1..],
+                &[txs[0].initiator_account()],
+            )
+            .await
+            .unwrap();
+        let expected_updates: Vec<_> = txs
+            .iter()
+            .map(|tx| api::TxStatusUpdate {
+                transaction_hash: tx.hash(),
+                initiator_address: tx.initiator_account(),
+                l1_batch_number: L1BatchNumber(1),
+                status: api::L1BatchStage::Committed,
+                eth_tx_hash: commit_tx_hash,
+            })
+            .collect();
+        assert_eq!(updates, expected_updates);
+
+        let updates = conn
+            .blocks_web3_dal()
+            .get_tx_status_updates(all_batches, api::L1BatchStage::Proven, &tx_hashes, &[])
+            .await
+            .unwrap();
+        assert!(updates.is_empty());
+        let updates = conn
+            .blocks_web3_dal()
+            .get_tx_status_updates(
+                L1BatchNumber(2)..=L1BatchNumber(3),
+                api::L1BatchStage::Committed,
+                &tx_hashes,
+                &[],
+            )
+            .await
+            .unwrap();
+        assert!(updates.is_empty());
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_l1_batch_status_updates(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+        for number in 1..=2 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+            );
+            conn.blocks_dal()
+                .insert_l1_batch(&header, Default::default())
+                .await;
+        }
+
+        let updates = conn
+            .blocks_web3_dal()
+            .get_l1_batch_status_updates(L1BatchNumber(1)..=L1BatchNumber(2), None)
+            .await
+            .unwrap();
+        let sealed_numbers: Vec<_> = updates
+            .iter()
+            .map(|update| update.l1_batch_number)
+            .collect();
+        assert_eq!(sealed_numbers, [L1BatchNumber(1), L1BatchNumber(2)]);
+        assert!(updates.iter().all(|update| {
+            update.status == api::L1BatchStatus::Sealed && update.eth_tx_hash.is_none()
+        }));
+        assert_eq!(updates[1].timestamp, 2);
+
+        let commit_tx_hash = H256::repeat_byte(1);
+        conn.eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::CommitBlocks,
+                commit_tx_hash,
+                Utc::now(),
+            )
+            .await;
+
+        let updates = conn
+            .blocks_web3_dal()
+            .get_l1_batch_status_updates(
+                L1BatchNumber(1)..=L1BatchNumber(2),
+                Some(api::L1BatchStage::Committed),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            updates,
+            [api::L1BatchStatusUpdate {
+                l1_batch_number: L1BatchNumber(1),
+                status: api::L1BatchStatus::Committed,
+                timestamp: 1,
+                eth_tx_hash: Some(commit_tx_hash),
+            }]
+        );
+
+        let updates = conn
+            .blocks_web3_dal()
+            .get_l1_batch_status_updates(
+                L1BatchNumber(1)..=L1BatchNumber(2),
+                Some(api::L1BatchStage::Executed),
+            )
+            .await
+            .unwrap();
+        assert!(updates.is_empty());
+    }
 }
@@ -20,7 +20,7 @@ use zksync_types::{
     EIP_712_TX_TYPE, H160, H256, U256,
 };
 use zksync_types::{ExecuteTransactionCommon, L1BatchNumber, MiniblockNumber};
-use zksync_utils::bigdecimal_to_u256;
+use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StorageTransaction {
@@ -264,6 +264,84 @@ pub fn extract_web3_transaction(db_row: PgRow, chain_id: L2ChainId) -> api::Tran
     }
 }
 
+/// Transaction receipt without logs, as stored in the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageTransactionReceipt {
+    pub tx_hash: Vec<u8>,
+    pub index_in_block: Option<i32>,
+    pub l1_batch_tx_index: Option<i32>,
+    pub block_number: Option<i64>,
+    pub error: Option<String>,
+    pub effective_gas_price: Option<BigDecimal>,
+    pub initiator_address: Vec<u8>,
+    pub transfer_to: Option<serde_json::Value>,
+    pub execute_contract_address: Option<serde_json::Value>,
+    pub tx_format: Option<i32>,
+    pub refunded_gas: i64,
+    pub gas_limit: Option<BigDecimal>,
+    pub block_hash: Option<Vec<u8>>,
+    pub l1_batch_number: Option<i64>,
+    pub contract_address: Option<Vec<u8>>,
+}
+
+impl From<StorageTransactionReceipt> for api::TransactionReceipt {
+    fn from(db_row: StorageTransactionReceipt) -> Self {
+        let status = match (db_row.block_number, db_row.error) {
+            (_, Some(_)) => Some(U64::from(0)),
+            (Some(_), None) => Some(U64::from(1)),
+            // tx not executed yet
+            _ => None,
+        };
+        let tx_type = db_row.tx_format.map(U64::from).unwrap_or_default();
+        let transaction_index = db_row.index_in_block.map(U64::from).unwrap_or_default();
+        let block_hash = db_row.block_hash.map(|bytes| H256::from_slice(&bytes));
+
+        api::TransactionReceipt {
+            transaction_hash: H256::from_slice(&db_row.tx_hash),
+            transaction_index,
+            block_hash,
+            block_number: db_row.block_number.map(U64::from),
+            l1_batch_tx_index: db_row.l1_batch_tx_index.map(U64::from),
+            l1_batch_number: db_row.l1_batch_number.map(U64::from),
+            from: H160::from_slice(&db_row.initiator_address),
+            to: db_row
+                .transfer_to
+                .or(db_row.execute_contract_address)
+                .map(|addr| {
+                    serde_json::from_value::<Address>(addr)
+                        .expect("invalid address value in the database")
+                })
+                // For better compatibility with various clients, we never return null.
+                .or_else(|| Some(Address::default())),
+            cumulative_gas_used: Default::default(),
+            gas_used: {
+                let refunded_gas: U256 = db_row.refunded_gas.into();
+                db_row.gas_limit.map(|val| {
+                    let gas_limit = bigdecimal_to_u256(val);
+                    gas_limit - refunded_gas
+                })
+            },
+            effective_gas_price: Some(
+                db_row
+                    .effective_gas_price
+                    .map(bigdecimal_to_u256)
+                    .unwrap_or_default(),
+            ),
+            contract_address: db_row
+                .contract_address
+                .map(|addr| h256_to_account_address(&H256::from_slice(&addr))),
+            logs: vec![],
+            l2_to_l1_logs: vec![],
+            status,
+            root: block_hash,
+            logs_bloom: Default::default(),
+            // Even though the Rust SDK recommends us to supply "None" for legacy transactions
+            // we always supply some number anyway to have the same behaviour as most popular RPCs
+            transaction_type: Some(tx_type),
+        }
+    }
+}
+
 impl From<StorageTransaction> for Transaction {
     fn from(tx: StorageTransaction) -> Self {
         let gas_limit = {
@@ -1,9 +1,9 @@
-use std::time::Instant;
+use std::{collections::HashMap, time::Instant};
 
 use zksync_types::{
     get_code_key, get_nonce_key,
     utils::{decompose_full_nonce, storage_key_for_standard_token_balance},
-    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey,
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, Nonce, StorageKey,
     FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
 use zksync_utils::h256_to_u256;
@@ -29,6 +29,33 @@ impl StorageWeb3Dal<'_, '_> {
         Ok(decompose_full_nonce(full_nonce).0)
     }
 
+    /// Returns the latest nonces of the specified accounts. Accounts that have never sent
+    /// a transaction are not present in the returned map.
+    pub async fn get_nonces_for_addresses(
+        &mut self,
+        addresses: &[Address],
+    ) -> Result<HashMap<Address, Nonce>, SqlxError> {
+        let nonce_keys: HashMap<_, _> = addresses
+            .iter()
+            .map(|address| (get_nonce_key(address).hashed_key(), *address))
+            .collect();
+        let storage_keys: Vec<_> = nonce_keys.keys().map(|key| key.0.to_vec()).collect();
+        let rows = sqlx::query!(
+            r#"SELECT hashed_key, value as "value!" FROM storage WHERE hashed_key = ANY($1)"#,
+            &storage_keys,
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let nonces = rows.into_iter().map(|row| {
+            let nonce_key = H256::from_slice(&row.hashed_key);
+            let full_nonce = h256_to_u256(H256::from_slice(&row.value));
+            let nonce = Nonce(decompose_full_nonce(full_nonce).0.as_u32());
+            (nonce_keys[&nonce_key], nonce)
+        });
+        Ok(nonces.collect())
+    }
+
     pub async fn standard_token_historical_balance(
         &mut self,
         token_id: AccountTreeId,
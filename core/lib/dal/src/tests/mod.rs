@@ -161,6 +161,58 @@ async fn workflow_with_submit_tx_diff_hashes(connection_pool: ConnectionPool) {
     assert_eq!(result, L2TxSubmissionResult::Replaced);
 }
 
+#[db_test(dal_crate)]
+async fn replacing_tx_with_fee_bump(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut transactions_dal = TransactionsDal { storage };
+
+    let tx = mock_l2_transaction();
+    let result = transactions_dal
+        .insert_transaction_l2_with_fee_bump(tx.clone(), mock_tx_execution_metrics(), Some(10))
+        .await;
+    assert_eq!(result, L2TxSubmissionResult::Added);
+    // Resubmitting the same transaction is not a replacement.
+    let result = transactions_dal
+        .insert_transaction_l2_with_fee_bump(tx.clone(), mock_tx_execution_metrics(), Some(10))
+        .await;
+    assert_eq!(result, L2TxSubmissionResult::Replaced);
+
+    let mut replacement = mock_l2_transaction();
+    replacement.common_data.nonce = tx.common_data.nonce;
+    replacement.common_data.initiator_address = tx.common_data.initiator_address;
+    replacement.common_data.fee.max_fee_per_gas = tx.common_data.fee.max_fee_per_gas * 105 / 100;
+    let result = transactions_dal
+        .insert_transaction_l2_with_fee_bump(
+            replacement.clone(),
+            mock_tx_execution_metrics(),
+            Some(10),
+        )
+        .await;
+    assert_eq!(result, L2TxSubmissionResult::ReplacementUnderpriced);
+
+    replacement.common_data.fee.max_fee_per_gas = tx.common_data.fee.max_fee_per_gas * 110 / 100;
+    let result = transactions_dal
+        .insert_transaction_l2_with_fee_bump(
+            replacement.clone(),
+            mock_tx_execution_metrics(),
+            Some(10),
+        )
+        .await;
+    assert_eq!(result, L2TxSubmissionResult::Replaced);
+
+    // Rejected transactions can be replaced without any restrictions.
+    transactions_dal
+        .mark_tx_as_rejected(replacement.hash(), "rejected")
+        .await;
+    let mut underpriced = mock_l2_transaction();
+    underpriced.common_data.nonce = tx.common_data.nonce;
+    underpriced.common_data.initiator_address = tx.common_data.initiator_address;
+    let result = transactions_dal
+        .insert_transaction_l2_with_fee_bump(underpriced, mock_tx_execution_metrics(), Some(10))
+        .await;
+    assert_eq!(result, L2TxSubmissionResult::Replaced);
+}
+
 #[db_test(dal_crate)]
 async fn remove_stuck_txs(connection_pool: ConnectionPool) {
     let storage = &mut connection_pool.access_test_storage().await;
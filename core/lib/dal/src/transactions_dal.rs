@@ -30,6 +30,8 @@ pub enum L2TxSubmissionResult {
     AlreadyExecuted,
     Duplicate,
     Proxied,
+    /// The transaction replaces a pending one without bumping its fees sufficiently.
+    ReplacementUnderpriced,
 }
 
 impl fmt::Display for L2TxSubmissionResult {
@@ -136,6 +138,20 @@ impl TransactionsDal<'_, '_> {
         &mut self,
         tx: L2Tx,
         exec_info: TransactionExecutionMetrics,
+    ) -> L2TxSubmissionResult {
+        self.insert_transaction_l2_with_fee_bump(tx, exec_info, None)
+            .await
+    }
+
+    /// Same as [`Self::insert_transaction_l2()`], but if `replacement_fee_bump_percent` is specified,
+    /// a pending transaction with the same initiator and nonce is only replaced if `tx` bumps both
+    /// its `max_fee_per_gas` and `max_priority_fee_per_gas` by at least the specified percentage.
+    /// Resubmitting the same transaction or replacing a rejected one is not restricted.
+    pub async fn insert_transaction_l2_with_fee_bump(
+        &mut self,
+        tx: L2Tx,
+        exec_info: TransactionExecutionMetrics,
+        replacement_fee_bump_percent: Option<u64>,
     ) -> L2TxSubmissionResult {
         {
             let contract_address = tx.execute.contract_address.as_bytes().to_vec();
@@ -169,13 +185,13 @@ impl TransactionsDal<'_, '_> {
             // 1) transaction is added
             // 2) transaction is replaced
             // 3) WHERE clause conditions for DO UPDATE block were not met, so the transaction can't be replaced
-            // the subquery in RETURNING clause looks into pre-UPDATE state of the table. So if the subquery will return NULL
-            // transaction is fresh and was added to db(the second condition of RETURNING clause checks it).
-            // Otherwise, if the subquery won't return NULL it means that there is already tx with such nonce and initiator_address in DB
-            // and we can replace it WHERE clause conditions are met.
-            // It is worth mentioning that if WHERE clause conditions are not met, None will be returned.
+            // `xmax` of the returned row is zero only if the row was freshly inserted. If no row is returned,
+            // the WHERE clause conditions were not met; in this case, `is_pending` tells whether the conflicting
+            // transaction is still pending (i.e., the replacement is underpriced) or was already executed.
+            // Both values come from the same statement, so they are consistent with the upsert.
             let query_result = sqlx::query!(
                 r#"
+                WITH upserted AS (
                 INSERT INTO transactions
                 (
                     hash,
@@ -228,7 +244,24 @@ impl TransactionsDal<'_, '_> {
                         updated_at=now(),
                         error = NULL
                     WHERE transactions.is_priority = FALSE AND transactions.miniblock_number IS NULL
-                    RETURNING (SELECT hash FROM transactions WHERE transactions.initiator_address = $2 AND transactions.nonce = $3) IS NOT NULL as "is_replaced!"
+                        AND (
+                            $20::bigint IS NULL
+                            OR transactions.hash = $1
+                            OR transactions.error IS NOT NULL
+                            OR (
+                                $6 * 100 >= transactions.max_fee_per_gas * (100 + $20::bigint)
+                                AND $7 * 100 >= transactions.max_priority_fee_per_gas * (100 + $20::bigint)
+                            )
+                        )
+                    RETURNING (xmax = 0) AS is_inserted
+                )
+                SELECT
+                    (SELECT is_inserted FROM upserted) AS "is_inserted?",
+                    EXISTS(
+                        SELECT 1 FROM transactions
+                        WHERE initiator_address = $2 AND nonce = $3 AND is_priority = FALSE
+                            AND miniblock_number IS NULL
+                    ) AS "is_pending!"
                 "#,
                 &tx_hash,
                 &initiator,
@@ -248,17 +281,19 @@ impl TransactionsDal<'_, '_> {
                 exec_info.gas_used as i64,
                 (exec_info.initial_storage_writes + exec_info.repeated_storage_writes) as i32,
                 exec_info.contracts_used as i32,
-                received_at
+                received_at,
+                replacement_fee_bump_percent.map(|percent| percent as i64)
             )
-                .fetch_optional(self.storage.conn())
+                .fetch_one(self.storage.conn())
                 .await
-                .map(|option_record| option_record.map(|record| record.is_replaced));
+                .map(|record| (record.is_inserted, record.is_pending));
 
             let l2_tx_insertion_result = match query_result {
-                Ok(option_query_result) => match option_query_result {
-                    Some(true) => L2TxSubmissionResult::Replaced,
-                    Some(false) => L2TxSubmissionResult::Added,
-                    None => L2TxSubmissionResult::AlreadyExecuted,
+                Ok(query_result) => match query_result {
+                    (Some(true), _) => L2TxSubmissionResult::Added,
+                    (Some(false), _) => L2TxSubmissionResult::Replaced,
+                    (None, true) => L2TxSubmissionResult::ReplacementUnderpriced,
+                    (None, false) => L2TxSubmissionResult::AlreadyExecuted,
                 },
                 Err(err) => {
                     // So, we consider a tx hash to be a primary key of the transaction
@@ -607,6 +642,33 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Marks transactions evicted from the mempool as rejected. `rejected` contains pairs
+    /// of transaction hashes and the corresponding errors.
+    pub async fn mark_txs_as_rejected(&mut self, rejected: &[(H256, String)]) {
+        {
+            if rejected.is_empty() {
+                return;
+            }
+            let (hashes, errors): (Vec<_>, Vec<_>) = rejected
+                .iter()
+                .map(|(hash, error)| (hash.0.to_vec(), error.clone()))
+                .unzip();
+            sqlx::query!(
+                "UPDATE transactions
+                    SET error = data_table.error, in_mempool = FALSE, updated_at = now()
+                    FROM (
+                        SELECT UNNEST($1::bytea[]) AS hash, UNNEST($2::varchar[]) AS error
+                    ) AS data_table
+                    WHERE transactions.hash = data_table.hash AND transactions.miniblock_number IS NULL",
+                &hashes,
+                &errors
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        }
+    }
+
     pub async fn reset_transactions_state(&mut self, miniblock_number: MiniblockNumber) {
         {
             let tx_hashes = sqlx::query!(
@@ -751,6 +813,49 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Synchronizes the `in_mempool` flags with the mempool restored from a snapshot: only the pending
+    /// transactions with the specified hashes are marked as present in the mempool, so the others will
+    /// be loaded by the regular mempool sync. Returns the specified transactions that are still pending,
+    /// i.e., are neither included into a miniblock nor rejected.
+    pub async fn restore_mempool(&mut self, tx_hashes: &[H256]) -> Vec<Transaction> {
+        {
+            let hashes: Vec<_> = tx_hashes.iter().map(|hash| hash.0.to_vec()).collect();
+            let mut transaction = self.storage.start_transaction().await;
+            sqlx::query!(
+                "UPDATE transactions
+                    SET in_mempool = (hash = ANY($1) AND miniblock_number IS NULL AND error IS NULL)
+                    WHERE in_mempool = TRUE OR hash = ANY($1)",
+                &hashes
+            )
+            .execute(transaction.conn())
+            .await
+            .unwrap();
+
+            let pending_transactions = sqlx::query_as!(
+                StorageTransaction,
+                "SELECT priority_op_id, hash, is_priority, full_fee, layer_2_tip_fee,
+                    initiator_address, nonce, signature, gas_limit, max_fee_per_gas,
+                    max_priority_fee_per_gas, gas_per_storage_limit, gas_per_pubdata_limit,
+                    input, tx_format, data, received_at, in_mempool, l1_block_number,
+                    l1_batch_number, l1_batch_tx_index, miniblock_number, index_in_block,
+                    error, effective_gas_price, contract_address, value, paymaster,
+                    paymaster_input, refunded_gas, execution_info, l1_tx_mint,
+                    l1_tx_refund_recipient, created_at, updated_at
+                FROM transactions
+                WHERE hash = ANY($1) AND miniblock_number IS NULL AND error IS NULL",
+                &hashes
+            )
+            .fetch_all(transaction.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(Transaction::from)
+            .collect();
+            transaction.commit().await;
+            pending_transactions
+        }
+    }
+
     pub async fn get_last_processed_l1_block(&mut self) -> Option<L1BlockNumber> {
         {
             sqlx::query!(
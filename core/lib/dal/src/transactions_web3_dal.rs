@@ -1,17 +1,18 @@
 use sqlx::types::chrono::NaiveDateTime;
 
+use std::collections::HashMap;
+
 use zksync_types::{
     api, Address, L2ChainId, MiniblockNumber, Transaction, ACCOUNT_CODE_STORAGE_ADDRESS,
-    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256, U256, U64,
+    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
-use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
 use crate::models::{
     storage_block::{bind_block_where_sql_params, web3_block_where_sql},
-    storage_event::StorageWeb3Log,
+    storage_event::{StorageL2ToL1Log, StorageWeb3Log},
     storage_transaction::{
         extract_web3_transaction, web3_transaction_select_sql, StorageTransaction,
-        StorageTransactionDetails,
+        StorageTransactionDetails, StorageTransactionReceipt,
     },
 };
 use crate::{SqlxError, StorageProcessor};
@@ -27,7 +28,8 @@ impl TransactionsWeb3Dal<'_, '_> {
         hash: H256,
     ) -> Result<Option<api::TransactionReceipt>, SqlxError> {
         {
-            let receipt = sqlx::query!(
+            let receipt = sqlx::query_as!(
+                StorageTransactionReceipt,
                 r#"
                 WITH sl AS (
                     SELECT * FROM storage_logs
@@ -64,63 +66,7 @@ impl TransactionsWeb3Dal<'_, '_> {
             )
             .fetch_optional(self.storage.conn())
             .await?
-            .map(|db_row| {
-                let status = match (db_row.block_number, db_row.error) {
-                    (_, Some(_)) => Some(U64::from(0)),
-                    (Some(_), None) => Some(U64::from(1)),
-                    // tx not executed yet
-                    _ => None,
-                };
-                let tx_type = db_row.tx_format.map(U64::from).unwrap_or_default();
-                let transaction_index = db_row.index_in_block.map(U64::from).unwrap_or_default();
-
-                api::TransactionReceipt {
-                    transaction_hash: H256::from_slice(&db_row.tx_hash),
-                    transaction_index,
-                    block_hash: db_row
-                        .block_hash
-                        .clone()
-                        .map(|bytes| H256::from_slice(&bytes)),
-                    block_number: db_row.block_number.map(U64::from),
-                    l1_batch_tx_index: db_row.l1_batch_tx_index.map(U64::from),
-                    l1_batch_number: db_row.l1_batch_number.map(U64::from),
-                    from: H160::from_slice(&db_row.initiator_address),
-                    to: db_row
-                        .transfer_to
-                        .or(db_row.execute_contract_address)
-                        .map(|addr| {
-                            serde_json::from_value::<Address>(addr)
-                                .expect("invalid address value in the database")
-                        })
-                        // For better compatibility with various clients, we never return null.
-                        .or_else(|| Some(Address::default())),
-                    cumulative_gas_used: Default::default(),
-                    gas_used: {
-                        let refunded_gas: U256 = db_row.refunded_gas.into();
-                        db_row.gas_limit.map(|val| {
-                            let gas_limit = bigdecimal_to_u256(val);
-                            gas_limit - refunded_gas
-                        })
-                    },
-                    effective_gas_price: Some(
-                        db_row
-                            .effective_gas_price
-                            .map(bigdecimal_to_u256)
-                            .unwrap_or_default(),
-                    ),
-                    contract_address: db_row
-                        .contract_address
-                        .map(|addr| h256_to_account_address(&H256::from_slice(&addr))),
-                    logs: vec![],
-                    l2_to_l1_logs: vec![],
-                    status,
-                    root: db_row.block_hash.map(|bytes| H256::from_slice(&bytes)),
-                    logs_bloom: Default::default(),
-                    // Even though the Rust SDK recommends us to supply "None" for legacy transactions
-                    // we always supply some number anyway to have the same behaviour as most popular RPCs
-                    transaction_type: Some(tx_type),
-                }
-            });
+            .map(api::TransactionReceipt::from);
             match receipt {
                 Some(mut receipt) => {
                     let logs: Vec<_> = sqlx::query_as!(
@@ -169,6 +115,122 @@ impl TransactionsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns receipts of all transactions in the specified miniblock ordered by their index
+    /// in the miniblock. Unlike calling [`Self::get_transaction_receipt()`] for each transaction,
+    /// this method uses a fixed number of queries regardless of the number of transactions.
+    /// Returns an empty list if the miniblock doesn't exist.
+    pub async fn get_block_receipts(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> Result<Vec<api::TransactionReceipt>, SqlxError> {
+        let mut receipts: Vec<api::TransactionReceipt> = sqlx::query_as!(
+            StorageTransactionReceipt,
+            r#"
+                SELECT
+                     transactions.hash as tx_hash,
+                     transactions.index_in_block as index_in_block,
+                     transactions.l1_batch_tx_index as l1_batch_tx_index,
+                     transactions.miniblock_number as block_number,
+                     transactions.error as error,
+                     transactions.effective_gas_price as effective_gas_price,
+                     transactions.initiator_address as initiator_address,
+                     transactions.data->'to' as "transfer_to?",
+                     transactions.data->'contractAddress' as "execute_contract_address?",
+                     transactions.tx_format as "tx_format?",
+                     transactions.refunded_gas as refunded_gas,
+                     transactions.gas_limit as gas_limit,
+                     miniblocks.hash as "block_hash?",
+                     miniblocks.l1_batch_number as "l1_batch_number?",
+                     sl.key as "contract_address?"
+                FROM transactions
+                INNER JOIN miniblocks
+                    ON miniblocks.number = transactions.miniblock_number
+                LEFT JOIN LATERAL (
+                    SELECT storage_logs.key, storage_logs.value FROM storage_logs
+                    WHERE storage_logs.address = $1 AND storage_logs.tx_hash = transactions.hash
+                    ORDER BY storage_logs.miniblock_number DESC, storage_logs.operation_number DESC
+                    LIMIT 1
+                ) sl
+                    ON sl.value != $3
+                WHERE transactions.miniblock_number = $2
+                ORDER BY transactions.index_in_block
+                "#,
+            ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
+            block_number.0 as i64,
+            FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(api::TransactionReceipt::from)
+        .collect();
+
+        let Some(first_receipt) = receipts.first() else {
+            return Ok(receipts);
+        };
+        // All transactions are in the same miniblock, so these values are shared.
+        let block_hash = first_receipt.block_hash;
+        let l1_batch_number = first_receipt.l1_batch_number;
+        let receipt_indices: HashMap<_, _> = receipts
+            .iter()
+            .enumerate()
+            .map(|(i, receipt)| (receipt.transaction_hash, i))
+            .collect();
+
+        let logs = sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            SELECT
+                address, topic1, topic2, topic3, topic4, value,
+                Null::bytea as "block_hash", Null::bigint as "l1_batch_number?",
+                miniblock_number, tx_hash, tx_index_in_block,
+                event_index_in_block, event_index_in_tx
+            FROM events
+            WHERE miniblock_number = $1
+            ORDER BY event_index_in_block ASC
+            "#,
+            block_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        for storage_log in logs {
+            let tx_hash = H256::from_slice(&storage_log.tx_hash);
+            let mut log = api::Log::from(storage_log);
+            log.block_hash = block_hash;
+            log.l1_batch_number = l1_batch_number;
+            if let Some(&i) = receipt_indices.get(&tx_hash) {
+                receipts[i].logs.push(log);
+            }
+        }
+
+        let l2_to_l1_logs = sqlx::query_as!(
+            StorageL2ToL1Log,
+            r#"
+            SELECT
+                miniblock_number, log_index_in_miniblock, log_index_in_tx, tx_hash,
+                Null::bytea as "block_hash", Null::bigint as "l1_batch_number?",
+                shard_id, is_service, tx_index_in_miniblock, tx_index_in_l1_batch, sender, key, value
+            FROM l2_to_l1_logs
+            WHERE miniblock_number = $1
+            ORDER BY log_index_in_miniblock ASC
+            "#,
+            block_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        for storage_l2_to_l1_log in l2_to_l1_logs {
+            let mut l2_to_l1_log = api::L2ToL1Log::from(storage_l2_to_l1_log);
+            l2_to_l1_log.block_hash = block_hash;
+            l2_to_l1_log.l1_batch_number = l1_batch_number;
+            if let Some(&i) = receipt_indices.get(&l2_to_l1_log.transaction_hash) {
+                receipts[i].l2_to_l1_logs.push(l2_to_l1_log);
+            }
+        }
+        Ok(receipts)
+    }
+
     pub async fn get_transaction(
         &mut self,
         transaction_id: api::TransactionId,
@@ -321,6 +383,23 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(U256::from(pending_nonce))
     }
 
+    /// Returns the number of the miniblock the transaction was included into, or `None`
+    /// if the transaction is unknown or not included into a miniblock yet.
+    pub async fn get_miniblock_number_of_transaction(
+        &mut self,
+        tx_hash: H256,
+    ) -> Result<Option<MiniblockNumber>, SqlxError> {
+        let row = sqlx::query!(
+            "SELECT miniblock_number FROM transactions WHERE hash = $1",
+            tx_hash.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row
+            .and_then(|row| row.miniblock_number)
+            .map(|number| MiniblockNumber(number as u32)))
+    }
+
     /// Returns the server transactions (not API ones) from a certain miniblock.
     /// Returns an empty list if the miniblock doesn't exist.
     pub async fn get_raw_miniblock_transactions(
@@ -451,5 +530,50 @@ mod tests {
             .unwrap();
         assert_eq!(raw_txs.len(), 1);
         assert_eq!(raw_txs[0].hash(), tx_hash);
+
+        let miniblock_number = conn
+            .transactions_web3_dal()
+            .get_miniblock_number_of_transaction(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(miniblock_number, Some(MiniblockNumber(1)));
+        let miniblock_number = conn
+            .transactions_web3_dal()
+            .get_miniblock_number_of_transaction(H256::zero())
+            .await
+            .unwrap();
+        assert_eq!(miniblock_number, None);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_block_receipts(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        prepare_transaction(&mut conn, tx).await;
+
+        let receipts = conn
+            .transactions_web3_dal()
+            .get_block_receipts(MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert!(receipts.is_empty());
+
+        let receipts = conn
+            .transactions_web3_dal()
+            .get_block_receipts(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(receipts.len(), 1);
+        let expected_receipt = conn
+            .transactions_web3_dal()
+            .get_transaction_receipt(tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(receipts[0], expected_receipt);
+        assert_eq!(receipts[0].transaction_hash, tx_hash);
+        assert_eq!(receipts[0].block_number, Some(1.into()));
+        assert_eq!(receipts[0].transaction_index, 0.into());
     }
 }
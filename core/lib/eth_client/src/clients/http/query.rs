@@ -3,6 +3,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
+use zksync_types::api::FeeHistory;
 use zksync_types::web3::{
     self,
     contract::{
@@ -10,7 +11,7 @@ use zksync_types::web3::{
         Contract, Options,
     },
     ethabi,
-    helpers::CallFuture,
+    helpers::{self, CallFuture},
     transports::Http,
     types::{
         Address, Block, BlockId, BlockNumber, Bytes, Filter, Log, Transaction, TransactionId,
@@ -20,7 +21,7 @@ use zksync_types::web3::{
 };
 
 use crate::{
-    types::{Error, ExecutedTxStatus, FailureInfo},
+    types::{BlockFees, Error, ExecutedTxStatus, FailureInfo},
     EthInterface,
 };
 
@@ -123,6 +124,59 @@ impl EthInterface for QueryClient {
         Ok(history.into_iter().map(|fee| fee.as_u64()).collect())
     }
 
+    async fn fee_history(
+        &self,
+        upto_block: usize,
+        block_count: usize,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<Vec<BlockFees>, Error> {
+        const MAX_REQUEST_CHUNK: usize = 1024;
+
+        metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "fee_history");
+        let start = Instant::now();
+
+        let mut history = Vec::with_capacity(block_count);
+        let from_block = upto_block.saturating_sub(block_count);
+
+        // Same as in `base_fee_history()`, we request blocks (from_block; upto_block]
+        // in chunks of size MAX_REQUEST_CHUNK starting from the oldest block.
+        for chunk_start in (from_block..upto_block).step_by(MAX_REQUEST_CHUNK) {
+            let chunk_end = (chunk_start + MAX_REQUEST_CHUNK).min(upto_block);
+            let chunk_size = chunk_end - chunk_start;
+            // `web3` doesn't support blob fees in `eth_feeHistory` responses, so we make a raw call.
+            let params = vec![
+                helpers::serialize(&U256::from(chunk_size)),
+                helpers::serialize(&BlockNumber::Number(chunk_end.into())),
+                helpers::serialize(&reward_percentiles),
+            ];
+            let chunk: FeeHistory =
+                CallFuture::new(self.web3.transport().execute("eth_feeHistory", params)).await?;
+
+            // Fees for the block following `chunk_end` are skipped, so that values correspond to blocks.
+            let mut rewards = chunk.reward.unwrap_or_default().into_iter();
+            let mut blob_base_fees = chunk.base_fee_per_blob_gas.into_iter();
+            let chunk = chunk
+                .base_fee_per_gas
+                .into_iter()
+                .take(chunk_size)
+                .map(|base_fee| BlockFees {
+                    base_fee_per_gas: base_fee.as_u64(),
+                    rewards: rewards
+                        .next()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|reward| reward.as_u64())
+                        .collect(),
+                    base_fee_per_blob_gas: blob_base_fees.next().map_or(0, |fee| fee.as_u64()),
+                });
+            history.extend(chunk);
+        }
+
+        metrics::histogram!("eth_client.direct.fee_history", start.elapsed());
+        Ok(history)
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
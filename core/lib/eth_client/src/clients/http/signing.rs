@@ -26,7 +26,7 @@ use zksync_types::{L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE};
 // Loal uses
 use super::query::QueryClient;
 use crate::{
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    types::{BlockFees, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     BoundEthInterface, EthInterface,
 };
 
@@ -140,6 +140,18 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
             .await
     }
 
+    async fn fee_history(
+        &self,
+        upto_block: usize,
+        block_count: usize,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<Vec<BlockFees>, Error> {
+        self.query_client
+            .fee_history(upto_block, block_count, reward_percentiles, component)
+            .await
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
@@ -18,7 +18,7 @@ use zksync_types::{Address, L1ChainId};
 use zksync_types::{web3::types::TransactionReceipt, H160, H256, U256};
 
 use crate::{
-    types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
+    types::{BlockFees, Error, ExecutedTxStatus, FailureInfo, SignedCallResult},
     BoundEthInterface, EthInterface,
 };
 
@@ -27,6 +27,7 @@ pub struct MockTx {
     pub hash: H256,
     pub nonce: u64,
     pub base_fee: U256,
+    pub priority_fee: U256,
 }
 
 impl From<Vec<u8>> for MockTx {
@@ -48,6 +49,7 @@ impl From<Vec<u8>> for MockTx {
             nonce,
             hash,
             base_fee,
+            priority_fee,
         }
     }
 }
@@ -58,6 +60,11 @@ pub struct MockEthereum {
     pub block_number: AtomicU64,
     pub max_fee_per_gas: U256,
     pub base_fee_history: RwLock<Vec<u64>>,
+    /// Priority fees returned as `eth_feeHistory` rewards, indexed by the block number.
+    /// Rewards are returned as is, regardless of the requested percentiles.
+    pub reward_history: RwLock<Vec<Vec<u64>>>,
+    /// Blob base fees indexed by the block number. Missing values are reported as zero.
+    pub blob_base_fee_history: RwLock<Vec<u64>>,
     pub max_priority_fee_per_gas: U256,
     pub tx_statuses: RwLock<HashMap<H256, ExecutedTxStatus>>,
     pub sent_txs: RwLock<HashMap<H256, MockTx>>,
@@ -76,6 +83,8 @@ impl Default for MockEthereum {
             max_priority_fee_per_gas: 10.into(),
             block_number: Default::default(),
             base_fee_history: Default::default(),
+            reward_history: Default::default(),
+            blob_base_fee_history: Default::default(),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
             current_nonce: Default::default(),
@@ -180,6 +189,20 @@ impl MockEthereum {
         }
     }
 
+    pub fn with_reward_history(self, history: Vec<Vec<u64>>) -> Self {
+        Self {
+            reward_history: RwLock::new(history),
+            ..self
+        }
+    }
+
+    pub fn with_blob_base_fee_history(self, history: Vec<u64>) -> Self {
+        Self {
+            blob_base_fee_history: RwLock::new(history),
+            ..self
+        }
+    }
+
     pub fn with_non_ordering_confirmation(self, non_ordering_confirmations: bool) -> Self {
         Self {
             non_ordering_confirmations,
@@ -246,6 +269,26 @@ impl EthInterface for MockEthereum {
             .to_vec())
     }
 
+    async fn fee_history(
+        &self,
+        upto_block: usize,
+        block_count: usize,
+        _reward_percentiles: &[f64],
+        _component: &'static str,
+    ) -> Result<Vec<BlockFees>, Error> {
+        let base_fee_history = self.base_fee_history.read().unwrap();
+        let reward_history = self.reward_history.read().unwrap();
+        let blob_base_fee_history = self.blob_base_fee_history.read().unwrap();
+        let blocks = upto_block.saturating_sub(block_count - 1)..=upto_block;
+        Ok(blocks
+            .map(|block| BlockFees {
+                base_fee_per_gas: base_fee_history[block],
+                rewards: reward_history.get(block).cloned().unwrap_or_default(),
+                base_fee_per_blob_gas: blob_base_fee_history.get(block).copied().unwrap_or(0),
+            })
+            .collect())
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         _component: &'static str,
@@ -409,6 +452,18 @@ impl<T: AsRef<MockEthereum> + Send + Sync> EthInterface for T {
             .await
     }
 
+    async fn fee_history(
+        &self,
+        upto_block: usize,
+        block_count: usize,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<Vec<BlockFees>, Error> {
+        self.as_ref()
+            .fee_history(upto_block, block_count, reward_percentiles, component)
+            .await
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
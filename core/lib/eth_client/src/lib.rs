@@ -3,7 +3,7 @@
 pub mod clients;
 pub mod types;
 
-use crate::types::{Error, ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::types::{BlockFees, Error, ExecutedTxStatus, FailureInfo, SignedCallResult};
 use async_trait::async_trait;
 use zksync_types::{
     web3::{
@@ -57,6 +57,20 @@ pub trait EthInterface: Sync + Send {
         component: &'static str,
     ) -> Result<Vec<u64>, Error>;
 
+    /// Collects the fee history for `block_count` blocks ending with `upto_block` (inclusive).
+    /// In addition to base fees, the history includes priority fees paid at the specified
+    /// `reward_percentiles` (in the `[0, 100]` range, sorted in the ascending order) and blob base fees.
+    ///
+    /// Returns 1 value for each block in range, assuming that these blocks exist.
+    /// Will return an error if `upto_block` is beyond the head block.
+    async fn fee_history(
+        &self,
+        upto_block: usize,
+        block_count: usize,
+        reward_percentiles: &[f64],
+        component: &'static str,
+    ) -> Result<Vec<BlockFees>, Error>;
+
     /// Returns the `base_fee_per_gas` value for the currently pending L1 block.
     async fn get_pending_block_base_fee_per_gas(
         &self,
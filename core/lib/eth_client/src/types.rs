@@ -39,6 +39,18 @@ pub struct SignedCallResult {
     pub hash: H256,
 }
 
+/// Fees paid in a single L1 block, as reported by the `eth_feeHistory` method.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlockFees {
+    /// `base_fee_per_gas` of the block.
+    pub base_fee_per_gas: u64,
+    /// Effective priority fees per gas paid at the requested percentiles of the gas used in the block.
+    /// Empty if the node doesn't report priority fees.
+    pub rewards: Vec<u64>,
+    /// Base fee per blob gas (EIP-4844). Zero for blocks preceding the Cancun upgrade.
+    pub base_fee_per_blob_gas: u64,
+}
+
 /// State of the executed Ethereum transaction.
 #[derive(Debug, Clone)]
 pub struct ExecutedTxStatus {
@@ -1,6 +1,12 @@
 mod mempool_store;
+mod ordering;
+mod snapshot;
 #[cfg(test)]
 mod tests;
 mod types;
 pub use mempool_store::{MempoolInfo, MempoolStore};
-pub use types::L2TxFilter;
+pub use ordering::{
+    FifoOrdering, HybridOrdering, MempoolOrdering, PriorityFeeOrdering, TxPriority,
+};
+pub use snapshot::{AccountSnapshot, MempoolSnapshot};
+pub use types::{EvictedTransaction, EvictionReason, L2TxFilter};
@@ -1,12 +1,17 @@
-use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
+use crate::ordering::{FifoOrdering, MempoolOrdering};
+use crate::snapshot::{AccountSnapshot, MempoolSnapshot};
+use crate::types::{
+    AccountTransactions, EvictedTransaction, EvictionReason, L2TxFilter, MempoolScore,
+};
+use std::cmp::Reverse;
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
 };
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct MempoolStore {
     /// Pending L1 transactions
     l1_transactions: HashMap<PriorityOpId, L1Tx>,
@@ -14,33 +19,71 @@ pub struct MempoolStore {
     l2_transactions_per_account: HashMap<Address, AccountTransactions>,
     /// Global priority queue for L2 transactions. Used for scoring
     l2_priority_queue: BTreeSet<MempoolScore>,
+    /// Policy used to order L2 transactions in the priority queue
+    ordering: Box<dyn MempoolOrdering>,
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
     /// number of l2 transactions in the mempool
     size: u64,
     capacity: u64,
+    /// max number of pending l2 transactions per initiator account
+    max_txs_per_account: Option<usize>,
+    /// min percentage by which a replacement transaction must bump fees of the replaced one
+    replacement_fee_bump_percent: Option<u64>,
+    /// l2 transactions evicted from the mempool since the last `get_mempool_info()` call
+    evicted_transactions: Vec<EvictedTransaction>,
 }
 
 #[derive(Debug)]
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    pub evicted_transactions: Vec<EvictedTransaction>,
 }
 
 impl MempoolStore {
+    /// Creates a mempool with the FIFO ordering of L2 transactions.
     pub fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
+        Self::with_ordering(next_priority_id, capacity, Box::new(FifoOrdering))
+    }
+
+    /// Creates a mempool with the specified ordering policy for L2 transactions.
+    pub fn with_ordering(
+        next_priority_id: PriorityOpId,
+        capacity: u64,
+        ordering: Box<dyn MempoolOrdering>,
+    ) -> Self {
         Self {
             l1_transactions: HashMap::new(),
             l2_transactions_per_account: HashMap::new(),
             l2_priority_queue: BTreeSet::new(),
+            ordering,
             next_priority_id,
             stashed_accounts: vec![],
             size: 0,
             capacity,
+            max_txs_per_account: None,
+            replacement_fee_bump_percent: None,
+            evicted_transactions: vec![],
         }
     }
 
+    /// Limits the number of pending L2 transactions per initiator account. If the limit is exceeded,
+    /// transactions of the account with the greatest nonces are evicted.
+    pub fn with_max_txs_per_account(mut self, max_txs_per_account: usize) -> Self {
+        self.max_txs_per_account = Some(max_txs_per_account);
+        self
+    }
+
+    /// Requires a transaction replacing another one with the same nonce to bump both
+    /// `max_fee_per_gas` and `max_priority_fee_per_gas` by at least the specified percentage.
+    /// Underpriced replacements are evicted.
+    pub fn with_replacement_fee_bump_percent(mut self, bump_percent: u64) -> Self {
+        self.replacement_fee_bump_percent = Some(bump_percent);
+        self
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -81,6 +124,7 @@ impl MempoolStore {
                 }
             }
         }
+        self.evict_cheapest_transactions();
         self.collect_stats();
     }
 
@@ -90,16 +134,23 @@ impl MempoolStore {
         initial_nonces: &HashMap<Address, Nonce>,
     ) {
         let account = transaction.initiator_account();
+        let ordering = self.ordering.as_ref();
+        let fee_bump_percent = self.replacement_fee_bump_percent;
 
-        let metadata = match self.l2_transactions_per_account.entry(account) {
-            Entry::Occupied(mut txs) => txs.get_mut().insert(transaction),
+        let account_transactions = match self.l2_transactions_per_account.entry(account) {
+            Entry::Occupied(txs) => txs.into_mut(),
             Entry::Vacant(entry) => {
                 let account_nonce = initial_nonces.get(&account).cloned().unwrap_or(Nonce(0));
-                entry
-                    .insert(AccountTransactions::new(account_nonce))
-                    .insert(transaction)
+                entry.insert(AccountTransactions::new(account_nonce))
             }
         };
+        let metadata = account_transactions.insert(transaction, ordering, fee_bump_percent);
+        let account_len = account_transactions.len();
+
+        if let Some(hash) = metadata.rejected_replacement {
+            self.report_eviction(hash, EvictionReason::ReplacementUnderpriced);
+            return;
+        }
         if let Some(score) = metadata.previous_score {
             self.l2_priority_queue.remove(&score);
         }
@@ -109,6 +160,69 @@ impl MempoolStore {
         if metadata.is_new {
             self.size += 1;
         }
+        if let Some(max_txs_per_account) = self.max_txs_per_account {
+            for _ in max_txs_per_account..account_len {
+                self.evict_tail(account, EvictionReason::AccountLimitExceeded);
+            }
+        }
+    }
+
+    /// Evicts the lowest-paying L2 transactions until the mempool fits into its capacity.
+    /// Only the transaction with the greatest nonce can be evicted for each account,
+    /// so that the remaining transactions of the account can still be executed.
+    fn evict_cheapest_transactions(&mut self) {
+        if self.size <= self.capacity {
+            return;
+        }
+        let mut candidates: BinaryHeap<_> = self
+            .l2_transactions_per_account
+            .values()
+            .filter_map(AccountTransactions::tail_eviction_key)
+            .map(Reverse)
+            .collect();
+
+        while self.size > self.capacity {
+            let account = match candidates.pop() {
+                Some(Reverse((.., account))) => account,
+                None => break,
+            };
+            self.evict_tail(account, EvictionReason::MempoolFull);
+            if let Some(key) = self.l2_transactions_per_account[&account].tail_eviction_key() {
+                candidates.push(Reverse(key));
+            }
+        }
+    }
+
+    /// Evicts the transaction with the greatest nonce for the specified account.
+    fn evict_tail(&mut self, account: Address, reason: EvictionReason) {
+        let evicted = self
+            .l2_transactions_per_account
+            .get_mut(&account)
+            .expect("mempool: evicting transaction for unknown account")
+            .evict_tail(self.ordering.as_ref());
+        let (transaction, score) = match evicted {
+            Some(evicted) => evicted,
+            None => return,
+        };
+
+        if let Some(score) = score {
+            self.l2_priority_queue.remove(&score);
+        }
+        self.size = self
+            .size
+            .checked_sub(1)
+            .expect("mempool size can't be negative");
+        self.report_eviction(transaction.hash(), reason);
+    }
+
+    fn report_eviction(&mut self, hash: H256, reason: EvictionReason) {
+        vlog::debug!("evicting transaction {:?} from mempool: {}", hash, reason);
+        metrics::increment_counter!(
+            "server.state_keeper.mempool_evicted_txs",
+            "reason" => reason.as_metric_label()
+        );
+        self.evicted_transactions
+            .push(EvictedTransaction { hash, reason });
     }
 
     /// Returns `true` if there is a transaction in the mempool satisfying the filter.
@@ -156,7 +270,7 @@ impl MempoolStore {
             .l2_transactions_per_account
             .get_mut(&tx_pointer.account)
             .expect("mempool: dangling pointer in priority queue")
-            .next();
+            .next(self.ordering.as_ref());
 
         if let Some(score) = score {
             self.l2_priority_queue.insert(score);
@@ -184,7 +298,7 @@ impl MempoolStore {
                     .l2_transactions_per_account
                     .get_mut(&tx.initiator_account())
                     .expect("account is not available in mempool")
-                    .reset(tx)
+                    .reset(tx, self.ordering.as_ref())
                 {
                     self.l2_priority_queue.remove(&score);
                 }
@@ -192,10 +306,76 @@ impl MempoolStore {
         }
     }
 
+    /// Returns the current state of the mempool that can be persisted and later restored
+    /// with [`Self::restore()`]. The snapshot only contains transaction hashes, so it's cheap to take.
+    pub fn snapshot(&self) -> MempoolSnapshot {
+        let mut l1_transactions: Vec<_> = self.l1_transactions.iter().collect();
+        l1_transactions.sort_unstable_by_key(|(serial_id, _)| **serial_id);
+        let accounts = self
+            .l2_transactions_per_account
+            .iter()
+            .map(|(&address, account_transactions)| AccountSnapshot {
+                address,
+                nonce: account_transactions.nonce(),
+                tx_hashes: account_transactions
+                    .transactions()
+                    .into_iter()
+                    .map(L2Tx::hash)
+                    .collect(),
+            })
+            .collect();
+
+        MempoolSnapshot {
+            next_priority_id: self.next_priority_id,
+            l1_tx_hashes: l1_transactions
+                .into_iter()
+                .map(|(_, tx)| tx.hash())
+                .collect(),
+            accounts,
+            stashed_accounts: self.stashed_accounts.clone(),
+            evicted_transactions: self.evicted_transactions.clone(),
+        }
+    }
+
+    /// Restores the mempool state from a snapshot. Must be called on an empty mempool.
+    /// Ordering and limits of this mempool are applied to the restored transactions.
+    ///
+    /// `transactions` must contain the transactions referenced by the snapshot; other transactions
+    /// are ignored, as well as L1 transactions preceding the next priority operation.
+    pub fn restore(&mut self, snapshot: MempoolSnapshot, transactions: Vec<Transaction>) {
+        assert!(
+            self.l1_transactions.is_empty() && self.l2_transactions_per_account.is_empty(),
+            "mempool can only be restored from a snapshot if it's empty"
+        );
+
+        let pending_tx_hashes: HashSet<_> = snapshot.pending_tx_hashes().collect();
+        self.next_priority_id = snapshot.next_priority_id;
+        self.stashed_accounts = snapshot.stashed_accounts;
+        self.evicted_transactions = snapshot.evicted_transactions;
+        for account in snapshot.accounts {
+            // Accounts are restored even if they have no pending transactions, so that
+            // their nonces account for transactions sent to state keeper. L2 transactions
+            // with nonces lower than the account nonce are skipped on insertion.
+            self.l2_transactions_per_account
+                .insert(account.address, AccountTransactions::new(account.nonce));
+        }
+
+        let next_priority_id = self.next_priority_id;
+        let transactions = transactions.into_iter().filter(|tx| {
+            let is_outdated = matches!(
+                &tx.common_data,
+                ExecuteTransactionCommon::L1(data) if data.serial_id < next_priority_id
+            );
+            !is_outdated && pending_tx_hashes.contains(&tx.hash())
+        });
+        self.insert(transactions.collect(), HashMap::new());
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
             purged_accounts: self.gc(),
+            evicted_transactions: std::mem::take(&mut self.evicted_transactions),
         }
     }
 
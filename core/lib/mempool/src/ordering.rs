@@ -0,0 +1,114 @@
+use std::{cmp::Ordering, fmt};
+
+use zksync_types::{l2::L2Tx, U256};
+
+/// 1 gwei in wei.
+const GWEI: u64 = 1_000_000_000;
+
+/// Priority of an L2 transaction assigned by a [`MempoolOrdering`] policy.
+///
+/// Transactions are compared by `fee` first (a greater fee means a greater priority), and then by
+/// `effective_received_at_ms` (an earlier timestamp means a greater priority).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TxPriority {
+    /// Fee-based component of the priority.
+    pub fee: U256,
+    /// Time-based component of the priority. Not necessarily equal to the time the transaction
+    /// was received at; e.g., [`HybridOrdering`] shifts it back in time based on the priority fee.
+    pub effective_received_at_ms: u64,
+}
+
+impl Ord for TxPriority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.fee.cmp(&other.fee).then_with(|| {
+            self.effective_received_at_ms
+                .cmp(&other.effective_received_at_ms)
+                .reverse()
+        })
+    }
+}
+
+impl PartialOrd for TxPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Policy used by the mempool to order L2 transactions for execution.
+///
+/// Only the transaction with the next nonce of each account takes part in ordering, so transactions
+/// of a single account are always executed in the nonce order regardless of the policy.
+/// The priority must be a pure function of the transaction; it is recomputed when the mempool
+/// needs to find the transaction in its priority queue.
+pub trait MempoolOrdering: fmt::Debug + Send + Sync + 'static {
+    /// Computes the priority of the transaction. Transactions with greater priority are executed first.
+    fn priority(&self, transaction: &L2Tx) -> TxPriority;
+}
+
+/// First-in, first-out ordering: transactions are executed in the order they were received in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FifoOrdering;
+
+impl MempoolOrdering for FifoOrdering {
+    fn priority(&self, transaction: &L2Tx) -> TxPriority {
+        TxPriority {
+            fee: U256::zero(),
+            effective_received_at_ms: transaction.received_timestamp_ms,
+        }
+    }
+}
+
+/// Ordering by the priority fee: transactions with the highest `max_priority_fee_per_gas` are executed
+/// first; transactions with equal fees are executed in the order they were received in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PriorityFeeOrdering;
+
+impl MempoolOrdering for PriorityFeeOrdering {
+    fn priority(&self, transaction: &L2Tx) -> TxPriority {
+        TxPriority {
+            fee: transaction.common_data.fee.max_priority_fee_per_gas,
+            effective_received_at_ms: transaction.received_timestamp_ms,
+        }
+    }
+}
+
+/// Hybrid ordering with anti-starvation aging.
+///
+/// Transactions are executed in the order they were received in, but each transaction is treated
+/// as if it was received earlier by a time boost proportional to its priority fee. The boost is capped,
+/// so a transaction that has waited in the mempool for longer than the max boost is guaranteed
+/// to be executed before all transactions received after it, regardless of their fees.
+#[derive(Debug, Clone, Copy)]
+pub struct HybridOrdering {
+    boost_ms_per_gwei: u64,
+    max_boost_ms: u64,
+}
+
+impl HybridOrdering {
+    /// Creates the ordering with the specified time boost per 1 gwei of the priority fee
+    /// and the max time boost (both in milliseconds).
+    pub fn new(boost_ms_per_gwei: u64, max_boost_ms: u64) -> Self {
+        Self {
+            boost_ms_per_gwei,
+            max_boost_ms,
+        }
+    }
+
+    fn boost_ms(&self, priority_fee: U256) -> u64 {
+        let max_boost_ms = U256::from(self.max_boost_ms);
+        let boost_ms = priority_fee
+            .checked_mul(U256::from(self.boost_ms_per_gwei))
+            .map_or(max_boost_ms, |boost| boost / GWEI);
+        boost_ms.min(max_boost_ms).as_u64()
+    }
+}
+
+impl MempoolOrdering for HybridOrdering {
+    fn priority(&self, transaction: &L2Tx) -> TxPriority {
+        let boost_ms = self.boost_ms(transaction.common_data.fee.max_priority_fee_per_gas);
+        TxPriority {
+            fee: U256::zero(),
+            effective_received_at_ms: transaction.received_timestamp_ms.saturating_sub(boost_ms),
+        }
+    }
+}
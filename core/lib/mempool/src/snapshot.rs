@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::types::EvictedTransaction;
+use zksync_types::{Address, Nonce, PriorityOpId, H256};
+
+/// Persistable state of the mempool allowing to restore it after a restart.
+///
+/// The snapshot only references transactions by their hashes, so that it's cheap to take while
+/// the mempool is locked; transactions themselves are loaded from Postgres on restore. Scores
+/// of L2 transactions are recomputed on restore using the ordering policy of the restored mempool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolSnapshot {
+    /// Next priority operation
+    pub next_priority_id: PriorityOpId,
+    /// Hashes of pending L1 transactions ordered by priority operation ID
+    pub l1_tx_hashes: Vec<H256>,
+    /// Pending L2 transactions grouped by initiator account
+    pub accounts: Vec<AccountSnapshot>,
+    /// Accounts stashed since the last `get_mempool_info()` call
+    pub stashed_accounts: Vec<Address>,
+    /// L2 transactions evicted since the last `get_mempool_info()` call
+    pub evicted_transactions: Vec<EvictedTransaction>,
+}
+
+/// Pending L2 transactions of a single account in [`MempoolSnapshot`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSnapshot {
+    pub address: Address,
+    /// Account nonce in mempool; may be greater than the committed nonce if some account transactions
+    /// were sent to state keeper
+    pub nonce: Nonce,
+    /// Hashes of pending transactions ordered by nonce
+    pub tx_hashes: Vec<H256>,
+}
+
+impl MempoolSnapshot {
+    /// Returns hashes of all transactions in the snapshot, including the evicted ones.
+    pub fn tx_hashes(&self) -> Vec<H256> {
+        let evicted_hashes = self.evicted_transactions.iter().map(|evicted| evicted.hash);
+        self.pending_tx_hashes().chain(evicted_hashes).collect()
+    }
+
+    /// Returns hashes of the pending (i.e., not evicted) transactions in the snapshot.
+    pub(crate) fn pending_tx_hashes(&self) -> impl Iterator<Item = H256> + '_ {
+        let l2_hashes = self
+            .accounts
+            .iter()
+            .flat_map(|account| account.tx_hashes.iter().copied());
+        self.l1_tx_hashes.iter().copied().chain(l2_hashes)
+    }
+
+    /// Returns addresses of all accounts with L2 transactions in the snapshot.
+    pub fn accounts(&self) -> Vec<Address> {
+        self.accounts
+            .iter()
+            .map(|account| account.address)
+            .collect()
+    }
+
+    /// Reconciles the snapshot with the persisted state, which could have changed after the snapshot
+    /// was taken (e.g., the state keeper could seal more transactions, or some transactions could be
+    /// sent to state keeper, but not sealed before the restart). Transactions with outdated priority IDs
+    /// or nonces are filtered out on restore, once the transactions themselves are loaded.
+    ///
+    /// - `next_priority_id` is the ID of the first priority operation not included into a miniblock
+    /// - `pending_tx_hashes` are hashes of the transactions that are neither included into a miniblock
+    ///   nor rejected; other transactions are removed from the snapshot
+    /// - `nonces` are committed nonces of the accounts; nonces of accounts missing from `nonces` are
+    ///   reset to 0
+    pub fn reconcile(
+        &mut self,
+        next_priority_id: PriorityOpId,
+        pending_tx_hashes: &HashSet<H256>,
+        nonces: &HashMap<Address, Nonce>,
+    ) {
+        self.next_priority_id = next_priority_id;
+        self.l1_tx_hashes
+            .retain(|hash| pending_tx_hashes.contains(hash));
+
+        for account in &mut self.accounts {
+            account.nonce = nonces.get(&account.address).copied().unwrap_or(Nonce(0));
+            account
+                .tx_hashes
+                .retain(|hash| pending_tx_hashes.contains(hash));
+        }
+        self.evicted_transactions
+            .retain(|evicted| pending_tx_hashes.contains(&evicted.hash));
+    }
+}
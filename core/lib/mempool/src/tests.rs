@@ -1,4 +1,8 @@
-use crate::{mempool_store::MempoolStore, types::L2TxFilter};
+use crate::{
+    mempool_store::MempoolStore,
+    ordering::{HybridOrdering, PriorityFeeOrdering},
+    types::{EvictedTransaction, EvictionReason, L2TxFilter},
+};
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 use zksync_types::fee::Fee;
@@ -359,6 +363,329 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn priority_fee_ordering() {
+    let mut mempool =
+        MempoolStore::with_ordering(PriorityOpId(0), 100, Box::new(PriorityFeeOrdering));
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let now = unix_timestamp_ms();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), now, 1),
+        gen_l2_tx_with_priority_fee(account0, Nonce(1), now, 100),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), now + 10, 5),
+        gen_l2_tx_with_priority_fee(account2, Nonce(0), now + 20, 5),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    // Transactions with equal fees are ordered by their timestamps.
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account1, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account2, 0)
+    );
+    // The high-fee transaction of `account0` must wait for its predecessor.
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account0, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account0, 1)
+    );
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+}
+
+#[test]
+fn priority_fee_ordering_with_rollback() {
+    let mut mempool =
+        MempoolStore::with_ordering(PriorityOpId(0), 100, Box::new(PriorityFeeOrdering));
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let now = unix_timestamp_ms();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), now, 10),
+        gen_l2_tx_with_priority_fee(account0, Nonce(1), now, 1),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), now, 5),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let tx = mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    assert_eq!(view(Some(tx.clone())), (account0, 0));
+    mempool.rollback(&tx);
+    mempool.insert(vec![tx], HashMap::new());
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account0, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account1, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account0, 1)
+    );
+}
+
+#[test]
+fn hybrid_ordering() {
+    // 1 gwei of priority fee is worth 10 ms of waiting; the boost is capped at 1 second.
+    let ordering = HybridOrdering::new(10, 1_000);
+    let mut mempool = MempoolStore::with_ordering(PriorityOpId(0), 100, Box::new(ordering));
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let now = unix_timestamp_ms();
+    let gwei = 1_000_000_000;
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), now, 0),
+        // Boosted by 500 ms, so it overtakes the transaction of `account0`.
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), now + 100, 50 * gwei),
+        // Boosted by 1 second (the cap) instead of 100 seconds, so it cannot starve
+        // the transaction of `account0`.
+        gen_l2_tx_with_priority_fee(account2, Nonce(0), now + 2_000, 10_000 * gwei),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account1, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account0, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account2, 0)
+    );
+}
+
+#[test]
+fn per_account_limit() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_max_txs_per_account(2);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx(account0, Nonce(2)),
+        gen_l2_tx(account1, Nonce(0)),
+    ];
+    let evicted_hash = transactions[2].hash();
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.size(), 3);
+    assert_eq!(
+        mempool.get_mempool_info().evicted_transactions,
+        vec![EvictedTransaction {
+            hash: evicted_hash,
+            reason: EvictionReason::AccountLimitExceeded,
+        }]
+    );
+
+    // Filling a nonce gap evicts the transaction with the greatest nonce instead.
+    let gapped_tx = gen_l2_tx(account1, Nonce(2));
+    let evicted_hash = gapped_tx.hash();
+    mempool.insert(vec![gapped_tx], HashMap::new());
+    mempool.insert(vec![gen_l2_tx(account1, Nonce(1))], HashMap::new());
+    assert_eq!(
+        mempool.get_mempool_info().evicted_transactions,
+        vec![EvictedTransaction {
+            hash: evicted_hash,
+            reason: EvictionReason::AccountLimitExceeded,
+        }]
+    );
+
+    let mut executed = HashSet::new();
+    while let Some(tx) = mempool.next_transaction(&L2TxFilter::default()) {
+        executed.insert(view(Some(tx)));
+    }
+    assert_eq!(
+        executed,
+        HashSet::from_iter([(account0, 0), (account0, 1), (account1, 0), (account1, 1)])
+    );
+}
+
+#[test]
+fn eviction_of_cheapest_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 3);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let now = unix_timestamp_ms();
+    let transactions = vec![
+        gen_l2_tx_with_max_fee(account0, Nonce(0), now, 10),
+        // Cheap, but not the last transaction of `account0`, so it cannot be evicted.
+        gen_l2_tx_with_max_fee(account0, Nonce(1), now, 1),
+        gen_l2_tx_with_max_fee(account0, Nonce(2), now, 5),
+        gen_l2_tx_with_max_fee(account1, Nonce(0), now, 3),
+        gen_l2_tx_with_max_fee(account2, Nonce(0), now, 7),
+    ];
+    let evicted_hashes = vec![transactions[3].hash(), transactions[2].hash()];
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.size(), 3);
+
+    let evicted = mempool.get_mempool_info().evicted_transactions;
+    let expected_evicted: Vec<_> = evicted_hashes
+        .into_iter()
+        .map(|hash| EvictedTransaction {
+            hash,
+            reason: EvictionReason::MempoolFull,
+        })
+        .collect();
+    assert_eq!(evicted, expected_evicted);
+
+    let mut executed = HashSet::new();
+    while let Some(tx) = mempool.next_transaction(&L2TxFilter::default()) {
+        executed.insert(view(Some(tx)));
+    }
+    assert_eq!(
+        executed,
+        HashSet::from_iter([(account0, 0), (account0, 1), (account2, 0)])
+    );
+}
+
+#[test]
+fn replacement_with_lower_fee() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_replacement_fee_bump_percent(10);
+    let account = Address::random();
+    let now = unix_timestamp_ms();
+    let original = gen_l2_tx_with_max_fee(account, Nonce(0), now, 100);
+    let original_hash = original.hash();
+    mempool.insert(vec![original], HashMap::new());
+
+    for (i, &max_fee) in [50, 105].iter().enumerate() {
+        let underpriced = gen_l2_tx_with_max_fee(account, Nonce(0), now + 1 + i as u64, max_fee);
+        let underpriced_hash = underpriced.hash();
+        mempool.insert(vec![underpriced], HashMap::new());
+        assert_eq!(mempool.size(), 1);
+        assert_eq!(
+            mempool.get_mempool_info().evicted_transactions,
+            vec![EvictedTransaction {
+                hash: underpriced_hash,
+                reason: EvictionReason::ReplacementUnderpriced,
+            }]
+        );
+    }
+
+    let tx = mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    assert_eq!(tx.hash(), original_hash);
+}
+
+#[test]
+fn replacement_with_sufficient_fee_bump() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_replacement_fee_bump_percent(10);
+    let account = Address::random();
+    let now = unix_timestamp_ms();
+    mempool.insert(
+        vec![gen_l2_tx_with_max_fee(account, Nonce(0), now, 100)],
+        HashMap::new(),
+    );
+
+    let replacement = gen_l2_tx_with_max_fee(account, Nonce(0), now + 1, 110);
+    let replacement_hash = replacement.hash();
+    mempool.insert(vec![replacement], HashMap::new());
+    assert_eq!(mempool.size(), 1);
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+    let tx = mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    assert_eq!(tx.hash(), replacement_hash);
+}
+
+#[test]
+fn snapshot_and_restore() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l1_tx(PriorityOpId(0)),
+        gen_l1_tx(PriorityOpId(1)),
+        gen_l2_tx_with_timestamp(account0, Nonce(0), 1),
+        gen_l2_tx_with_timestamp(account0, Nonce(1), 2),
+        gen_l2_tx_with_timestamp(account0, Nonce(2), 3),
+        gen_l2_tx_with_timestamp(account1, Nonce(0), 10),
+    ];
+    mempool.insert(transactions.clone(), HashMap::new());
+    // Send the L1 transaction and a transaction of `account0` to state keeper.
+    let tx = mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    assert_eq!(priority_id(&tx), Some(PriorityOpId(0)));
+    mempool.next_transaction(&L2TxFilter::default()).unwrap();
+
+    let snapshot = mempool.snapshot();
+    assert_eq!(snapshot.next_priority_id, PriorityOpId(1));
+    assert_eq!(snapshot.l1_tx_hashes, [transactions[1].hash()]);
+    assert_eq!(snapshot.tx_hashes().len(), 4);
+    // Transactions not referenced by the snapshot (e.g., ones sent to state keeper) are ignored.
+    let mut restored = MempoolStore::new(PriorityOpId(0), 100);
+    restored.restore(snapshot, transactions);
+    assert_eq!(restored.size(), mempool.size());
+
+    let mut transactions = vec![];
+    while let Some(tx) = restored.next_transaction(&L2TxFilter::default()) {
+        transactions.push(tx);
+    }
+    assert_eq!(transactions.len(), 4);
+    assert_eq!(priority_id(&transactions[0]), Some(PriorityOpId(1)));
+    let l2_transactions: HashSet<_> = transactions[1..]
+        .iter()
+        .map(|tx| view(Some(tx.clone())))
+        .collect();
+    assert_eq!(
+        l2_transactions,
+        HashSet::from_iter([(account0, 1), (account0, 2), (account1, 0)])
+    );
+    // Nonce of `account0` is preserved, so an outdated transaction is not accepted.
+    restored.insert(vec![gen_l2_tx(account0, Nonce(0))], HashMap::new());
+    assert_eq!(restored.next_transaction(&L2TxFilter::default()), None);
+}
+
+#[test]
+fn snapshot_reconciliation() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx(account0, Nonce(2)),
+        gen_l2_tx(account1, Nonce(0)),
+        gen_l2_tx(account1, Nonce(1)),
+    ];
+    let rejected_hash = transactions[4].hash();
+    mempool.insert(transactions.clone(), HashMap::new());
+    let mut snapshot = mempool.snapshot();
+
+    // Since the snapshot was taken, the first transaction of `account0` was sealed,
+    // and the second transaction of `account1` was rejected.
+    let pending_tx_hashes: HashSet<_> = snapshot
+        .tx_hashes()
+        .into_iter()
+        .filter(|&hash| hash != rejected_hash)
+        .collect();
+    let nonces = HashMap::from_iter([(account0, Nonce(1))]);
+    snapshot.reconcile(PriorityOpId(0), &pending_tx_hashes, &nonces);
+
+    let pending_transactions = transactions
+        .into_iter()
+        .filter(|tx| pending_tx_hashes.contains(&tx.hash()))
+        .collect();
+    let mut restored = MempoolStore::new(PriorityOpId(0), 100);
+    restored.restore(snapshot, pending_transactions);
+    assert_eq!(restored.size(), 3);
+    let mut executed = HashSet::new();
+    while let Some(tx) = restored.next_transaction(&L2TxFilter::default()) {
+        executed.insert(view(Some(tx)));
+    }
+    assert_eq!(
+        executed,
+        HashSet::from_iter([(account0, 1), (account0, 2), (account1, 0)])
+    );
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -375,9 +702,41 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
         Default::default(),
     );
     txn.received_timestamp_ms = received_at_ms;
+    txn.set_input(vec![], H256::random());
     txn.into()
 }
 
+fn gen_l2_tx_with_priority_fee(
+    address: Address,
+    nonce: Nonce,
+    received_at_ms: u64,
+    max_priority_fee_per_gas: u64,
+) -> Transaction {
+    let mut tx = gen_l2_tx_with_timestamp(address, nonce, received_at_ms);
+    match &mut tx.common_data {
+        ExecuteTransactionCommon::L2(data) => {
+            data.fee.max_priority_fee_per_gas = U256::from(max_priority_fee_per_gas)
+        }
+        _ => unreachable!(),
+    };
+    tx
+}
+
+/// Generates a transaction with both `max_fee_per_gas` and `max_priority_fee_per_gas` set to `fee`.
+fn gen_l2_tx_with_max_fee(
+    address: Address,
+    nonce: Nonce,
+    received_at_ms: u64,
+    fee: u64,
+) -> Transaction {
+    let mut tx = gen_l2_tx_with_priority_fee(address, nonce, received_at_ms, fee);
+    match &mut tx.common_data {
+        ExecuteTransactionCommon::L2(data) => data.fee.max_fee_per_gas = U256::from(fee),
+        _ => unreachable!(),
+    };
+    tx
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
@@ -410,6 +769,13 @@ fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     }
 }
 
+fn priority_id(transaction: &Transaction) -> Option<PriorityOpId> {
+    match &transaction.common_data {
+        ExecuteTransactionCommon::L1(data) => Some(data.serial_id),
+        ExecuteTransactionCommon::L2(_) => None,
+    }
+}
+
 fn view(transaction: Option<Transaction>) -> (Address, u32) {
     let tx = transaction.unwrap();
     (tx.initiator_account(), tx.nonce().unwrap().0)
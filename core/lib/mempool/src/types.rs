@@ -1,8 +1,11 @@
-use std::cmp::Ordering;
+use crate::ordering::{MempoolOrdering, TxPriority};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
+use std::fmt;
 use zksync_types::fee::Fee;
 use zksync_types::l2::L2Tx;
-use zksync_types::{Address, Nonce, Transaction, U256};
+use zksync_types::{Address, Nonce, Transaction, H256, U256};
 
 /// Pending mempool transactions of account
 #[derive(Debug)]
@@ -23,18 +26,39 @@ impl AccountTransactions {
     }
 
     /// Inserts new transaction for given account. Returns insertion metadata
-    pub fn insert(&mut self, transaction: L2Tx) -> InsertionMetadata {
+    /// If `replacement_fee_bump_percent` is set, a transaction replacing another one with the same nonce
+    /// must increase both fee values by at least this percentage; otherwise, it is rejected
+    pub fn insert(
+        &mut self,
+        transaction: L2Tx,
+        ordering: &dyn MempoolOrdering,
+        replacement_fee_bump_percent: Option<u64>,
+    ) -> InsertionMetadata {
         let mut metadata = InsertionMetadata::default();
         let nonce = transaction.common_data.nonce;
         // skip insertion if transaction is old
         if nonce < self.nonce {
             return metadata;
         }
-        let new_score = Self::score_for_transaction(&transaction);
+        if let (Some(bump_percent), Some(previous)) =
+            (replacement_fee_bump_percent, self.transactions.get(&nonce))
+        {
+            let is_underpriced = previous.hash() != transaction.hash()
+                && !is_sufficient_fee_bump(
+                    &previous.common_data.fee,
+                    &transaction.common_data.fee,
+                    bump_percent,
+                );
+            if is_underpriced {
+                metadata.rejected_replacement = Some(transaction.hash());
+                return metadata;
+            }
+        }
+        let new_score = Self::score_for_transaction(&transaction, ordering);
         let previous_score = self
             .transactions
             .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+            .map(|tx| Self::score_for_transaction(&tx, ordering));
         metadata.is_new = previous_score.is_none();
         if nonce == self.nonce {
             metadata.new_score = Some(new_score);
@@ -45,7 +69,7 @@ impl AccountTransactions {
 
     /// Returns next transaction to be included in block and optional score of its successor
     /// Panics if no such transaction exists
-    pub fn next(&mut self) -> (L2Tx, Option<MempoolScore>) {
+    pub fn next(&mut self, ordering: &dyn MempoolOrdering) -> (L2Tx, Option<MempoolScore>) {
         let transaction = self
             .transactions
             .remove(&self.nonce)
@@ -54,12 +78,16 @@ impl AccountTransactions {
         let score = self
             .transactions
             .get(&self.nonce)
-            .map(Self::score_for_transaction);
+            .map(|tx| Self::score_for_transaction(tx, ordering));
         (transaction, score)
     }
 
     /// Handles transaction rejection. Returns optional score of its successor
-    pub fn reset(&mut self, transaction: &Transaction) -> Option<MempoolScore> {
+    pub fn reset(
+        &mut self,
+        transaction: &Transaction,
+        ordering: &dyn MempoolOrdering,
+    ) -> Option<MempoolScore> {
         // current nonce for the group needs to be reset
         let tx_nonce = transaction
             .nonce()
@@ -67,16 +95,55 @@ impl AccountTransactions {
         self.nonce = self.nonce.min(tx_nonce);
         self.transactions
             .get(&(tx_nonce + 1))
-            .map(Self::score_for_transaction)
+            .map(|tx| Self::score_for_transaction(tx, ordering))
+    }
+
+    /// Removes the transaction with the greatest nonce. Returns the removed transaction and its score
+    /// if the transaction was the next one to be executed for the account
+    pub fn evict_tail(
+        &mut self,
+        ordering: &dyn MempoolOrdering,
+    ) -> Option<(L2Tx, Option<MempoolScore>)> {
+        let nonce = *self.transactions.keys().max()?;
+        let transaction = self.transactions.remove(&nonce)?;
+        let score =
+            (nonce == self.nonce).then(|| Self::score_for_transaction(&transaction, ordering));
+        Some((transaction, score))
+    }
+
+    /// Returns eviction key of the transaction with the greatest nonce
+    /// Transactions with lower keys are evicted first
+    pub fn tail_eviction_key(&self) -> Option<EvictionKey> {
+        let (_, transaction) = self.transactions.iter().max_by_key(|(nonce, _)| **nonce)?;
+        let fee = &transaction.common_data.fee;
+        Some((
+            fee.max_fee_per_gas,
+            fee.max_priority_fee_per_gas,
+            // Among transactions with equal fees, the most recent ones are evicted first
+            Reverse(transaction.received_timestamp_ms),
+            transaction.initiator_account(),
+        ))
     }
 
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
-    fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
+    pub fn nonce(&self) -> Nonce {
+        self.nonce
+    }
+
+    /// Returns transactions of the account ordered by nonce
+    pub fn transactions(&self) -> Vec<&L2Tx> {
+        let mut transactions: Vec<_> = self.transactions.values().collect();
+        transactions.sort_unstable_by_key(|tx| tx.common_data.nonce);
+        transactions
+    }
+
+    fn score_for_transaction(transaction: &L2Tx, ordering: &dyn MempoolOrdering) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
+            priority: ordering.priority(transaction),
             received_at_ms: transaction.received_timestamp_ms,
             fee_data: transaction.common_data.fee.clone(),
         }
@@ -84,10 +151,11 @@ impl AccountTransactions {
 }
 
 /// Mempool score of transaction. Used to prioritize L2 transactions in mempool
-/// Currently trivial ordering is used based on received at timestamp
+/// Ordering is based on the priority assigned by the mempool ordering policy
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct MempoolScore {
     pub account: Address,
+    pub priority: TxPriority,
     pub received_at_ms: u64,
     // Not used for actual scoring, but state keeper would request
     // transactions that have acceptable fee values (so transactions
@@ -105,7 +173,7 @@ impl MempoolScore {
 
 impl Ord for MempoolScore {
     fn cmp(&self, other: &MempoolScore) -> Ordering {
-        match self.received_at_ms.cmp(&other.received_at_ms).reverse() {
+        match self.priority.cmp(&other.priority) {
             Ordering::Equal => {}
             ordering => return ordering,
         }
@@ -119,11 +187,72 @@ impl PartialOrd for MempoolScore {
     }
 }
 
+/// Checks whether `new_fee` increases both fee values of `previous_fee` by at least `bump_percent`
+fn is_sufficient_fee_bump(previous_fee: &Fee, new_fee: &Fee, bump_percent: u64) -> bool {
+    let bumped = |value: U256| {
+        value
+            .checked_mul(U256::from(100 + bump_percent))
+            .map(|value| value / 100)
+    };
+    let is_bumped = |previous: U256, new: U256| bumped(previous).map_or(false, |min| new >= min);
+    is_bumped(previous_fee.max_fee_per_gas, new_fee.max_fee_per_gas)
+        && is_bumped(
+            previous_fee.max_priority_fee_per_gas,
+            new_fee.max_priority_fee_per_gas,
+        )
+}
+
+/// Key used to select transactions for eviction: max fee per gas, max priority fee per gas,
+/// reversed received at timestamp and initiator account
+pub(crate) type EvictionKey = (U256, U256, Reverse<u64>, Address);
+
 #[derive(Debug, Default)]
 pub(crate) struct InsertionMetadata {
     pub new_score: Option<MempoolScore>,
     pub previous_score: Option<MempoolScore>,
     pub is_new: bool,
+    /// Hash of the transaction if it was rejected as an underpriced replacement
+    pub rejected_replacement: Option<H256>,
+}
+
+/// Reason for evicting an L2 transaction from the mempool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvictionReason {
+    /// Mempool is full, and the transaction is among the lowest-paying ones
+    MempoolFull,
+    /// Initiator account has too many pending transactions in the mempool
+    AccountLimitExceeded,
+    /// Transaction replaces another one with the same nonce without a sufficient fee bump
+    ReplacementUnderpriced,
+}
+
+impl EvictionReason {
+    pub(crate) fn as_metric_label(self) -> &'static str {
+        match self {
+            Self::MempoolFull => "mempool_full",
+            Self::AccountLimitExceeded => "account_limit_exceeded",
+            Self::ReplacementUnderpriced => "replacement_underpriced",
+        }
+    }
+}
+
+impl fmt::Display for EvictionReason {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::MempoolFull => "evicted from the full mempool due to low fee",
+            Self::AccountLimitExceeded => {
+                "too many pending transactions from the initiator account"
+            }
+            Self::ReplacementUnderpriced => "replacement transaction underpriced",
+        })
+    }
+}
+
+/// L2 transaction evicted from the mempool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvictedTransaction {
+    pub hash: H256,
+    pub reason: EvictionReason,
 }
 
 /// Structure that can be used by state keeper to describe
@@ -159,6 +288,10 @@ mod tests {
 
         let score = MempoolScore {
             account: Address::random(),
+            priority: TxPriority {
+                fee: U256::zero(),
+                effective_received_at_ms: 0,
+            }, // Not important
             received_at_ms: Default::default(), // Not important
             fee_data: Fee {
                 gas_limit: Default::default(), // Not important
@@ -7,7 +7,7 @@ use std::{borrow::Borrow, num::NonZeroU32};
 use crate::{
     storage::{MerkleTreeColumnFamily, PatchSet, Patched, RocksDBWrapper},
     types::{Key, Root, TreeInstruction, TreeLogEntry, ValueHash, TREE_DEPTH},
-    BlockOutput, HashTree, MerkleTree,
+    BlockOutput, HashTree, MerkleTree, TreeLogEntryWithProof,
 };
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_storage::RocksDB;
@@ -104,6 +104,11 @@ impl ZkSyncTree {
         }
     }
 
+    /// Returns a read-only view of this tree that shares its RocksDB instance.
+    pub fn reader(&self) -> ZkSyncTreeReader {
+        ZkSyncTreeReader(MerkleTree::new(self.tree.db.inner().clone()))
+    }
+
     /// Signals that the tree should use a dedicated `rayon` thread pool for parallel operations
     /// (for now, hash computations).
     ///
@@ -349,3 +354,41 @@ impl ZkSyncTree {
         self.tree.db.reset();
     }
 }
+
+/// Read-only view of a [`ZkSyncTree`] sharing its RocksDB instance.
+///
+/// The reader only observes changes that were saved to RocksDB via [`ZkSyncTree::save()`];
+/// changes accumulated in RAM are not visible to it.
+#[derive(Debug)]
+pub struct ZkSyncTreeReader(MerkleTree<'static, RocksDBWrapper>);
+
+impl ZkSyncTreeReader {
+    /// Returns the root hash of the tree after the specified L1 batch, or `None` if the L1 batch
+    /// is not present in the tree.
+    pub fn root_hash(&self, l1_batch_number: L1BatchNumber) -> Option<ValueHash> {
+        self.0.root_hash(u64::from(l1_batch_number.0))
+    }
+
+    /// Returns entries with Merkle proofs for the specified hashed storage `keys` in the tree
+    /// state after the specified L1 batch. Returns `None` if the L1 batch is not present
+    /// in the tree.
+    pub fn entries_with_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: &[Key],
+    ) -> Option<Vec<TreeLogEntryWithProof>> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.entries_with_proofs(version, keys)
+    }
+
+    /// Same as [`Self::entries_with_proofs()`], but additionally returns the root hash of the tree
+    /// after the specified L1 batch. The root hash and the proofs are read from the same tree version.
+    pub fn root_hash_with_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: &[Key],
+    ) -> Option<(ValueHash, Vec<TreeLogEntryWithProof>)> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.root_hash_with_proofs(version, keys)
+    }
+}
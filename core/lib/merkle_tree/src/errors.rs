@@ -2,7 +2,7 @@
 
 use std::{error, fmt, str::Utf8Error};
 
-use crate::types::NodeKey;
+use crate::types::{NodeKey, TreeLogEntry, ValueHash};
 
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -134,6 +134,27 @@ impl fmt::Display for DeserializeError {
 
 impl error::Error for DeserializeError {}
 
+/// Error verifying a [`TreeLogEntryWithProof`](crate::TreeLogEntryWithProof).
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ProofVerificationError {
+    /// Only read entries can be verified.
+    #[error("only read entries can be verified, got {0:?}")]
+    UnsupportedEntry(TreeLogEntry),
+    /// Merkle path is longer than the tree depth.
+    #[error("Merkle path has length {0}, which exceeds the tree depth")]
+    PathTooLong(usize),
+    /// Root hash recorded in the entry or computed from its Merkle path doesn't match
+    /// the trusted root hash.
+    #[error("root hash mismatch: expected {expected:?}, got {actual:?}")]
+    RootHashMismatch {
+        /// Trusted root hash.
+        expected: ValueHash,
+        /// Root hash obtained from the entry.
+        actual: ValueHash,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
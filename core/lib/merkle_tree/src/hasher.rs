@@ -5,10 +5,11 @@ use once_cell::sync::Lazy;
 use std::{fmt, iter, slice};
 
 use crate::{
+    errors::ProofVerificationError,
     metrics::HashingMetrics,
     types::{
         BlockOutputWithProofs, ChildRef, InternalNode, Key, LeafNode, Node, TreeInstruction,
-        TreeLogEntry, ValueHash, TREE_DEPTH,
+        TreeLogEntry, TreeLogEntryWithProof, ValueHash, TREE_DEPTH,
     },
 };
 use zksync_crypto::hasher::{blake2::Blake2Hasher, Hasher};
@@ -494,6 +495,41 @@ impl BlockOutputWithProofs {
     }
 }
 
+impl TreeLogEntryWithProof {
+    /// Verifies this read entry for the specified `key` against the trusted root hash
+    /// of the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry is not a read or if the proof doesn't verify.
+    pub fn verify(
+        &self,
+        hasher: &dyn HashTree,
+        key: Key,
+        trusted_root_hash: ValueHash,
+    ) -> Result<(), ProofVerificationError> {
+        if self.merkle_path.len() > TREE_DEPTH {
+            return Err(ProofVerificationError::PathTooLong(self.merkle_path.len()));
+        }
+        let (leaf_index, value) = match self.base {
+            TreeLogEntry::Read { leaf_index, value } => (leaf_index, value),
+            TreeLogEntry::ReadMissingKey => (0, ValueHash::zero()),
+            other => return Err(ProofVerificationError::UnsupportedEntry(other)),
+        };
+
+        let root_hash = hasher.fold_merkle_path(&self.merkle_path, key, value, leaf_index);
+        for actual in [self.root_hash, root_hash] {
+            if actual != trusted_root_hash {
+                return Err(ProofVerificationError::RootHashMismatch {
+                    expected: trusted_root_hash,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
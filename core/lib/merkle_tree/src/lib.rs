@@ -52,6 +52,7 @@ mod types;
 mod utils;
 
 pub use crate::{
+    errors::ProofVerificationError,
     hasher::HashTree,
     pruning::{MerkleTreePruner, MerkleTreePrunerHandle},
     storage::{
@@ -157,6 +158,33 @@ impl<'a, DB: Database> MerkleTree<'a, DB> {
         self.db.root(version)
     }
 
+    /// Returns entries together with their authenticity Merkle proofs for the specified `keys`
+    /// at the specified tree `version`. The entries are returned in the same order as `keys`;
+    /// each entry is either [`TreeLogEntry::Read`] or [`TreeLogEntry::ReadMissingKey`].
+    ///
+    /// Returns `None` if the version was not written yet.
+    pub fn entries_with_proofs(
+        &self,
+        version: u64,
+        keys: &[Key],
+    ) -> Option<Vec<TreeLogEntryWithProof>> {
+        let (_, entries) = self.root_hash_with_proofs(version, keys)?;
+        Some(entries)
+    }
+
+    /// Same as [`Self::entries_with_proofs()`], but additionally returns the root hash
+    /// of the tree `version`. The root hash is computed from the same nodes as the proofs,
+    /// so the proofs always verify against it.
+    pub fn root_hash_with_proofs(
+        &self,
+        version: u64,
+        keys: &[Key],
+    ) -> Option<(ValueHash, Vec<TreeLogEntryWithProof>)> {
+        self.root(version)?;
+        let storage = Storage::new(&self.db, self.hasher, version + 1);
+        Some(storage.prove(keys))
+    }
+
     /// Returns the latest version of the tree present in the database, or `None` if
     /// no versions are present yet.
     pub fn latest_version(&self) -> Option<u64> {
@@ -169,6 +169,10 @@ impl<DB: Database> Patched<DB> {
         Self { inner, patch: None }
     }
 
+    pub(crate) fn inner(&self) -> &DB {
+        &self.inner
+    }
+
     pub(crate) fn patched_versions(&self) -> Vec<u64> {
         self.patch
             .as_ref()
@@ -402,6 +402,47 @@ impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
 
         (block_output, patch)
     }
+
+    /// Proves the existence or absence of the specified `keys` in the tree version *preceding*
+    /// the one this storage was created for. Unlike [`Self::extend_with_proofs()`],
+    /// this operation is read-only; it does not produce a patch set or report block metrics.
+    ///
+    /// Returns the root hash of the proved tree version together with the entries.
+    pub fn prove(mut self, keys: &[Key]) -> (ValueHash, Vec<TreeLogEntryWithProof>) {
+        let sorted_keys = SortedKeys::new(keys.iter().copied());
+        let parent_nibbles = self.updater.load_ancestors(&sorted_keys, self.db);
+
+        let hashing_stats = HashingMetrics::default();
+        let mut hasher = self.hasher.with_stats(&hashing_stats);
+        let root_leaf = match self.updater.patch_set.get(&Nibbles::EMPTY) {
+            Some(Node::Leaf(leaf)) => Some(*leaf),
+            _ => None,
+        };
+        let mut root = self.updater.patch_set.ensure_internal_root_node();
+        if let Some(leaf) = root_leaf {
+            // The child reference created for the moved leaf does not have its hash set.
+            let nibble = Nibbles::nibble(&leaf.full_key, 0);
+            let leaf_hash = leaf.hash(&mut hasher, SUBTREE_ROOT_LEVEL);
+            root.updater(&mut hasher, 0, nibble)
+                .update_child_hash(leaf_hash);
+        }
+        let root_hash = root.hash(&mut hasher, 0);
+
+        let mut logs = Vec::with_capacity(keys.len());
+        for (&key, parent_nibbles) in keys.iter().zip(&parent_nibbles) {
+            let (base, mut merkle_path) = self.updater.prove(&mut hasher, key, parent_nibbles);
+            root.updater(&mut hasher, 0, Nibbles::nibble(&key, 0))
+                .extend_merkle_path(&mut merkle_path);
+            logs.push(TreeLogEntryWithProof {
+                base,
+                merkle_path: merkle_path.into_inner(),
+                root_hash,
+            });
+        }
+        drop(hasher);
+        hashing_stats.report();
+        (root_hash, logs)
+    }
 }
 
 /// Mention of a key in a block: either the first mention, or the same mention as the specified
@@ -8,7 +8,7 @@ use std::{num::NonZeroU32, slice};
 
 use zksync_config::constants::ACCOUNT_CODE_STORAGE_ADDRESS;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
-use zksync_merkle_tree::{domain::ZkSyncTree, HashTree};
+use zksync_merkle_tree::{domain::ZkSyncTree, HashTree, TreeLogEntry};
 use zksync_storage::RocksDB;
 use zksync_types::{
     proofs::StorageLogMetadata, AccountTreeId, Address, L1BatchNumber, StorageKey, StorageLog,
@@ -89,6 +89,54 @@ fn basic_workflow() {
     assert_eq!(tree.block_number(), block_number.get());
 }
 
+#[test]
+fn reading_proofs_via_tree_reader() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let (first_block, second_block) = logs.split_at(50);
+
+    let db = RocksDB::new(temp_dir.as_ref(), false);
+    let mut tree = ZkSyncTree::new_lightweight(db);
+    let reader = tree.reader();
+    let first_root_hash = tree.process_block(first_block).root_hash;
+    tree.save();
+    tree.process_block(second_block);
+
+    let keys: Vec<_> = logs
+        .iter()
+        .map(|log| log.storage_log.key.hashed_key_u256())
+        .collect();
+    let entries = reader.entries_with_proofs(L1BatchNumber(0), &keys).unwrap();
+    for (i, (entry, &key)) in entries.iter().zip(&keys).enumerate() {
+        if i < first_block.len() {
+            let expected_entry = TreeLogEntry::Read {
+                leaf_index: i as u64 + 1,
+                value: logs[i].storage_log.value,
+            };
+            assert_eq!(entry.base, expected_entry);
+        } else {
+            assert_eq!(entry.base, TreeLogEntry::ReadMissingKey);
+        }
+        entry.verify(&Blake2Hasher, key, first_root_hash).unwrap();
+    }
+    // The second block is not saved yet, so it should not be visible to the reader.
+    assert!(reader
+        .entries_with_proofs(L1BatchNumber(1), &keys)
+        .is_none());
+
+    tree.save();
+    let (root_hash, entries) = reader
+        .root_hash_with_proofs(L1BatchNumber(1), &keys)
+        .unwrap();
+    assert_eq!(root_hash, tree.root_hash());
+    for (entry, &key) in entries.iter().zip(&keys) {
+        assert!(matches!(entry.base, TreeLogEntry::Read { .. }));
+        entry.verify(&Blake2Hasher, key, tree.root_hash()).unwrap();
+        // Proofs for the newer tree version must not verify against the older root hash.
+        assert!(entry.verify(&Blake2Hasher, key, first_root_hash).is_err());
+    }
+}
+
 #[test]
 fn basic_workflow_multiblock() {
     let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
@@ -223,6 +223,49 @@ fn proofs_are_computed_correctly_with_intermediate_commits() {
     }
 }
 
+#[test]
+fn entries_with_proofs_are_computed_correctly_for_past_versions() {
+    let (kvs, _) = &*KVS_AND_HASH;
+    let missing_keys: Vec<_> = generate_key_value_pairs(100..120)
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+
+    let mut tree = MerkleTree::new(PatchSet::default());
+    // A single-leaf tree has a leaf as its root node; check that it is handled correctly.
+    let (first_kv, other_kvs) = kvs.split_first().unwrap();
+    tree.extend(vec![*first_kv]);
+    for chunk in other_kvs.chunks(33) {
+        tree.extend(chunk.to_vec());
+    }
+
+    let latest_version = tree.latest_version().unwrap();
+    for version in 0..=latest_version {
+        let root_hash = tree.root_hash(version).unwrap();
+        let mut keys: Vec<_> = kvs.iter().map(|(key, _)| *key).collect();
+        keys.extend_from_slice(&missing_keys);
+        let entries = tree.entries_with_proofs(version, &keys).unwrap();
+        assert_eq!(entries.len(), keys.len());
+
+        let present_count = 1 + 33 * version as usize;
+        for (i, (entry, &key)) in entries.iter().zip(&keys).enumerate() {
+            if i < present_count.min(kvs.len()) {
+                let expected_entry = TreeLogEntry::Read {
+                    leaf_index: i as u64 + 1,
+                    value: kvs[i].1,
+                };
+                assert_eq!(entry.base, expected_entry);
+            } else {
+                assert_eq!(entry.base, TreeLogEntry::ReadMissingKey);
+            }
+            entry.verify(&Blake2Hasher, key, root_hash).unwrap();
+        }
+    }
+    assert!(tree
+        .entries_with_proofs(latest_version + 1, &missing_keys)
+        .is_none());
+}
+
 fn test_accumulated_commits<DB: Database>(db: DB, chunk_size: usize) -> DB {
     let (kvs, expected_hash) = &*KVS_AND_HASH;
     let mut db = Patched::new(db);
@@ -0,0 +1,487 @@
+//! [`ObjectStore`] wrapper caching blobs on the local disk.
+
+use async_trait::async_trait;
+use tokio::{fs, io};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry {
+    size: u64,
+    last_access: u64,
+}
+
+/// Bookkeeping for the least recently used eviction policy. Instead of timestamps, accesses
+/// are ordered using a monotonically increasing counter.
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<PathBuf, CacheEntry>,
+    access_order: BTreeMap<u64, PathBuf>,
+    total_size: u64,
+    clock: u64,
+}
+
+impl LruState {
+    /// Marks the entry as most recently used. Returns `false` if the entry is not cached.
+    fn touch(&mut self, path: &Path) -> bool {
+        let Some(entry) = self.entries.get_mut(path) else {
+            return false;
+        };
+        self.access_order.remove(&entry.last_access);
+        self.clock += 1;
+        entry.last_access = self.clock;
+        self.access_order.insert(self.clock, path.to_owned());
+        true
+    }
+
+    /// Inserts a new entry, evicting least recently used entries to fit into `capacity`.
+    /// Returns paths of the evicted entries.
+    fn insert(&mut self, path: PathBuf, size: u64, capacity: u64) -> Vec<PathBuf> {
+        self.remove(&path);
+        self.clock += 1;
+        let entry = CacheEntry {
+            size,
+            last_access: self.clock,
+        };
+        self.entries.insert(path.clone(), entry);
+        self.access_order.insert(self.clock, path);
+        self.total_size += size;
+
+        let mut evicted_paths = vec![];
+        while self.total_size > capacity {
+            let (_, path) = self
+                .access_order
+                .pop_first()
+                .expect("cache size exceeds capacity with no entries");
+            let entry = self.entries.remove(&path).unwrap();
+            self.total_size -= entry.size;
+            evicted_paths.push(path);
+        }
+        evicted_paths
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(entry) = self.entries.remove(path) {
+            self.access_order.remove(&entry.last_access);
+            self.total_size -= entry.size;
+        }
+    }
+}
+
+/// [`ObjectStore`] wrapper keeping recently accessed blobs in a local directory, with
+/// the total size of cached blobs capped by the capacity. Blobs are cached both on reads
+/// and writes; writes and removals are propagated to the wrapped store.
+///
+/// Blobs are stored in per-bucket subdirectories of the cache directory, and are reused
+/// across restarts. The cache never touches other contents of the cache directory.
+/// Since the cache index is kept in memory, a directory must not be shared by several caches;
+/// [`ObjectStoreFactory`](crate::ObjectStoreFactory) shares a single cache among all stores
+/// it creates.
+#[derive(Debug)]
+pub(crate) struct CachingObjectStore {
+    inner: Box<dyn ObjectStore>,
+    cache_dir: PathBuf,
+    capacity: u64,
+    state: Mutex<LruState>,
+}
+
+impl CachingObjectStore {
+    /// Extension of temporary files used when caching blobs.
+    const TMP_EXTENSION: &'static str = "tmp";
+
+    /// Creates a cache in `cache_dir` with the specified capacity in bytes. Blobs cached
+    /// in `cache_dir` previously are indexed, evicting the least recently modified ones
+    /// if they don't fit into the capacity.
+    pub async fn new(inner: Box<dyn ObjectStore>, cache_dir: String, capacity: u64) -> Self {
+        let cache_dir = PathBuf::from(cache_dir);
+        fs::create_dir_all(&cache_dir).await.unwrap_or_else(|err| {
+            panic!("failed creating cache dir `{}`: {err}", cache_dir.display());
+        });
+
+        let this = Self {
+            inner,
+            cache_dir,
+            capacity,
+            state: Mutex::default(),
+        };
+        this.load_cached_blobs().await.unwrap_or_else(|err| {
+            panic!(
+                "failed loading cached blobs from `{}`: {err}",
+                this.cache_dir.display()
+            );
+        });
+        this
+    }
+
+    /// Restores the cache index from the bucket subdirectories of the cache directory.
+    /// Since access times are not persisted, blobs are ordered by the modification time.
+    /// Temporary files left by interrupted writes are removed.
+    async fn load_cached_blobs(&self) -> io::Result<()> {
+        let mut blobs = vec![];
+        for bucket in Bucket::ALL {
+            let mut pending_dirs = vec![self.cache_dir.join(bucket.as_str())];
+            while let Some(dir) = pending_dirs.pop() {
+                let mut entries = match fs::read_dir(&dir).await {
+                    Ok(entries) => entries,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                    Err(err) => return Err(err),
+                };
+                while let Some(entry) = entries.next_entry().await? {
+                    let path = entry.path();
+                    let metadata = entry.metadata().await?;
+                    if metadata.is_dir() {
+                        pending_dirs.push(path);
+                    } else if path.extension() == Some(Self::TMP_EXTENSION.as_ref()) {
+                        Self::remove_file(&path).await;
+                    } else if metadata.is_file() {
+                        let modified_at = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                        blobs.push((modified_at, path, metadata.len()));
+                    }
+                }
+            }
+        }
+        blobs.sort_unstable();
+
+        let evicted_paths: Vec<_> = {
+            let mut state = self.state.lock().unwrap();
+            blobs
+                .into_iter()
+                .flat_map(|(_, path, size)| state.insert(path, size, self.capacity))
+                .collect()
+        };
+        for path in evicted_paths {
+            Self::remove_file(&path).await;
+        }
+        Ok(())
+    }
+
+    fn cache_path(&self, bucket: Bucket, key: &str) -> PathBuf {
+        self.cache_dir.join(bucket.as_str()).join(key)
+    }
+
+    async fn read_cached(&self, path: &Path) -> Option<Vec<u8>> {
+        if !self.state.lock().unwrap().touch(path) {
+            return None;
+        }
+        match fs::read(path).await {
+            Ok(blob) => Some(blob),
+            Err(err) => {
+                // The file may have been evicted concurrently, so we treat this as a cache miss.
+                vlog::warn!("Failed reading cached blob `{}`: {err}", path.display());
+                self.state.lock().unwrap().remove(path);
+                None
+            }
+        }
+    }
+
+    async fn cache(&self, path: PathBuf, blob: &[u8]) {
+        let size = blob.len() as u64;
+        if size > self.capacity {
+            return;
+        }
+        if let Err(err) = Self::write_file(&path, blob).await {
+            vlog::warn!("Failed caching blob `{}`: {err}", path.display());
+            return;
+        }
+
+        let evicted_paths = self.state.lock().unwrap().insert(path, size, self.capacity);
+        for path in evicted_paths {
+            Self::remove_file(&path).await;
+        }
+    }
+
+    async fn write_file(path: &Path, blob: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        // Write to a temporary file first, so that readers never observe a partially written blob.
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".");
+        tmp_path.push(Self::TMP_EXTENSION);
+        fs::write(&tmp_path, blob).await?;
+        fs::rename(&tmp_path, path).await
+    }
+
+    async fn remove_file(path: &Path) {
+        if let Err(err) = fs::remove_file(path).await {
+            if err.kind() != io::ErrorKind::NotFound {
+                vlog::warn!("Failed removing cached blob `{}`: {err}", path.display());
+            }
+        }
+    }
+
+    async fn invalidate(&self, path: &Path) {
+        self.state.lock().unwrap().remove(path);
+        Self::remove_file(path).await;
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CachingObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let path = self.cache_path(bucket, key);
+        if let Some(blob) = self.read_cached(&path).await {
+            metrics::increment_counter!(
+                "server.object_store.local_cache",
+                "bucket" => bucket.as_str(),
+                "result" => "hit"
+            );
+            return Ok(blob);
+        }
+
+        metrics::increment_counter!(
+            "server.object_store.local_cache",
+            "bucket" => bucket.as_str(),
+            "result" => "miss"
+        );
+        let blob = self.inner.get_raw(bucket, key).await?;
+        self.cache(path, &blob).await;
+        Ok(blob)
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let path = self.cache_path(bucket, key);
+        // Invalidate the cached blob first, so that it doesn't become stale if the write fails.
+        self.invalidate(&path).await;
+        self.inner.put_raw(bucket, key, value.clone()).await?;
+        self.cache(path, &value).await;
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.invalidate(&self.cache_path(bucket, key)).await;
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    async fn list_raw(
+        &self,
+        bucket: Bucket,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<Vec<String>, ObjectStoreError> {
+        self.inner.list_raw(bucket, prefix, start_after).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempdir::TempDir;
+
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{mock::MockStore, ObjectStoreFactory};
+    use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
+
+    async fn caching_store(capacity: u64) -> (TempDir, Arc<MockStore>, CachingObjectStore) {
+        let dir = TempDir::new("test-cache").unwrap();
+        let inner = Arc::new(MockStore::default());
+        let store = restart_caching_store(&dir, &inner, capacity).await;
+        (dir, inner, store)
+    }
+
+    async fn restart_caching_store(
+        dir: &TempDir,
+        inner: &Arc<MockStore>,
+        capacity: u64,
+    ) -> CachingObjectStore {
+        let cache_dir = dir.path().join("cache").into_os_string().into_string();
+        CachingObjectStore::new(Box::new(Arc::clone(inner)), cache_dir.unwrap(), capacity).await
+    }
+
+    #[tokio::test]
+    async fn fetched_blobs_are_cached() {
+        let (_dir, inner, store) = caching_store(1_024).await;
+        inner
+            .put_raw(Bucket::ProverJobs, "test-key.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let blob = store
+            .get_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(blob, [1, 2, 3]);
+
+        // Remove the blob from the wrapped store; it should still be returned from the cache.
+        inner
+            .remove_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        let blob = store
+            .get_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(blob, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn writes_and_removals_are_propagated() {
+        let (_dir, inner, store) = caching_store(1_024).await;
+        store
+            .put_raw(Bucket::WitnessInput, "test-key.bin", vec![1, 2])
+            .await
+            .unwrap();
+        let blob = inner
+            .get_raw(Bucket::WitnessInput, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(blob, [1, 2]);
+
+        store
+            .put_raw(Bucket::WitnessInput, "test-key.bin", vec![3])
+            .await
+            .unwrap();
+        let blob = store
+            .get_raw(Bucket::WitnessInput, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(blob, [3]);
+
+        store
+            .remove_raw(Bucket::WitnessInput, "test-key.bin")
+            .await
+            .unwrap();
+        let stores: [&dyn ObjectStore; 2] = [&store, &inner];
+        for store in stores {
+            let err = store
+                .get_raw(Bucket::WitnessInput, "test-key.bin")
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_blobs_are_evicted() {
+        let (_dir, inner, store) = caching_store(10).await;
+        for key in ["a", "b", "c"] {
+            inner
+                .put_raw(Bucket::ProofsFri, key, vec![0; 4])
+                .await
+                .unwrap();
+        }
+        for key in ["a", "b", "a", "c"] {
+            store.get_raw(Bucket::ProofsFri, key).await.unwrap();
+        }
+
+        for key in ["a", "b", "c"] {
+            inner.remove_raw(Bucket::ProofsFri, key).await.unwrap();
+        }
+        store.get_raw(Bucket::ProofsFri, "a").await.unwrap();
+        store.get_raw(Bucket::ProofsFri, "c").await.unwrap();
+        let err = store.get_raw(Bucket::ProofsFri, "b").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        assert!(!store.cache_path(Bucket::ProofsFri, "b").exists());
+    }
+
+    #[tokio::test]
+    async fn blobs_exceeding_capacity_are_not_cached() {
+        let (_dir, inner, store) = caching_store(2).await;
+        inner
+            .put_raw(Bucket::ProverJobs, "large.bin", vec![0; 3])
+            .await
+            .unwrap();
+        store
+            .get_raw(Bucket::ProverJobs, "large.bin")
+            .await
+            .unwrap();
+        assert!(!store.cache_path(Bucket::ProverJobs, "large.bin").exists());
+    }
+
+    #[tokio::test]
+    async fn cached_blobs_are_reused_after_restart() {
+        let (dir, inner, store) = caching_store(1_024).await;
+        for key in ["a", "b", "c"] {
+            store
+                .put_raw(Bucket::ProverJobs, key, vec![0; 4])
+                .await
+                .unwrap();
+            inner.remove_raw(Bucket::ProverJobs, key).await.unwrap();
+        }
+        drop(store);
+
+        // Restart the cache with a smaller capacity; the least recently modified blob is evicted.
+        let store = restart_caching_store(&dir, &inner, 8).await;
+        for key in ["b", "c"] {
+            let blob = store.get_raw(Bucket::ProverJobs, key).await.unwrap();
+            assert_eq!(blob, [0; 4]);
+        }
+        let err = store.get_raw(Bucket::ProverJobs, "a").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        assert!(!store.cache_path(Bucket::ProverJobs, "a").exists());
+    }
+
+    #[tokio::test]
+    async fn unrelated_files_in_cache_dir_are_retained() {
+        let dir = TempDir::new("test-cache").unwrap();
+        let cache_dir = dir.path().join("cache");
+        let unrelated_path = cache_dir.join("unrelated.bin");
+        let tmp_path = cache_dir
+            .join(Bucket::ProverJobs.as_str())
+            .join("a.bin.tmp");
+        fs::create_dir_all(tmp_path.parent().unwrap())
+            .await
+            .unwrap();
+        fs::write(&unrelated_path, [0; 16]).await.unwrap();
+        fs::write(&tmp_path, [0; 4]).await.unwrap();
+
+        let inner = Arc::new(MockStore::default());
+        let store = restart_caching_store(&dir, &inner, 8).await;
+        assert!(unrelated_path.exists());
+        assert!(!tmp_path.exists());
+        assert_eq!(store.state.lock().unwrap().total_size, 0);
+    }
+
+    #[tokio::test]
+    async fn factory_shares_cache_among_stores() {
+        let dir = TempDir::new("test-cache").unwrap();
+        let path = |name: &str| {
+            dir.path()
+                .join(name)
+                .into_os_string()
+                .into_string()
+                .unwrap()
+        };
+        let config = ObjectStoreConfig {
+            bucket_base_url: String::new(),
+            mode: ObjectStoreMode::FileBacked,
+            file_backed_base_path: path("store"),
+            gcs_credential_file_path: String::new(),
+            max_retries: 1,
+            s3_endpoint: None,
+            s3_region: None,
+            s3_path_style: false,
+            blob_encoding: Default::default(),
+            local_cache_path: Some(path("cache")),
+            local_cache_capacity_mb: None,
+            mirror_bucket_base_url: None,
+        };
+        let factory = ObjectStoreFactory::new(config);
+        let store = factory.create_store().await;
+        let other_store = factory.create_store().await;
+
+        store
+            .put_raw(Bucket::ProverJobs, "test-key.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let stored_path = dir.path().join("store/prover_jobs/test-key.bin");
+        fs::remove_file(stored_path).await.unwrap();
+        let blob = other_store
+            .get_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(blob, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,263 @@
+//! Versioned encoding of blobs with optional compression and integrity checks.
+//!
+//! Encoded blobs have the following layout:
+//!
+//! | Bytes   | Contents                                                   |
+//! |---------|------------------------------------------------------------|
+//! | `0..4`  | Magic bytes `\xFFZKB`                                      |
+//! | `4`     | Format version (currently, 1)                              |
+//! | `5`     | Compression: 0 for none, 1 for zstd                        |
+//! | `6..10` | CRC32 checksum of the uncompressed contents, little-endian |
+//! | `10..`  | (Possibly compressed) contents                             |
+//!
+//! Blobs not starting with the magic bytes are considered to be written before the encoding
+//! was introduced, and are returned as is.
+
+use async_trait::async_trait;
+use zksync_config::configs::object_store::BlobEncoding;
+
+use crate::raw::{BoxedError, Bucket, ObjectStore, ObjectStoreError};
+
+const MAGIC: [u8; 4] = *b"\xFFZKB";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    None = 0,
+    Zstd = 1,
+}
+
+impl Compression {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::None),
+            1 => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+fn encode(contents: &[u8], compression: Compression) -> Result<Vec<u8>, BoxedError> {
+    let checksum = crc32fast::hash(contents);
+    let mut blob = Vec::with_capacity(HEADER_LEN + contents.len());
+    blob.extend_from_slice(&MAGIC);
+    blob.push(VERSION);
+    blob.push(compression as u8);
+    blob.extend_from_slice(&checksum.to_le_bytes());
+    match compression {
+        Compression::None => blob.extend_from_slice(contents),
+        Compression::Zstd => {
+            zstd::stream::copy_encode(contents, &mut blob, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+        }
+    }
+    Ok(blob)
+}
+
+fn decode(mut blob: Vec<u8>) -> Result<Vec<u8>, BoxedError> {
+    if blob.len() < HEADER_LEN || blob[..MAGIC.len()] != MAGIC {
+        return Ok(blob);
+    }
+
+    let version = blob[4];
+    if version != VERSION {
+        return Err(format!("unsupported blob format version: {version}").into());
+    }
+    let compression = Compression::from_byte(blob[5])
+        .ok_or_else(|| format!("unsupported blob compression: {}", blob[5]))?;
+    let expected_checksum = u32::from_le_bytes(blob[6..HEADER_LEN].try_into().unwrap());
+    let contents = match compression {
+        Compression::None => {
+            blob.drain(..HEADER_LEN);
+            blob
+        }
+        Compression::Zstd => zstd::stream::decode_all(&blob[HEADER_LEN..])?,
+    };
+
+    let checksum = crc32fast::hash(&contents);
+    if checksum != expected_checksum {
+        let err = format!(
+            "blob checksum mismatch: expected {expected_checksum:#010x}, got {checksum:#010x}"
+        );
+        return Err(err.into());
+    }
+    Ok(contents)
+}
+
+/// [`ObjectStore`] wrapper writing blobs using the configured [`BlobEncoding`]. Blobs are decoded
+/// on read regardless of the configured encoding.
+#[derive(Debug)]
+pub(crate) struct EncodingObjectStore {
+    inner: Box<dyn ObjectStore>,
+    encoding: BlobEncoding,
+}
+
+impl EncodingObjectStore {
+    pub fn new(inner: Box<dyn ObjectStore>, encoding: BlobEncoding) -> Self {
+        Self { inner, encoding }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for EncodingObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let blob = self.inner.get_raw(bucket, key).await?;
+        // Blobs may be large, so we decode them on a blocking thread.
+        tokio::task::spawn_blocking(move || decode(blob))
+            .await
+            .expect("decoding blob panicked")
+            .map_err(|err| {
+                vlog::warn!("Failed decoding blob for key {key} from bucket {bucket}: {err}");
+                ObjectStoreError::Serialization(err)
+            })
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let compression = match self.encoding {
+            BlobEncoding::Raw => return self.inner.put_raw(bucket, key, value).await,
+            BlobEncoding::Checksummed => Compression::None,
+            BlobEncoding::Zstd => Compression::Zstd,
+        };
+        let blob = tokio::task::spawn_blocking(move || encode(&value, compression))
+            .await
+            .expect("encoding blob panicked")
+            .map_err(ObjectStoreError::Serialization)?;
+        self.inner.put_raw(bucket, key, blob).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    async fn list_raw(
+        &self,
+        bucket: Bucket,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<Vec<String>, ObjectStoreError> {
+        self.inner.list_raw(bucket, prefix, start_after).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    fn encoding_store(encoding: BlobEncoding) -> (Arc<MockStore>, EncodingObjectStore) {
+        let inner = Arc::new(MockStore::default());
+        let store = EncodingObjectStore::new(Box::new(Arc::clone(&inner)), encoding);
+        (inner, store)
+    }
+
+    fn test_contents() -> Vec<u8> {
+        (0_u32..10_000)
+            .flat_map(|i| (i % 64).to_le_bytes())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn raw_blobs_are_stored_as_is() {
+        let (inner, store) = encoding_store(BlobEncoding::Raw);
+        store
+            .put_raw(Bucket::ProverJobs, "test-key.bin", test_contents())
+            .await
+            .unwrap();
+        let blob = inner
+            .get_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(blob, test_contents());
+    }
+
+    #[tokio::test]
+    async fn encoded_blobs_roundtrip() {
+        for encoding in [BlobEncoding::Checksummed, BlobEncoding::Zstd] {
+            let (inner, store) = encoding_store(encoding);
+            store
+                .put_raw(Bucket::ProverJobs, "test-key.bin", test_contents())
+                .await
+                .unwrap();
+
+            let blob = inner
+                .get_raw(Bucket::ProverJobs, "test-key.bin")
+                .await
+                .unwrap();
+            assert_eq!(blob[..4], MAGIC);
+            assert_eq!(blob[4], VERSION);
+            if encoding == BlobEncoding::Zstd {
+                assert!(blob.len() < test_contents().len());
+            } else {
+                assert_eq!(blob[HEADER_LEN..], test_contents());
+            }
+
+            let contents = store
+                .get_raw(Bucket::ProverJobs, "test-key.bin")
+                .await
+                .unwrap();
+            assert_eq!(contents, test_contents());
+            // Blobs must be readable regardless of the configured encoding.
+            let raw_store = EncodingObjectStore::new(Box::new(inner), BlobEncoding::Raw);
+            let contents = raw_store
+                .get_raw(Bucket::ProverJobs, "test-key.bin")
+                .await
+                .unwrap();
+            assert_eq!(contents, test_contents());
+        }
+    }
+
+    #[tokio::test]
+    async fn legacy_blobs_are_readable() {
+        let (inner, store) = encoding_store(BlobEncoding::Zstd);
+        for legacy_blob in [vec![], vec![0xff, b'Z'], test_contents()] {
+            inner
+                .put_raw(Bucket::WitnessInput, "legacy.bin", legacy_blob.clone())
+                .await
+                .unwrap();
+            let contents = store
+                .get_raw(Bucket::WitnessInput, "legacy.bin")
+                .await
+                .unwrap();
+            assert_eq!(contents, legacy_blob);
+        }
+    }
+
+    #[tokio::test]
+    async fn corrupted_blobs_are_rejected() {
+        for encoding in [BlobEncoding::Checksummed, BlobEncoding::Zstd] {
+            let (inner, store) = encoding_store(encoding);
+            store
+                .put_raw(Bucket::ProofsFri, "proof.bin", test_contents())
+                .await
+                .unwrap();
+            let mut blob = inner.get_raw(Bucket::ProofsFri, "proof.bin").await.unwrap();
+            let last_byte = blob.last_mut().unwrap();
+            *last_byte = !*last_byte;
+            inner
+                .put_raw(Bucket::ProofsFri, "proof.bin", blob)
+                .await
+                .unwrap();
+
+            let err = store
+                .get_raw(Bucket::ProofsFri, "proof.bin")
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ObjectStoreError::Serialization(_)), "{err}");
+        }
+    }
+
+    #[test]
+    fn unsupported_versions_are_rejected() {
+        let mut blob = encode(&test_contents(), Compression::None).unwrap();
+        blob[4] = VERSION + 1;
+        let err = decode(blob).unwrap_err();
+        assert!(err.to_string().contains("version"), "{err}");
+    }
+}
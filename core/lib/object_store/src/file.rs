@@ -3,7 +3,7 @@ use tokio::{fs, io};
 
 use std::fmt::Debug;
 
-use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError, LIST_PAGE_SIZE};
 
 impl From<io::Error> for ObjectStoreError {
     fn from(err: io::Error) -> Self {
@@ -21,18 +21,7 @@ pub(crate) struct FileBackedObjectStore {
 
 impl FileBackedObjectStore {
     pub async fn new(base_dir: String) -> Self {
-        for bucket in &[
-            Bucket::ProverJobs,
-            Bucket::WitnessInput,
-            Bucket::LeafAggregationWitnessJobs,
-            Bucket::NodeAggregationWitnessJobs,
-            Bucket::SchedulerWitnessJobs,
-            Bucket::ProverJobsFri,
-            Bucket::LeafAggregationWitnessJobsFri,
-            Bucket::NodeAggregationWitnessJobsFri,
-            Bucket::SchedulerWitnessJobsFri,
-            Bucket::ProofsFri,
-        ] {
+        for bucket in Bucket::ALL {
             let bucket_path = format!("{base_dir}/{bucket}");
             fs::create_dir_all(&bucket_path)
                 .await
@@ -69,6 +58,28 @@ impl ObjectStore for FileBackedObjectStore {
         let filename = self.filename(bucket, key);
         fs::remove_file(filename).await.map_err(From::from)
     }
+
+    async fn list_raw(
+        &self,
+        bucket: Bucket,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<Vec<String>, ObjectStoreError> {
+        let mut entries = fs::read_dir(format!("{}/{bucket}", self.base_dir)).await?;
+        let mut keys = vec![];
+        while let Some(entry) = entries.next_entry().await? {
+            let Ok(key) = entry.file_name().into_string() else {
+                continue;
+            };
+            let is_listed = key.starts_with(prefix) && Some(key.as_str()) > start_after;
+            if is_listed && entry.file_type().await?.is_file() {
+                keys.push(key);
+            }
+        }
+        keys.sort_unstable();
+        keys.truncate(LIST_PAGE_SIZE);
+        Ok(keys)
+    }
 }
 
 #[cfg(test)]
@@ -120,4 +131,36 @@ mod test {
             .await;
         assert!(result.is_ok(), "result must be OK");
     }
+
+    #[tokio::test]
+    async fn test_list() {
+        let dir = TempDir::new("test-data").unwrap();
+        let path = dir.into_path().into_os_string().into_string().unwrap();
+        let object_store = FileBackedObjectStore::new(path).await;
+        for key in [
+            "basic_circuits_2.bin",
+            "basic_circuits_1.bin",
+            "aggregations_1.bin",
+        ] {
+            object_store
+                .put_raw(Bucket::ProverJobs, key, vec![0, 1])
+                .await
+                .unwrap();
+        }
+        let keys = object_store
+            .list_raw(Bucket::ProverJobs, "basic_circuits_", None)
+            .await
+            .unwrap();
+        assert_eq!(keys, ["basic_circuits_1.bin", "basic_circuits_2.bin"]);
+        let keys = object_store
+            .list_raw(Bucket::ProverJobs, "", Some("aggregations_1.bin"))
+            .await
+            .unwrap();
+        assert_eq!(keys, ["basic_circuits_1.bin", "basic_circuits_2.bin"]);
+        let keys = object_store
+            .list_raw(Bucket::WitnessInput, "", None)
+            .await
+            .unwrap();
+        assert!(keys.is_empty());
+    }
 }
@@ -8,42 +8,19 @@ use google_cloud_storage::{
         delete::DeleteObjectRequest,
         download::Range,
         get::GetObjectRequest,
+        list::ListObjectsRequest,
         upload::{Media, UploadObjectRequest, UploadType},
     },
     http::Error as HttpError,
 };
 use http::StatusCode;
 
-use std::{
-    fmt,
-    future::Future,
-    time::{Duration, Instant},
-};
-
-use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+use std::{fmt, future::Future, time::Instant};
 
-async fn retry<T, E, Fut, F>(max_retries: u16, mut f: F) -> Result<T, E>
-where
-    Fut: Future<Output = Result<T, E>>,
-    F: FnMut() -> Fut,
-{
-    let mut retries = 1;
-    let mut backoff = 1;
-    loop {
-        match f().await {
-            Ok(result) => return Ok(result),
-            Err(err) => {
-                vlog::warn!("Failed gcs request {retries}/{max_retries}, retrying.");
-                if retries > max_retries {
-                    return Err(err);
-                }
-                retries += 1;
-                tokio::time::sleep(Duration::from_secs(backoff)).await;
-                backoff *= 2;
-            }
-        }
-    }
-}
+use crate::{
+    raw::{Bucket, ObjectStore, ObjectStoreError, LIST_PAGE_SIZE},
+    retries::retry,
+};
 
 pub struct GoogleCloudStorage {
     bucket_prefix: String,
@@ -215,44 +192,35 @@ impl ObjectStore for GoogleCloudStorage {
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
         self.remove_inner(bucket.as_str(), key).await
     }
-}
-
-#[cfg(test)]
-mod test {
-    use std::sync::atomic::{AtomicU16, Ordering};
-
-    use super::*;
-
-    #[tokio::test]
-    async fn test_retry_success_immediate() {
-        let result = retry(2, || async { Ok::<_, ()>(42) }).await;
-        assert_eq!(result, Ok(42));
-    }
-
-    #[tokio::test]
-    async fn test_retry_failure_exhausted() {
-        let result = retry(2, || async { Err::<i32, _>(()) }).await;
-        assert_eq!(result, Err(()));
-    }
-
-    async fn retry_success_after_n_retries(n: u16) -> Result<u32, String> {
-        let retries = AtomicU16::new(0);
-        let result = retry(n, || async {
-            let retries = retries.fetch_add(1, Ordering::Relaxed);
-            if retries + 1 == n {
-                Ok(42)
-            } else {
-                Err(())
-            }
-        })
-        .await;
 
-        result.map_err(|_| "Retry failed".to_string())
-    }
+    async fn list_raw(
+        &self,
+        bucket: Bucket,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<Vec<String>, ObjectStoreError> {
+        let bucket_prefix = Self::filename(bucket.as_str(), "");
+        vlog::trace!(
+            "Listing data in GCS with prefix {bucket_prefix}{prefix} after {start_after:?} \
+             from bucket {}",
+            self.bucket_prefix
+        );
 
-    #[tokio::test]
-    async fn test_retry_success_after_retry() {
-        let result = retry(2, || retry_success_after_n_retries(2)).await;
-        assert_eq!(result, Ok(42));
+        let request = ListObjectsRequest {
+            bucket: self.bucket_prefix.clone(),
+            prefix: Some(format!("{bucket_prefix}{prefix}")),
+            // The start offset is inclusive, so the object at `start_after` is filtered out below.
+            start_offset: start_after.map(|key| format!("{bucket_prefix}{key}")),
+            max_results: Some(LIST_PAGE_SIZE as i32),
+            ..ListObjectsRequest::default()
+        };
+        let response = retry(self.max_retries, || self.client.list_objects(&request)).await?;
+        let objects = response.items.unwrap_or_default();
+        let keys = objects
+            .into_iter()
+            .filter_map(|object| object.name.strip_prefix(&bucket_prefix).map(str::to_owned))
+            .filter(|key| Some(key.as_str()) != start_after)
+            .collect();
+        Ok(keys)
     }
 }
@@ -3,11 +3,15 @@
 //!
 //! - File-based storage saving blobs as separate files in the local filesystem
 //! - GCS-based storage
+//! - S3-based storage (AWS S3 or an S3-compatible storage, such as MinIO)
 //!
 //! These implementations are not exposed externally. Instead, a store trait object
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
 //! The configuration can be provided explicitly (see [`ObjectStoreFactory::new()`])
 //! or obtained from the environment (see [`ObjectStoreFactory::from_env()`]).
+//! Stores created from the configuration can optionally compress blobs and check their integrity;
+//! see [`BlobEncoding`](zksync_config::configs::object_store::BlobEncoding). They can also
+//! cache blobs on the local disk and mirror writes to another bucket.
 //!
 //! Besides the lower-level storage abstraction, the crate provides high-level
 //! typesafe `<dyn ObjectStore>::get()` and `<dyn ObjectStore>::put()` methods
@@ -23,11 +27,16 @@
     clippy::doc_markdown
 )]
 
+mod cache;
+mod encoding;
 mod file;
 mod gcs;
+mod mirror;
 mod mock;
 mod objects;
 mod raw;
+mod retries;
+mod s3;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
@@ -39,5 +48,5 @@ pub mod _reexports {
 
 pub use self::{
     objects::{AggregationsKey, CircuitKey, ClosedFormInputKey, FriCircuitKey, StoredObject},
-    raw::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory},
+    raw::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory, LIST_PAGE_SIZE},
 };
@@ -0,0 +1,193 @@
+//! [`ObjectStore`] wrapper mirroring writes to another store.
+
+use async_trait::async_trait;
+
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+
+/// [`ObjectStore`] wrapper writing blobs to both the primary and the mirror store. Reads
+/// are served from the primary store, falling back to the mirror if a blob is not found.
+/// This allows to migrate between buckets without downtime: blobs written before the migration
+/// remain accessible, and blobs written after it are available in both buckets.
+#[derive(Debug)]
+pub(crate) struct MirroringObjectStore {
+    primary: Box<dyn ObjectStore>,
+    mirror: Box<dyn ObjectStore>,
+}
+
+impl MirroringObjectStore {
+    pub fn new(primary: Box<dyn ObjectStore>, mirror: Box<dyn ObjectStore>) -> Self {
+        Self { primary, mirror }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for MirroringObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        match self.primary.get_raw(bucket, key).await {
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                vlog::trace!("Key {key} not found in bucket {bucket}, falling back to mirror");
+                self.mirror.get_raw(bucket, key).await
+            }
+            result => result,
+        }
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        tokio::try_join!(
+            self.primary.put_raw(bucket, key, value.clone()),
+            self.mirror.put_raw(bucket, key, value)
+        )?;
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let (primary_result, mirror_result) = tokio::join!(
+            self.primary.remove_raw(bucket, key),
+            self.mirror.remove_raw(bucket, key)
+        );
+        // The blob may be missing in the mirror if it was written before mirroring was enabled.
+        match mirror_result {
+            Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => primary_result,
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn list_raw(
+        &self,
+        bucket: Bucket,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<Vec<String>, ObjectStoreError> {
+        let (mut keys, mut mirror_keys) = tokio::try_join!(
+            self.primary.list_raw(bucket, prefix, start_after),
+            self.mirror.list_raw(bucket, prefix, start_after)
+        )?;
+        // Keys following the last key of either page may be missing from the other page,
+        // so they are left for the next page.
+        if let (Some(last_key), Some(last_mirror_key)) = (keys.last(), mirror_keys.last()) {
+            let last_key = last_key.min(last_mirror_key).clone();
+            keys.retain(|key| *key <= last_key);
+            mirror_keys.retain(|key| *key <= last_key);
+        }
+        keys.extend(mirror_keys);
+        keys.sort_unstable();
+        keys.dedup();
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    fn mirroring_store() -> (Arc<MockStore>, Arc<MockStore>, MirroringObjectStore) {
+        let primary = Arc::new(MockStore::default());
+        let mirror = Arc::new(MockStore::default());
+        let store = MirroringObjectStore::new(
+            Box::new(Arc::clone(&primary)),
+            Box::new(Arc::clone(&mirror)),
+        );
+        (primary, mirror, store)
+    }
+
+    #[tokio::test]
+    async fn writes_are_mirrored() {
+        let (primary, mirror, store) = mirroring_store();
+        store
+            .put_raw(Bucket::ProverJobs, "test-key.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+        for inner in [&primary, &mirror] {
+            let blob = inner
+                .get_raw(Bucket::ProverJobs, "test-key.bin")
+                .await
+                .unwrap();
+            assert_eq!(blob, [1, 2, 3]);
+        }
+
+        store
+            .remove_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        for inner in [&primary, &mirror] {
+            let err = inner
+                .get_raw(Bucket::ProverJobs, "test-key.bin")
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_fall_back_to_mirror() {
+        let (primary, mirror, store) = mirroring_store();
+        mirror
+            .put_raw(Bucket::WitnessInput, "old.bin", vec![1])
+            .await
+            .unwrap();
+        primary
+            .put_raw(Bucket::WitnessInput, "new.bin", vec![2])
+            .await
+            .unwrap();
+
+        let blob = store
+            .get_raw(Bucket::WitnessInput, "old.bin")
+            .await
+            .unwrap();
+        assert_eq!(blob, [1]);
+        let blob = store
+            .get_raw(Bucket::WitnessInput, "new.bin")
+            .await
+            .unwrap();
+        assert_eq!(blob, [2]);
+        let err = store
+            .get_raw(Bucket::WitnessInput, "missing.bin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+
+        let keys = store
+            .list_raw(Bucket::WitnessInput, "", None)
+            .await
+            .unwrap();
+        assert_eq!(keys, ["new.bin", "old.bin"]);
+    }
+
+    #[tokio::test]
+    async fn listing_pages_are_merged() {
+        let (primary, mirror, store) = mirroring_store();
+        for key in ["a", "c", "d"] {
+            primary
+                .put_raw(Bucket::ProverJobs, key, vec![1])
+                .await
+                .unwrap();
+        }
+        for key in ["b", "c"] {
+            mirror
+                .put_raw(Bucket::ProverJobs, key, vec![1])
+                .await
+                .unwrap();
+        }
+
+        let keys = store.list_raw(Bucket::ProverJobs, "", None).await.unwrap();
+        assert_eq!(keys, ["a", "b", "c"]);
+        let keys = store
+            .list_raw(Bucket::ProverJobs, "", Some("c"))
+            .await
+            .unwrap();
+        assert_eq!(keys, ["d"]);
+        let keys = store
+            .list_raw(Bucket::ProverJobs, "", Some("d"))
+            .await
+            .unwrap();
+        assert!(keys.is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use tokio::sync::Mutex;
 
 use std::collections::HashMap;
 
-use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError, LIST_PAGE_SIZE};
 
 type BucketMap = HashMap<String, Vec<u8>>;
 
@@ -45,4 +45,24 @@ impl ObjectStore for MockStore {
         bucket_map.remove(key);
         Ok(())
     }
+
+    async fn list_raw(
+        &self,
+        bucket: Bucket,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<Vec<String>, ObjectStoreError> {
+        let lock = self.inner.lock().await;
+        let Some(bucket_map) = lock.get(&bucket) else {
+            return Ok(vec![]);
+        };
+        let mut keys: Vec<_> = bucket_map
+            .keys()
+            .filter(|key| key.starts_with(prefix) && Some(key.as_str()) > start_after)
+            .cloned()
+            .collect();
+        keys.sort_unstable();
+        keys.truncate(LIST_PAGE_SIZE);
+        Ok(keys)
+    }
 }
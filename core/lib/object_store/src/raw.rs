@@ -1,8 +1,17 @@
 use async_trait::async_trait;
+use tokio::sync::OnceCell;
 
-use std::{error, fmt, sync::Arc};
+use std::{error, fmt, str::FromStr, sync::Arc};
 
-use crate::{file::FileBackedObjectStore, gcs::GoogleCloudStorage, mock::MockStore};
+use crate::{
+    cache::CachingObjectStore,
+    encoding::EncodingObjectStore,
+    file::FileBackedObjectStore,
+    gcs::GoogleCloudStorage,
+    mirror::MirroringObjectStore,
+    mock::MockStore,
+    s3::{S3Credentials, S3Storage},
+};
 use zksync_config::configs::object_store::ObjectStoreMode;
 use zksync_config::ObjectStoreConfig;
 
@@ -23,6 +32,20 @@ pub enum Bucket {
 }
 
 impl Bucket {
+    /// All supported buckets.
+    pub const ALL: [Self; 10] = [
+        Self::ProverJobs,
+        Self::WitnessInput,
+        Self::LeafAggregationWitnessJobs,
+        Self::NodeAggregationWitnessJobs,
+        Self::SchedulerWitnessJobs,
+        Self::ProverJobsFri,
+        Self::LeafAggregationWitnessJobsFri,
+        Self::NodeAggregationWitnessJobsFri,
+        Self::SchedulerWitnessJobsFri,
+        Self::ProofsFri,
+    ];
+
     pub(crate) fn as_str(self) -> &'static str {
         match self {
             Self::ProverJobs => "prover_jobs",
@@ -45,6 +68,17 @@ impl fmt::Display for Bucket {
     }
 }
 
+impl FromStr for Bucket {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|bucket| bucket.as_str() == s)
+            .ok_or_else(|| format!("unknown bucket: `{s}`"))
+    }
+}
+
 /// Thread-safe boxed error.
 pub type BoxedError = Box<dyn error::Error + Send + Sync>;
 
@@ -79,6 +113,9 @@ impl error::Error for ObjectStoreError {
     }
 }
 
+/// Max number of keys returned by [`ObjectStore::list_raw()`] in a single page.
+pub const LIST_PAGE_SIZE: usize = 1_000;
+
 /// Functionality to fetch and store byte blobs from an object store (AWS S3, Google Cloud Storage,
 /// Azure Blobstore etc).
 ///
@@ -114,6 +151,22 @@ pub trait ObjectStore: fmt::Debug + Send + Sync {
     ///
     /// Returns an error if removal fails.
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError>;
+
+    /// Lists a page of keys of objects in the given bucket that start with `prefix` and
+    /// lexicographically follow `start_after` (if specified). Keys are returned in the lexicographic
+    /// order; the page size depends on the store, but is not greater than [`LIST_PAGE_SIZE`].
+    /// To list all keys, the method should be called with `start_after` set to the last key
+    /// of the previous page until an empty page is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if listing fails.
+    async fn list_raw(
+        &self,
+        bucket: Bucket,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<Vec<String>, ObjectStoreError>;
 }
 
 #[async_trait]
@@ -134,11 +187,27 @@ impl<T: ObjectStore + ?Sized> ObjectStore for Arc<T> {
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
         (**self).remove_raw(bucket, key).await
     }
+
+    async fn list_raw(
+        &self,
+        bucket: Bucket,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<Vec<String>, ObjectStoreError> {
+        (**self).list_raw(bucket, prefix, start_after).await
+    }
 }
 
 #[derive(Debug)]
 enum ObjectStoreOrigin {
-    Config(ObjectStoreConfig),
+    Config {
+        config: ObjectStoreConfig,
+        /// S3 credentials loaded when the factory is created; only set for the S3 mode.
+        s3_credentials: Option<S3Credentials>,
+        /// Store with the local cache, shared among all stores created by the factory so that
+        /// they use a single cache index for the cache directory.
+        cached_store: OnceCell<Arc<CachingObjectStore>>,
+    },
     Mock(Arc<MockStore>),
 }
 
@@ -154,10 +223,19 @@ impl ObjectStoreFactory {
     /// # Panics
     ///
     /// If the GCS-backed implementation is configured, this constructor will panic if called
-    /// outside the Tokio runtime.
+    /// outside the Tokio runtime. If the S3-backed implementation is configured, this constructor
+    /// will panic if S3 credentials are missing from the environment.
     pub fn new(config: ObjectStoreConfig) -> Self {
+        let s3_credentials = (config.mode == ObjectStoreMode::S3).then(|| {
+            S3Credentials::from_env()
+                .unwrap_or_else(|err| panic!("invalid S3 Object store credentials: {err}"))
+        });
         Self {
-            origin: ObjectStoreOrigin::Config(config),
+            origin: ObjectStoreOrigin::Config {
+                config,
+                s3_credentials,
+                cached_store: OnceCell::new(),
+            },
         }
     }
 
@@ -179,12 +257,66 @@ impl ObjectStoreFactory {
     /// Creates an [`ObjectStore`].
     pub async fn create_store(&self) -> Box<dyn ObjectStore> {
         match &self.origin {
-            ObjectStoreOrigin::Config(config) => Self::create_from_config(config).await,
+            ObjectStoreOrigin::Config {
+                config,
+                s3_credentials,
+                cached_store,
+            } => Self::create_from_config(config, s3_credentials.as_ref(), cached_store).await,
             ObjectStoreOrigin::Mock(store) => Box::new(Arc::clone(store)),
         }
     }
 
-    async fn create_from_config(config: &ObjectStoreConfig) -> Box<dyn ObjectStore> {
+    /// Creates a store according to `config`, wrapping the store for the configured mode
+    /// in the (optional) mirroring, caching and encoding layers, in this order. The caching layer
+    /// (together with the layers it wraps) is created once and stored in `cached_store`.
+    async fn create_from_config(
+        config: &ObjectStoreConfig,
+        s3_credentials: Option<&S3Credentials>,
+        cached_store: &OnceCell<Arc<CachingObjectStore>>,
+    ) -> Box<dyn ObjectStore> {
+        let store: Box<dyn ObjectStore> = if let Some(cache_path) = &config.local_cache_path {
+            let cached_store = cached_store
+                .get_or_init(|| async {
+                    let store = Self::create_mirrored_store(config, s3_credentials).await;
+                    vlog::trace!("Initialized local cache for Object store at {cache_path}");
+                    let capacity = config.local_cache_capacity_bytes();
+                    let store = CachingObjectStore::new(store, cache_path.clone(), capacity).await;
+                    Arc::new(store)
+                })
+                .await;
+            Box::new(Arc::clone(cached_store))
+        } else {
+            Self::create_mirrored_store(config, s3_credentials).await
+        };
+        Box::new(EncodingObjectStore::new(store, config.blob_encoding))
+    }
+
+    async fn create_mirrored_store(
+        config: &ObjectStoreConfig,
+        s3_credentials: Option<&S3Credentials>,
+    ) -> Box<dyn ObjectStore> {
+        let store = Self::create_base_store(config, s3_credentials).await;
+        let Some(mirror_bucket_base_url) = &config.mirror_bucket_base_url else {
+            return store;
+        };
+        assert_ne!(
+            config.mode,
+            ObjectStoreMode::FileBacked,
+            "mirroring is not supported for file-backed object stores"
+        );
+        vlog::trace!("Mirroring Object store writes to {mirror_bucket_base_url}");
+        let mirror_config = ObjectStoreConfig {
+            bucket_base_url: mirror_bucket_base_url.clone(),
+            ..config.clone()
+        };
+        let mirror = Self::create_base_store(&mirror_config, s3_credentials).await;
+        Box::new(MirroringObjectStore::new(store, mirror))
+    }
+
+    async fn create_base_store(
+        config: &ObjectStoreConfig,
+        s3_credentials: Option<&S3Credentials>,
+    ) -> Box<dyn ObjectStore> {
         let gcs_credential_file_path = match config.mode {
             ObjectStoreMode::GCSWithCredentialFile => Some(config.gcs_credential_file_path.clone()),
             _ => None,
@@ -215,6 +347,20 @@ impl ObjectStoreFactory {
                 let store = FileBackedObjectStore::new(config.file_backed_base_path.clone()).await;
                 Box::new(store)
             }
+            ObjectStoreMode::S3 => {
+                vlog::trace!("Initialized S3 Object store");
+                let store = S3Storage::new(
+                    config.s3_endpoint.as_deref(),
+                    config.s3_region(),
+                    config.s3_path_style,
+                    config.bucket_base_url.clone(),
+                    s3_credentials
+                        .expect("S3 credentials are loaded on factory creation")
+                        .clone(),
+                    config.max_retries,
+                );
+                Box::new(store)
+            }
         }
     }
 }
@@ -0,0 +1,67 @@
+//! Retry logic shared by remote [`ObjectStore`](crate::ObjectStore) implementations.
+
+use std::{future::Future, time::Duration};
+
+/// Retries the operation produced by `f` up to `max_retries` times with exponential backoff.
+pub(crate) async fn retry<T, E, Fut, F>(max_retries: u16, mut f: F) -> Result<T, E>
+where
+    Fut: Future<Output = Result<T, E>>,
+    F: FnMut() -> Fut,
+{
+    let mut retries = 1;
+    let mut backoff = 1;
+    loop {
+        match f().await {
+            Ok(result) => return Ok(result),
+            Err(err) => {
+                vlog::warn!("Failed object store request {retries}/{max_retries}, retrying.");
+                if retries > max_retries {
+                    return Err(err);
+                }
+                retries += 1;
+                tokio::time::sleep(Duration::from_secs(backoff)).await;
+                backoff *= 2;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU16, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_retry_success_immediate() {
+        let result = retry(2, || async { Ok::<_, ()>(42) }).await;
+        assert_eq!(result, Ok(42));
+    }
+
+    #[tokio::test]
+    async fn test_retry_failure_exhausted() {
+        let result = retry(2, || async { Err::<i32, _>(()) }).await;
+        assert_eq!(result, Err(()));
+    }
+
+    async fn retry_success_after_n_retries(n: u16) -> Result<u32, String> {
+        let retries = AtomicU16::new(0);
+        let result = retry(n, || async {
+            let retries = retries.fetch_add(1, Ordering::Relaxed);
+            if retries + 1 == n {
+                Ok(42)
+            } else {
+                Err(())
+            }
+        })
+        .await;
+
+        result.map_err(|_| "Retry failed".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_retry_success_after_retry() {
+        let result = retry(2, || retry_success_after_n_retries(2)).await;
+        assert_eq!(result, Ok(42));
+    }
+}
@@ -19,6 +19,7 @@ use zksync_types::{
 
 mod cache;
 mod in_memory;
+mod overlay;
 mod postgres;
 mod rocksdb;
 mod storage_view;
@@ -27,6 +28,7 @@ mod test_utils;
 
 pub use self::{
     in_memory::InMemoryStorage,
+    overlay::OverlayStorage,
     postgres::FactoryDepsCache,
     postgres::PostgresStorage,
     rocksdb::RocksdbStorage,
@@ -67,6 +69,10 @@ pub trait WriteStorage: ReadStorage {
     /// Returns the number of read / write ops for which the value was read from the underlying
     /// storage.
     fn missed_storage_invocations(&self) -> usize;
+
+    /// Reads the current value of the key without updating caches or metrics of this storage.
+    /// Intended for observers of the VM execution (e.g., tracers), so that they don't influence it.
+    fn peek_value(&mut self, key: &StorageKey) -> StorageValue;
 }
 
 /// Smart pointer to a dynamically typed [`WriteStorage`].
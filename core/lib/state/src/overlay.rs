@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ReadStorage;
+use zksync_types::{AccountTreeId, StorageKey, StorageValue, H256};
+
+/// Read-only storage that overrides some of the values of the underlying storage.
+///
+/// Used to execute transactions in the API sandbox against a hypothetical state
+/// (e.g., for state overrides in `eth_call`). Unlike writes to [`StorageView`](crate::StorageView),
+/// overrides are not considered modifications made by the executed transaction.
+#[derive(Debug)]
+pub struct OverlayStorage<S> {
+    storage_handle: S,
+    overridden_values: HashMap<StorageKey, StorageValue>,
+    overridden_factory_deps: HashMap<H256, Vec<u8>>,
+    cleared_accounts: HashSet<AccountTreeId>,
+}
+
+impl<S: ReadStorage> OverlayStorage<S> {
+    /// Creates an overlay without overrides on top of the underlying storage.
+    pub fn new(storage_handle: S) -> Self {
+        Self {
+            storage_handle,
+            overridden_values: HashMap::new(),
+            overridden_factory_deps: HashMap::new(),
+            cleared_accounts: HashSet::new(),
+        }
+    }
+
+    /// Overrides the value at the specified `key`.
+    pub fn set_value(&mut self, key: StorageKey, value: StorageValue) {
+        self.overridden_values.insert(key, value);
+    }
+
+    /// Clears the storage of the specified `account`: all its slots that are not explicitly
+    /// overridden afterwards will read as zero.
+    pub fn clear_account_storage(&mut self, account: AccountTreeId) {
+        self.overridden_values
+            .retain(|key, _| *key.account() != account);
+        self.cleared_accounts.insert(account);
+    }
+
+    /// Stores a factory dependency with the specified `hash` and `bytecode`.
+    pub fn store_factory_dep(&mut self, hash: H256, bytecode: Vec<u8>) {
+        self.overridden_factory_deps.insert(hash, bytecode);
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for OverlayStorage<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(value) = self.overridden_values.get(key) {
+            *value
+        } else if self.cleared_accounts.contains(key.account()) {
+            StorageValue::zero()
+        } else {
+            self.storage_handle.read_value(key)
+        }
+    }
+
+    /// Overrides do not influence this check; it is always delegated to the underlying storage.
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.storage_handle.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(bytecode) = self.overridden_factory_deps.get(&hash) {
+            return Some(bytecode.clone());
+        }
+        self.storage_handle.load_factory_dep(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryStorage;
+    use zksync_types::Address;
+
+    #[test]
+    fn overriding_values() {
+        let account = AccountTreeId::new(Address::repeat_byte(0xfe));
+        let other_account = AccountTreeId::new(Address::repeat_byte(0xef));
+        let key = StorageKey::new(account, H256::from_low_u64_be(1));
+        let other_key = StorageKey::new(account, H256::from_low_u64_be(2));
+        let other_account_key = StorageKey::new(other_account, H256::from_low_u64_be(1));
+
+        let mut raw_storage = InMemoryStorage::default();
+        raw_storage.set_value(key, H256::repeat_byte(1));
+        raw_storage.set_value(other_key, H256::repeat_byte(2));
+        raw_storage.set_value(other_account_key, H256::repeat_byte(3));
+        raw_storage.store_factory_dep(H256::repeat_byte(4), vec![4; 32]);
+
+        let mut storage = OverlayStorage::new(&raw_storage);
+        assert_eq!(storage.read_value(&key), H256::repeat_byte(1));
+
+        storage.set_value(key, H256::repeat_byte(0xff));
+        assert_eq!(storage.read_value(&key), H256::repeat_byte(0xff));
+        assert_eq!(storage.read_value(&other_key), H256::repeat_byte(2));
+        assert!(!storage.is_write_initial(&key));
+
+        storage.clear_account_storage(account);
+        assert_eq!(storage.read_value(&key), H256::zero());
+        assert_eq!(storage.read_value(&other_key), H256::zero());
+        assert_eq!(storage.read_value(&other_account_key), H256::repeat_byte(3));
+        storage.set_value(other_key, H256::repeat_byte(0xee));
+        assert_eq!(storage.read_value(&other_key), H256::repeat_byte(0xee));
+
+        storage.store_factory_dep(H256::repeat_byte(5), vec![5; 32]);
+        let factory_dep = storage.load_factory_dep(H256::repeat_byte(5));
+        assert_eq!(factory_dep, Some(vec![5; 32]));
+        let factory_dep = storage.load_factory_dep(H256::repeat_byte(4));
+        assert_eq!(factory_dep, Some(vec![4; 32]));
+    }
+}
@@ -178,6 +178,17 @@ impl<S: ReadStorage + fmt::Debug> WriteStorage for StorageView<S> {
     fn missed_storage_invocations(&self) -> usize {
         self.metrics.storage_invocations_missed
     }
+
+    fn peek_value(&mut self, key: &StorageKey) -> StorageValue {
+        let cached_value = self
+            .modified_storage_keys
+            .get(key)
+            .or_else(|| self.read_storage_keys.get(key));
+        match cached_value {
+            Some(&value) => value,
+            None => self.storage_handle.read_value(key),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -227,4 +238,27 @@ mod test {
         assert_eq!(metrics.get_value_storage_invocations, 3);
         assert_eq!(metrics.set_value_storage_invocations, 2);
     }
+
+    #[test]
+    fn peeking_values_does_not_affect_metrics() {
+        let account = AccountTreeId::new(Address::from([0xfe; 20]));
+        let key = StorageKey::new(account, H256::from_low_u64_be(61));
+        let value = H256::from_low_u64_be(73);
+        let mut raw_storage = InMemoryStorage::default();
+        raw_storage.set_value(key, value);
+        let mut storage_view = StorageView::new(&raw_storage);
+
+        assert_eq!(storage_view.peek_value(&key), value);
+        let new_key = StorageKey::new(account, H256::from_low_u64_be(62));
+        assert_eq!(storage_view.peek_value(&new_key), H256::zero());
+        let metrics = storage_view.metrics();
+        assert_eq!(metrics.storage_invocations_missed, 0);
+        assert_eq!(metrics.get_value_storage_invocations, 0);
+        assert_eq!(metrics.cache_size, 0);
+
+        let new_value = H256::from_low_u64_be(74);
+        storage_view.set_value(key, new_value);
+        assert_eq!(storage_view.peek_value(&key), new_value);
+        assert_eq!(storage_view.metrics().storage_invocations_missed, 1);
+    }
 }
@@ -1,17 +1,21 @@
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::aggregated_operations::AggregatedActionType;
 use crate::explorer_api::TransactionStatus;
 pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
-use crate::vm_trace::{Call, CallType};
+use crate::utils::{decompose_full_nonce, storage_key_for_eth_balance};
+use crate::vm_trace::{Call, CallType, MemoryInteraction, StorageAccessTrace, VmExecutionTrace};
 use crate::web3::types::{AccessList, Index, H2048};
-use crate::{Address, MiniblockNumber};
+use crate::{get_code_key, get_nonce_key, Address, L1BatchNumber, MiniblockNumber, StorageKey};
 use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
 pub use zksync_basic_types::web3::{
     self, ethabi,
     types::{Bytes, Work, H160, H256, H64, U256, U64},
 };
+use zksync_utils::h256_to_u256;
 
 pub mod en;
 
@@ -175,6 +179,36 @@ pub struct L2ToL1LogProof {
     pub root: H256,
 }
 
+/// Merkle proofs for storage slots of an account against the Merkle tree of a specific L1 batch.
+///
+/// This is not an EIP-1186 (`eth_getProof`) proof: zkSync keeps all accounts in a single sparse
+/// Merkle tree, so there is no per-account storage trie or account proof.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Proof {
+    /// Address of the account.
+    pub address: Address,
+    /// Root hash of the Merkle tree after the L1 batch; all proofs are verified against it.
+    pub root_hash: H256,
+    /// Proofs for each of the requested storage slots, in the order of the request.
+    pub storage_proof: Vec<StorageProof>,
+}
+
+/// A struct with the Merkle proof for a single storage slot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageProof {
+    /// The storage slot key.
+    pub key: H256,
+    /// The value of the slot; zero if the slot is missing from the tree.
+    pub value: H256,
+    /// 1-based index of the leaf in the tree; zero if the slot is missing from the tree.
+    pub index: u64,
+    /// The Merkle path for the leaf starting from the leaf level. Hashes for empty subtrees
+    /// at the bottom of the tree are omitted.
+    pub proof: Vec<H256>,
+}
+
 /// A struct with the two default bridge contracts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -523,6 +557,137 @@ pub struct TransactionDetails {
     pub eth_execute_tx_hash: Option<H256>,
 }
 
+/// Response of the `eth_feeHistory` method.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+    /// Lowest number block of the returned range.
+    pub oldest_block: U64,
+    /// Base fees per gas for each block in the range, plus the one for the block following the newest one.
+    pub base_fee_per_gas: Vec<U256>,
+    /// Ratios of gas used to gas limit for each block in the range.
+    pub gas_used_ratio: Vec<f64>,
+    /// Effective priority fees per gas at the requested percentiles for each block in the range.
+    /// Returned only if reward percentiles were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<U256>>>,
+    /// Base fees per blob gas (EIP-4844) for each block in the range, plus the one for the block following
+    /// the newest one. Returned by L1 nodes after the Cancun upgrade; always empty for zkSync blocks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub base_fee_per_blob_gas: Vec<U256>,
+}
+
+/// Overrides of the account state used in `eth_call` and `eth_estimateGas`, keyed by account address.
+pub type StateOverride = HashMap<Address, OverrideAccount>;
+
+/// Overrides of a single account state. `state` replaces the entire account storage, while `stateDiff`
+/// only overrides the specified slots; at most one of them may be set.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideAccount {
+    pub balance: Option<U256>,
+    pub nonce: Option<U256>,
+    pub code: Option<Bytes>,
+    pub state: Option<HashMap<H256, H256>>,
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+/// Execution status of a transaction simulated by `zks_simulateBundle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulatedTransactionStatus {
+    /// Transaction was executed successfully.
+    Success,
+    /// Transaction was executed, but reverted. Its fee is still charged.
+    Reverted,
+    /// Transaction was rejected by the bootloader (e.g., because of failed validation), so it could not
+    /// be included into a block. Transactions following it in the bundle are not executed.
+    Rejected,
+    /// Transaction was not executed because a preceding transaction in the bundle was rejected.
+    NotExecuted,
+}
+
+/// Result of simulating a single transaction from a bundle in `zks_simulateBundle`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedTransaction {
+    pub status: SimulatedTransactionStatus,
+    /// Gas spent by the transaction, computed as the difference between the gas limit the transaction
+    /// was executed with (the same as for `eth_call`) and the gas refunded by the bootloader.
+    /// Zero for rejected and not executed transactions.
+    pub gas_used: U256,
+    pub revert_reason: Option<String>,
+    /// Events emitted by the transaction. Since the transaction isn't included into a block,
+    /// block- and hash-related fields of the logs are not set.
+    pub logs: Vec<Log>,
+    /// Call trace of the transaction. Not set for rejected transactions.
+    pub trace: Option<DebugCall>,
+}
+
+/// Stage of processing of an L1 batch on L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum L1BatchStage {
+    Committed,
+    Proven,
+    Executed,
+}
+
+impl From<AggregatedActionType> for L1BatchStage {
+    fn from(action_type: AggregatedActionType) -> Self {
+        match action_type {
+            AggregatedActionType::CommitBlocks => Self::Committed,
+            AggregatedActionType::PublishProofBlocksOnchain => Self::Proven,
+            AggregatedActionType::ExecuteBlocks => Self::Executed,
+        }
+    }
+}
+
+/// Notification about a transaction moving to a new stage together with its L1 batch,
+/// sent to `txStatus` subscribers and webhooks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxStatusUpdate {
+    pub transaction_hash: H256,
+    pub initiator_address: Address,
+    pub l1_batch_number: L1BatchNumber,
+    pub status: L1BatchStage,
+    /// Hash of the confirmed L1 transaction that moved the batch to `status`.
+    pub eth_tx_hash: H256,
+}
+
+/// Status of an L1 batch reported to `l1BatchStatus` subscribers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum L1BatchStatus {
+    Sealed,
+    Committed,
+    Proven,
+    Executed,
+}
+
+impl From<L1BatchStage> for L1BatchStatus {
+    fn from(stage: L1BatchStage) -> Self {
+        match stage {
+            L1BatchStage::Committed => Self::Committed,
+            L1BatchStage::Proven => Self::Proven,
+            L1BatchStage::Executed => Self::Executed,
+        }
+    }
+}
+
+/// Notification about an L1 batch moving to a new status, sent to `l1BatchStatus` subscribers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchStatusUpdate {
+    pub l1_batch_number: L1BatchNumber,
+    pub status: L1BatchStatus,
+    pub timestamp: u64,
+    /// Hash of the confirmed L1 transaction that moved the batch to `status`.
+    /// Not set for sealed batches.
+    pub eth_tx_hash: Option<H256>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetLogsFilter {
     pub from_block: MiniblockNumber,
@@ -536,7 +701,21 @@ pub struct GetLogsFilter {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ResultDebugCall {
-    pub result: DebugCall,
+    pub result: DebugTrace,
+}
+
+/// Output of a `debug_trace*` method; its shape depends on the requested tracer.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum DebugTrace {
+    /// Output of `callTracer`.
+    Call(DebugCall),
+    /// Output of `4byteTracer`: number of calls per `{selector}-{calldata size}` pair.
+    FourByte(BTreeMap<String, usize>),
+    /// Output of `prestateTracer`.
+    Prestate(PrestateTrace),
+    /// Output of the opcode-level struct logger.
+    StructLogs(StructLogsTrace),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -561,6 +740,35 @@ pub struct DebugCall {
     pub calls: Vec<DebugCall>,
 }
 
+impl DebugCall {
+    /// Counts calls in this call tree by their function selector and calldata size, in the format
+    /// used by the `4byteTracer` in geth. Deployments and calls to system contracts are not counted.
+    pub fn four_byte_trace(&self) -> BTreeMap<String, usize> {
+        let mut trace = BTreeMap::new();
+        self.collect_four_byte_trace(&mut trace);
+        trace
+    }
+
+    fn collect_four_byte_trace(&self, trace: &mut BTreeMap<String, usize>) {
+        let is_counted = self.r#type == DebugCallType::Call
+            && self.input.0.len() >= 4
+            && !is_kernel_space_address(&self.to);
+        if is_counted {
+            let (selector, args) = self.input.0.split_at(4);
+            let key = format!("0x{}-{}", hex::encode(selector), args.len());
+            *trace.entry(key).or_default() += 1;
+        }
+        for call in &self.calls {
+            call.collect_four_byte_trace(trace);
+        }
+    }
+}
+
+/// Checks whether the address belongs to the kernel space (i.e., is below 2^16), where system contracts live.
+fn is_kernel_space_address(address: &Address) -> bool {
+    address.as_bytes()[..18].iter().all(|&byte| byte == 0)
+}
+
 impl From<Call> for DebugCall {
     fn from(value: Call) -> Self {
         let calls = value.calls.into_iter().map(DebugCall::from).collect();
@@ -585,21 +793,438 @@ impl From<Call> for DebugCall {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// State of an account as reported by the `prestateTracer`. Only the fields touched
+/// during the execution are present.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+pub struct PrestateAccount {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_hash: Option<H256>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// Output of the `prestateTracer`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+    /// Values of modified slots before and after the execution (returned if `diffMode` is set).
+    Diff {
+        pre: BTreeMap<Address, PrestateAccount>,
+        post: BTreeMap<Address, PrestateAccount>,
+    },
+    /// Values of all touched slots before the execution.
+    Prestate(BTreeMap<Address, PrestateAccount>),
+}
+
+/// Account field that a storage slot is attributed to.
+#[derive(Debug, Clone, Copy)]
+enum AccountSlot {
+    Balance(Address),
+    Nonce(Address),
+    CodeHash(Address),
+}
+
+impl PrestateTrace {
+    pub fn new(trace: &StorageAccessTrace, diff_mode: bool) -> Self {
+        let account_slots: HashMap<_, _> = trace
+            .touched_accounts
+            .iter()
+            .flat_map(|address| {
+                [
+                    (
+                        storage_key_for_eth_balance(address),
+                        AccountSlot::Balance(*address),
+                    ),
+                    (get_nonce_key(address), AccountSlot::Nonce(*address)),
+                    (get_code_key(address), AccountSlot::CodeHash(*address)),
+                ]
+            })
+            .collect();
+
+        if diff_mode {
+            let modified = || trace.accesses.iter().filter(|access| access.is_modified());
+            Self::Diff {
+                pre: Self::accounts(
+                    &account_slots,
+                    modified().map(|access| (&access.key, access.value_before)),
+                ),
+                post: Self::accounts(
+                    &account_slots,
+                    modified().map(|access| (&access.key, access.value_after)),
+                ),
+            }
+        } else {
+            let values = trace
+                .accesses
+                .iter()
+                .map(|access| (&access.key, access.value_before));
+            Self::Prestate(Self::accounts(&account_slots, values))
+        }
+    }
+
+    fn accounts<'a>(
+        account_slots: &HashMap<StorageKey, AccountSlot>,
+        values: impl Iterator<Item = (&'a StorageKey, H256)>,
+    ) -> BTreeMap<Address, PrestateAccount> {
+        let mut accounts = BTreeMap::<_, PrestateAccount>::new();
+        for (key, value) in values {
+            match account_slots.get(key) {
+                Some(AccountSlot::Balance(address)) => {
+                    accounts.entry(*address).or_default().balance = Some(h256_to_u256(value));
+                }
+                Some(AccountSlot::Nonce(address)) => {
+                    let (nonce, _) = decompose_full_nonce(h256_to_u256(value));
+                    accounts.entry(*address).or_default().nonce = Some(nonce);
+                }
+                Some(AccountSlot::CodeHash(address)) => {
+                    accounts.entry(*address).or_default().code_hash = Some(value);
+                }
+                None => {
+                    let account = accounts.entry(*key.address()).or_default();
+                    account.storage.insert(*key.key(), value);
+                }
+            }
+        }
+        accounts
+    }
+}
+
+/// Single opcode execution step reported by the struct logger.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: u16,
+    pub op: String,
+    pub gas: u32,
+    pub gas_cost: u32,
+    /// Call depth starting from 1 for the first traced frame.
+    pub depth: usize,
+    /// Values of the registers (zkEVM has no operand stack).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    /// Heap reads and writes performed by the opcode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<MemoryInteraction>>,
+    /// Storage slots of the current contract accessed by the opcode. Only set for storage opcodes;
+    /// unlike geth, slots accessed by the preceding opcodes are not repeated, so that the trace size
+    /// is linear in the number of steps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<H256, H256>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Output of the struct logger.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogsTrace {
+    pub gas: U256,
+    pub failed: bool,
+    pub return_value: Bytes,
+    pub struct_logs: Vec<StructLog>,
+}
+
+impl StructLogsTrace {
+    pub fn new(
+        trace: &VmExecutionTrace,
+        config: &StructLoggerConfig,
+        gas: U256,
+        failed: bool,
+        return_value: Bytes,
+    ) -> Self {
+        let base_depth = trace.steps.first().map_or(0, |step| step.depth);
+        let struct_logs = trace
+            .steps
+            .iter()
+            .map(|step| {
+                let storage = if config.disable_storage || step.storage_interactions.is_empty() {
+                    None
+                } else {
+                    let accessed_slots = step
+                        .storage_interactions
+                        .iter()
+                        .map(|interaction| (interaction.key, interaction.value));
+                    Some(accessed_slots.collect())
+                };
+
+                StructLog {
+                    pc: step.pc,
+                    op: step.opcode.clone(),
+                    gas: step.ergs_remaining,
+                    gas_cost: step.ergs_cost,
+                    depth: step.depth.saturating_sub(base_depth) + 1,
+                    stack: (!config.disable_stack).then(|| step.registers.clone()),
+                    memory: (!config.disable_memory).then(|| step.memory_interactions.clone()),
+                    storage,
+                    error: step.error.clone(),
+                }
+            })
+            .collect();
+
+        Self {
+            gas,
+            failed,
+            return_value,
+            struct_logs,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum SupportedTracers {
+    #[default]
+    #[serde(rename = "callTracer")]
     CallTracer,
+    #[serde(rename = "prestateTracer")]
+    PrestateTracer,
+    #[serde(rename = "4byteTracer")]
+    FourByteTracer,
+    /// Opcode-level logger; unlike in geth, it must be requested explicitly.
+    #[serde(rename = "structLogger")]
+    StructLogger,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CallTracerConfig {
+    /// Used by `callTracer`: only the top-level call is traced.
+    #[serde(default)]
     pub only_top_call: bool,
+    /// Used by `prestateTracer`: values both before and after the execution are returned,
+    /// only for modified slots.
+    #[serde(default)]
+    pub diff_mode: bool,
+}
+
+/// Options of the struct logger. Unlike the options of other tracers, they are specified
+/// at the top level of the tracer config.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerConfig {
+    #[serde(default)]
+    pub disable_stack: bool,
+    #[serde(default)]
+    pub disable_memory: bool,
+    #[serde(default)]
+    pub disable_storage: bool,
+    /// Maximum number of recorded steps; 0 means that only the server-side limit applies.
+    #[serde(default)]
+    pub limit: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TracerConfig {
+    #[serde(default)]
     pub tracer: SupportedTracers,
+    #[serde(default)]
     pub tracer_config: CallTracerConfig,
+    #[serde(flatten)]
+    pub struct_logger: StructLoggerConfig,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm_trace::{MemoryDirection, StorageAccess, StorageInteraction, VmExecutionStep};
+    use crate::AccountTreeId;
+    use zksync_utils::u256_to_h256;
+
+    fn debug_call(to: Address, input: Vec<u8>, calls: Vec<DebugCall>) -> DebugCall {
+        DebugCall {
+            r#type: DebugCallType::Call,
+            from: Address::repeat_byte(1),
+            to,
+            gas: U256::zero(),
+            gas_used: U256::zero(),
+            value: U256::zero(),
+            output: Bytes::default(),
+            input: Bytes(input),
+            error: None,
+            revert_reason: None,
+            calls,
+        }
+    }
+
+    #[test]
+    fn tracer_config_deserialization() {
+        let config: TracerConfig = serde_json::from_str(r#"{"tracer": "4byteTracer"}"#).unwrap();
+        assert_eq!(config.tracer, SupportedTracers::FourByteTracer);
+        assert!(!config.tracer_config.only_top_call);
+
+        let config: TracerConfig = serde_json::from_str(
+            r#"{"tracer": "prestateTracer", "tracerConfig": {"diffMode": true}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.tracer, SupportedTracers::PrestateTracer);
+        assert!(config.tracer_config.diff_mode);
+
+        let config: TracerConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.tracer, SupportedTracers::CallTracer);
+
+        let config: TracerConfig = serde_json::from_str(
+            r#"{"tracer": "structLogger", "disableStack": true, "limit": 100}"#,
+        )
+        .unwrap();
+        assert_eq!(config.tracer, SupportedTracers::StructLogger);
+        assert!(config.struct_logger.disable_stack);
+        assert!(!config.struct_logger.disable_memory);
+        assert_eq!(config.struct_logger.limit, 100);
+    }
+
+    #[test]
+    fn state_override_deserialization() {
+        let state_override: StateOverride = serde_json::from_str(
+            r#"{
+                "0x0000000000000000000000000000000000000042": {
+                    "balance": "0x100",
+                    "stateDiff": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": "0x00000000000000000000000000000000000000000000000000000000000000ff"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let account = &state_override[&Address::from_low_u64_be(0x42)];
+        assert_eq!(account.balance, Some(0x100.into()));
+        assert_eq!(account.nonce, None);
+        assert_eq!(account.state, None);
+        let state_diff = account.state_diff.as_ref().unwrap();
+        assert_eq!(
+            state_diff[&H256::from_low_u64_be(1)],
+            H256::from_low_u64_be(0xff)
+        );
+    }
+
+    #[test]
+    fn four_byte_trace() {
+        let contract = Address::repeat_byte(0x42);
+        let system_contract = Address::from_low_u64_be(0x8001);
+        let call = debug_call(
+            contract,
+            vec![0xaa, 0xbb, 0xcc, 0xdd, 0, 0],
+            vec![
+                debug_call(contract, vec![0xaa, 0xbb, 0xcc, 0xdd, 1, 1], vec![]),
+                debug_call(contract, vec![0x11, 0x22, 0x33, 0x44], vec![]),
+                debug_call(contract, vec![0x11], vec![]),
+                debug_call(system_contract, vec![0x11, 0x22, 0x33, 0x44], vec![]),
+            ],
+        );
+
+        let trace = call.four_byte_trace();
+        let expected_trace = BTreeMap::from([
+            ("0xaabbccdd-2".to_owned(), 2),
+            ("0x11223344-0".to_owned(), 1),
+        ]);
+        assert_eq!(trace, expected_trace);
+    }
+
+    #[test]
+    fn prestate_trace_from_storage_accesses() {
+        let account = Address::repeat_byte(0x42);
+        let slot = StorageKey::new(AccountTreeId::new(account), H256::repeat_byte(1));
+        let unchanged_slot = StorageKey::new(AccountTreeId::new(account), H256::repeat_byte(2));
+        let trace = StorageAccessTrace {
+            touched_accounts: vec![account],
+            accesses: vec![
+                StorageAccess {
+                    key: storage_key_for_eth_balance(&account),
+                    value_before: u256_to_h256(100.into()),
+                    value_after: u256_to_h256(90.into()),
+                },
+                StorageAccess {
+                    key: get_nonce_key(&account),
+                    value_before: u256_to_h256(3.into()),
+                    value_after: u256_to_h256(4.into()),
+                },
+                StorageAccess {
+                    key: slot,
+                    value_before: H256::zero(),
+                    value_after: H256::repeat_byte(0xff),
+                },
+                StorageAccess {
+                    key: unchanged_slot,
+                    value_before: H256::repeat_byte(3),
+                    value_after: H256::repeat_byte(3),
+                },
+            ],
+        };
+
+        let PrestateTrace::Prestate(prestate) = PrestateTrace::new(&trace, false) else {
+            panic!("Unexpected trace format");
+        };
+        let account_state = &prestate[&account];
+        assert_eq!(account_state.balance, Some(100.into()));
+        assert_eq!(account_state.nonce, Some(3.into()));
+        assert_eq!(account_state.code_hash, None);
+        assert_eq!(account_state.storage.len(), 2);
+
+        let PrestateTrace::Diff { pre, post } = PrestateTrace::new(&trace, true) else {
+            panic!("Unexpected trace format");
+        };
+        assert_eq!(
+            pre[&account].storage,
+            BTreeMap::from([(*slot.key(), H256::zero())])
+        );
+        assert_eq!(post[&account].balance, Some(90.into()));
+        assert_eq!(post[&account].nonce, Some(4.into()));
+        assert_eq!(
+            post[&account].storage,
+            BTreeMap::from([(*slot.key(), H256::repeat_byte(0xff))])
+        );
+    }
+
+    #[test]
+    fn struct_logs_from_execution_trace() {
+        let contract = Address::repeat_byte(0x42);
+        let step = |depth, storage_interactions| VmExecutionStep {
+            contract_address: contract,
+            memory_page_index: 0,
+            child_memory_index: 0,
+            pc: 1,
+            opcode: "Log(StorageRead)".to_owned(),
+            ergs_remaining: 1_000,
+            ergs_cost: 10,
+            depth,
+            set_flags: vec![],
+            registers: vec![U256::one(); 15],
+            register_interactions: HashMap::new(),
+            sp: Some(0),
+            memory_interactions: vec![],
+            storage_interactions,
+            error: None,
+        };
+        let interaction = |key, value| StorageInteraction {
+            key: H256::repeat_byte(key),
+            value: H256::repeat_byte(value),
+            direction: MemoryDirection::Read,
+        };
+        let trace = VmExecutionTrace {
+            steps: vec![
+                step(3, vec![interaction(1, 1)]),
+                step(4, vec![]),
+                step(3, vec![interaction(2, 2)]),
+            ],
+            contracts: Default::default(),
+        };
+
+        let config = StructLoggerConfig {
+            disable_stack: true,
+            ..StructLoggerConfig::default()
+        };
+        let logs = StructLogsTrace::new(&trace, &config, 100.into(), false, Bytes::default());
+        let depths: Vec<_> = logs.struct_logs.iter().map(|log| log.depth).collect();
+        assert_eq!(depths, [1, 2, 1]);
+        assert!(logs.struct_logs.iter().all(|log| log.stack.is_none()));
+        assert_eq!(logs.struct_logs[1].storage, None);
+        // Only the slots accessed by the step itself are reported.
+        let expected_storage = BTreeMap::from([(H256::repeat_byte(2), H256::repeat_byte(2))]);
+        assert_eq!(logs.struct_logs[2].storage, Some(expected_storage));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Address, U256};
+use crate::{Address, StorageKey, H256, U256};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{HashMap, HashSet};
 use std::fmt;
@@ -9,6 +9,30 @@ use zksync_config::constants::BOOTLOADER_ADDRESS;
 pub enum VmTrace {
     ExecutionTrace(VmExecutionTrace),
     CallTrace(Vec<Call>),
+    PrestateTrace(StorageAccessTrace),
+}
+
+/// Storage slots accessed during VM execution, together with their values before and after the execution.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct StorageAccessTrace {
+    /// Accounts that were called or accessed their storage during the execution, sorted by address.
+    /// Used to attribute balance slots of the L2 ETH token to the accounts they belong to.
+    pub touched_accounts: Vec<Address>,
+    /// Accessed storage slots sorted by key.
+    pub accesses: Vec<StorageAccess>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct StorageAccess {
+    pub key: StorageKey,
+    pub value_before: H256,
+    pub value_after: H256,
+}
+
+impl StorageAccess {
+    pub fn is_modified(&self) -> bool {
+        self.value_before != self.value_after
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
@@ -23,11 +47,21 @@ pub struct VmExecutionStep {
     pub memory_page_index: usize,
     pub child_memory_index: usize,
     pub pc: u16,
+    /// Executed opcode.
+    pub opcode: String,
+    /// Ergs remaining in the current frame before the opcode is executed.
+    pub ergs_remaining: u32,
+    /// Ergs spent on the opcode. Not tracked (i.e., 0) for opcodes switching frames.
+    pub ergs_cost: u32,
+    /// Depth of the callstack, including near call frames.
+    pub depth: usize,
     pub set_flags: Vec<String>,
     pub registers: Vec<U256>,
     pub register_interactions: HashMap<u8, MemoryDirection>,
     pub sp: Option<u16>,
     pub memory_interactions: Vec<MemoryInteraction>,
+    /// Storage slots of the current contract accessed by the opcode.
+    pub storage_interactions: Vec<StorageInteraction>,
     pub error: Option<String>,
 }
 
@@ -35,11 +69,18 @@ pub struct VmExecutionStep {
 pub struct MemoryInteraction {
     pub memory_type: String,
     pub page: usize,
-    pub address: u16,
+    pub address: u32,
     pub value: U256,
     pub direction: MemoryDirection,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct StorageInteraction {
+    pub key: H256,
+    pub value: H256,
+    pub direction: MemoryDirection,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub enum MemoryDirection {
     Read,
@@ -4,6 +4,8 @@ use zk_evm::vm_state::VmLocalState;
 mod bootloader;
 mod call;
 mod one_tx;
+mod prestate;
+mod struct_logger;
 mod transaction_result;
 mod utils;
 mod validation;
@@ -11,6 +13,8 @@ mod validation;
 pub use bootloader::BootloaderTracer;
 pub use call::CallTracer;
 pub use one_tx::OneTxTracer;
+pub use prestate::PrestateTracer;
+pub use struct_logger::{StructLogger, StructLoggerParams};
 pub use validation::{ValidationError, ValidationTracer, ValidationTracerParams};
 
 pub(crate) use transaction_result::TransactionResultTracer;
@@ -1,4 +1,4 @@
-use super::utils::{computational_gas_price, print_debug_if_needed};
+use super::utils::{computational_gas_price, print_debug_if_needed, read_pointer};
 use crate::{
     history_recorder::HistoryMode,
     memory::SimpleMemory,
@@ -9,19 +9,20 @@ use crate::{
     vm::get_vm_hook_params,
 };
 
-use crate::oracles::tracer::{CallTracer, StorageInvocationTracer};
+use crate::oracles::tracer::{CallTracer, PrestateTracer, StorageInvocationTracer, StructLogger};
 use zk_evm::{
     tracing::{
         AfterDecodingData, AfterExecutionData, BeforeExecutionData, Tracer, VmLocalStateData,
     },
     vm_state::VmLocalState,
+    zkevm_opcode_defs::FatPointer,
 };
-use zksync_types::vm_trace::Call;
+use zksync_types::{vm_trace::Call, U256};
 
 /// Allows any opcodes, but tells the VM to end the execution once the tx is over.
 // Internally depeds on Bootloader's VMHooks to get the notification once the transaction is finished.
 #[derive(Debug)]
-pub struct OneTxTracer<H: HistoryMode> {
+pub struct OneTxTracer<'a, H: HistoryMode> {
     tx_has_been_processed: bool,
 
     // Some(x) means that the bootloader has asked the operator
@@ -40,9 +41,16 @@ pub struct OneTxTracer<H: HistoryMode> {
 
     bootloader_tracer: BootloaderTracer<H>,
     call_tracer: Option<CallTracer<H>>,
+    pub(crate) prestate_tracer: Option<PrestateTracer<'a, H>>,
+    pub(crate) struct_logger: Option<StructLogger<'a, H>>,
+
+    // Whether to record the execution result reported by the bootloader.
+    record_execution_result: bool,
+    // Whether the transaction has succeeded and its return data (or the revert reason if it has failed).
+    pub(crate) execution_result: Option<(bool, Vec<u8>)>,
 }
 
-impl<H: HistoryMode> Tracer for OneTxTracer<H> {
+impl<H: HistoryMode> Tracer for OneTxTracer<'_, H> {
     const CALL_BEFORE_EXECUTION: bool = true;
     const CALL_AFTER_EXECUTION: bool = true;
     type SupportedMemory = SimpleMemory<H>;
@@ -80,11 +88,24 @@ impl<H: HistoryMode> Tracer for OneTxTracer<H> {
             }
             VmHook::NoValidationEntered => self.in_account_validation = false,
             VmHook::AccountValidationEntered => self.in_account_validation = true,
+            VmHook::ExecutionResult if self.record_execution_result => {
+                let vm_hook_params = get_vm_hook_params(memory);
+                let success = vm_hook_params[0] != U256::zero();
+                let returndata_ptr = FatPointer::from_u256(vm_hook_params[1]);
+                self.execution_result = Some((success, read_pointer(memory, returndata_ptr)));
+            }
             _ => {}
         }
 
         self.gas_spent_on_bytecodes_and_long_messages +=
             gas_spent_on_bytecodes_and_long_messages_this_opcode(&state, &data);
+
+        if let Some(prestate_tracer) = self.prestate_tracer.as_mut() {
+            prestate_tracer.before_execution(state, data, memory);
+        }
+        if let Some(struct_logger) = self.struct_logger.as_mut() {
+            struct_logger.before_execution(state, data, memory);
+        }
     }
 
     fn after_execution(
@@ -97,10 +118,16 @@ impl<H: HistoryMode> Tracer for OneTxTracer<H> {
         if let Some(call_tracer) = self.call_tracer.as_mut() {
             call_tracer.after_execution(state, data, memory);
         }
+        if let Some(prestate_tracer) = self.prestate_tracer.as_mut() {
+            prestate_tracer.after_execution(state, data, memory);
+        }
+        if let Some(struct_logger) = self.struct_logger.as_mut() {
+            struct_logger.after_execution(state, data, memory);
+        }
     }
 }
 
-impl<H: HistoryMode> ExecutionEndTracer<H> for OneTxTracer<H> {
+impl<H: HistoryMode> ExecutionEndTracer<H> for OneTxTracer<'_, H> {
     fn should_stop_execution(&self) -> bool {
         self.tx_has_been_processed
             || self.bootloader_tracer.should_stop_execution()
@@ -108,7 +135,7 @@ impl<H: HistoryMode> ExecutionEndTracer<H> for OneTxTracer<H> {
     }
 }
 
-impl<H: HistoryMode> PendingRefundTracer<H> for OneTxTracer<H> {
+impl<H: HistoryMode> PendingRefundTracer<H> for OneTxTracer<'_, H> {
     fn requested_refund(&self) -> Option<u32> {
         self.pending_operator_refund
     }
@@ -118,15 +145,15 @@ impl<H: HistoryMode> PendingRefundTracer<H> for OneTxTracer<H> {
     }
 }
 
-impl<H: HistoryMode> PubdataSpentTracer<H> for OneTxTracer<H> {
+impl<H: HistoryMode> PubdataSpentTracer<H> for OneTxTracer<'_, H> {
     fn gas_spent_on_pubdata(&self, vm_local_state: &VmLocalState) -> u32 {
         self.gas_spent_on_bytecodes_and_long_messages + vm_local_state.spent_pubdata_counter
     }
 }
 
-impl<H: HistoryMode> StorageInvocationTracer<H> for OneTxTracer<H> {}
+impl<H: HistoryMode> StorageInvocationTracer<H> for OneTxTracer<'_, H> {}
 
-impl<H: HistoryMode> OneTxTracer<H> {
+impl<H: HistoryMode> OneTxTracer<'_, H> {
     pub fn new(computational_gas_limit: u32, with_call_tracer: bool) -> Self {
         let call_tracer = if with_call_tracer {
             Some(CallTracer::new())
@@ -143,9 +170,19 @@ impl<H: HistoryMode> OneTxTracer<H> {
             in_account_validation: false,
             bootloader_tracer: BootloaderTracer::default(),
             call_tracer,
+            prestate_tracer: None,
+            struct_logger: None,
+            record_execution_result: false,
+            execution_result: None,
         }
     }
 
+    /// Makes the tracer record the execution result of the transaction reported by the bootloader.
+    pub(crate) fn with_execution_result(mut self) -> Self {
+        self.record_execution_result = true;
+        self
+    }
+
     pub fn is_bootloader_out_of_gas(&self) -> bool {
         self.bootloader_tracer.is_bootloader_out_of_gas()
     }
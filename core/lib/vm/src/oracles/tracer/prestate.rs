@@ -0,0 +1,141 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::fmt;
+use std::marker::PhantomData;
+
+use zk_evm::tracing::{
+    AfterDecodingData, AfterExecutionData, BeforeExecutionData, Tracer, VmLocalStateData,
+};
+use zk_evm::zkevm_opcode_defs::{LogOpcode, Opcode};
+use zksync_state::StoragePtr;
+use zksync_types::vm_trace::{StorageAccess, StorageAccessTrace};
+use zksync_types::{
+    get_code_key, get_nonce_key, storage_key_for_eth_balance, AccountTreeId, Address, StorageKey,
+    H256,
+};
+use zksync_utils::u256_to_h256;
+
+use crate::history_recorder::HistoryMode;
+use crate::memory::SimpleMemory;
+
+/// Tracer that records storage slots touched during the execution together with their values
+/// before the execution. Values after the execution are read once the execution is finished.
+/// Values are peeked from the storage, so that tracing doesn't affect its caches or metrics.
+///
+/// Besides the slots accessed by the executed contracts, the tracer records the balance, nonce
+/// and code hash slots of every called account, so that they are present in the trace even if
+/// the execution didn't access them.
+pub struct PrestateTracer<'a, H: HistoryMode> {
+    storage: StoragePtr<'a>,
+    initial_values: HashMap<StorageKey, H256>,
+    touched_accounts: HashSet<Address>,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: HistoryMode> fmt::Debug for PrestateTracer<'_, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("PrestateTracer")
+            .field("initial_values", &self.initial_values)
+            .field("touched_accounts", &self.touched_accounts)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, H: HistoryMode> PrestateTracer<'a, H> {
+    pub fn new(storage: StoragePtr<'a>) -> Self {
+        Self {
+            storage,
+            initial_values: HashMap::new(),
+            touched_accounts: HashSet::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn record_initial_value(&mut self, key: StorageKey) {
+        if let Entry::Vacant(entry) = self.initial_values.entry(key) {
+            let value = self.storage.borrow_mut().peek_value(&key);
+            entry.insert(value);
+        }
+    }
+
+    fn touch_account(&mut self, address: Address) {
+        if self.touched_accounts.insert(address) {
+            self.record_initial_value(storage_key_for_eth_balance(&address));
+            self.record_initial_value(get_nonce_key(&address));
+            self.record_initial_value(get_code_key(&address));
+        }
+    }
+
+    /// Finishes tracing and reads the current values of all touched storage slots.
+    pub fn into_trace(self) -> StorageAccessTrace {
+        let mut storage = self.storage.borrow_mut();
+        let mut accesses: Vec<_> = self
+            .initial_values
+            .into_iter()
+            .map(|(key, value_before)| StorageAccess {
+                key,
+                value_before,
+                value_after: storage.peek_value(&key),
+            })
+            .collect();
+        accesses.sort_unstable_by_key(|access| access.key);
+
+        let mut touched_accounts: Vec<_> = self.touched_accounts.into_iter().collect();
+        touched_accounts.sort_unstable();
+        StorageAccessTrace {
+            touched_accounts,
+            accesses,
+        }
+    }
+}
+
+impl<H: HistoryMode> Tracer for PrestateTracer<'_, H> {
+    const CALL_BEFORE_EXECUTION: bool = true;
+    const CALL_AFTER_EXECUTION: bool = true;
+    type SupportedMemory = SimpleMemory<H>;
+
+    fn before_decoding(&mut self, _state: VmLocalStateData<'_>, _memory: &Self::SupportedMemory) {}
+
+    fn after_decoding(
+        &mut self,
+        _state: VmLocalStateData<'_>,
+        _data: AfterDecodingData,
+        _memory: &Self::SupportedMemory,
+    ) {
+    }
+
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        _memory: &Self::SupportedMemory,
+    ) {
+        // Storage writes are applied immediately, so the initial value must be read
+        // before the opcode is executed.
+        if let Opcode::Log(LogOpcode::StorageRead | LogOpcode::StorageWrite) =
+            data.opcode.variant.opcode
+        {
+            let this_address = state.vm_local_state.callstack.current.this_address;
+            let key = StorageKey::new(
+                AccountTreeId::new(this_address),
+                u256_to_h256(data.src0_value.value),
+            );
+            self.touch_account(this_address);
+            self.record_initial_value(key);
+        }
+    }
+
+    fn after_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: AfterExecutionData,
+        _memory: &Self::SupportedMemory,
+    ) {
+        if let Opcode::FarCall(_) = data.opcode.variant.opcode {
+            let current = &state.vm_local_state.callstack.current;
+            let (this_address, msg_sender) = (current.this_address, current.msg_sender);
+            self.touch_account(msg_sender);
+            self.touch_account(this_address);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::marker::PhantomData;
+
+use zk_evm::tracing::{
+    AfterDecodingData, AfterExecutionData, BeforeExecutionData, Tracer, VmLocalStateData,
+};
+use zk_evm::zkevm_opcode_defs::{FatPointer, LogOpcode, Opcode, RetOpcode, UMAOpcode};
+use zksync_config::constants::BOOTLOADER_ADDRESS;
+use zksync_state::StoragePtr;
+use zksync_types::vm_trace::{
+    MemoryDirection, MemoryInteraction, StorageInteraction, VmExecutionStep, VmExecutionTrace,
+};
+use zksync_types::{AccountTreeId, Address, StorageKey, U256};
+use zksync_utils::u256_to_h256;
+
+use crate::history_recorder::HistoryMode;
+use crate::memory::SimpleMemory;
+use crate::utils::{aux_heap_page_from_base, heap_page_from_base};
+
+/// Parameters of [`StructLogger`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructLoggerParams {
+    /// Whether to record register values for each step.
+    pub capture_registers: bool,
+    /// Whether to record heap reads and writes.
+    pub capture_memory: bool,
+    /// Whether to record storage reads and writes.
+    pub capture_storage: bool,
+    /// Maximum number of recorded steps. Once it is reached, the execution continues,
+    /// but further steps are not recorded.
+    pub step_limit: usize,
+}
+
+/// Tracer recording every opcode executed outside of the bootloader, similar to the default
+/// struct logger in geth.
+pub struct StructLogger<'a, H: HistoryMode> {
+    storage: StoragePtr<'a>,
+    params: StructLoggerParams,
+    steps: Vec<VmExecutionStep>,
+    contracts: HashSet<Address>,
+    /// Whether the last recorded step still waits for the post-execution data.
+    has_pending_step: bool,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: HistoryMode> fmt::Debug for StructLogger<'_, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("StructLogger")
+            .field("params", &self.params)
+            .field("steps", &self.steps.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, H: HistoryMode> StructLogger<'a, H> {
+    pub fn new(storage: StoragePtr<'a>, params: StructLoggerParams) -> Self {
+        Self {
+            storage,
+            params,
+            steps: vec![],
+            contracts: HashSet::new(),
+            has_pending_step: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn into_trace(self) -> VmExecutionTrace {
+        VmExecutionTrace {
+            steps: self.steps,
+            contracts: self.contracts,
+        }
+    }
+
+    fn memory_interaction(
+        state: &VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+        memory: &SimpleMemory<H>,
+    ) -> Option<MemoryInteraction> {
+        let uma_opcode = match data.opcode.variant.opcode {
+            Opcode::UMA(
+                uma_opcode @ (UMAOpcode::HeapRead
+                | UMAOpcode::HeapWrite
+                | UMAOpcode::AuxHeapRead
+                | UMAOpcode::AuxHeapWrite),
+            ) => uma_opcode,
+            _ => return None,
+        };
+
+        let base_page = state.vm_local_state.callstack.current.base_memory_page;
+        let (memory_type, page) = match uma_opcode {
+            UMAOpcode::HeapRead | UMAOpcode::HeapWrite => ("heap", heap_page_from_base(base_page)),
+            _ => ("aux_heap", aux_heap_page_from_base(base_page)),
+        };
+        let address = FatPointer::from_u256(data.src0_value.value).offset;
+        let (direction, value) = match uma_opcode {
+            UMAOpcode::HeapWrite | UMAOpcode::AuxHeapWrite => {
+                (MemoryDirection::Write, data.src1_value.value)
+            }
+            _ => {
+                let bytes = memory.read_unaligned_bytes(page.0 as usize, address as usize, 32);
+                (MemoryDirection::Read, U256::from_big_endian(&bytes))
+            }
+        };
+
+        Some(MemoryInteraction {
+            memory_type: memory_type.to_owned(),
+            page: page.0 as usize,
+            address,
+            value,
+            direction,
+        })
+    }
+
+    fn storage_interaction(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+    ) -> Option<StorageInteraction> {
+        let direction = match data.opcode.variant.opcode {
+            Opcode::Log(LogOpcode::StorageRead) => MemoryDirection::Read,
+            Opcode::Log(LogOpcode::StorageWrite) => MemoryDirection::Write,
+            _ => return None,
+        };
+        let this_address = state.vm_local_state.callstack.current.this_address;
+        let key = u256_to_h256(data.src0_value.value);
+        let value = match direction {
+            MemoryDirection::Read => {
+                let storage_key = StorageKey::new(AccountTreeId::new(this_address), key);
+                self.storage.borrow_mut().peek_value(&storage_key)
+            }
+            MemoryDirection::Write => u256_to_h256(data.src1_value.value),
+        };
+        Some(StorageInteraction {
+            key,
+            value,
+            direction,
+        })
+    }
+}
+
+impl<H: HistoryMode> Tracer for StructLogger<'_, H> {
+    const CALL_BEFORE_EXECUTION: bool = true;
+    const CALL_AFTER_EXECUTION: bool = true;
+    type SupportedMemory = SimpleMemory<H>;
+
+    fn before_decoding(&mut self, _state: VmLocalStateData<'_>, _memory: &Self::SupportedMemory) {}
+
+    fn after_decoding(
+        &mut self,
+        _state: VmLocalStateData<'_>,
+        _data: AfterDecodingData,
+        _memory: &Self::SupportedMemory,
+    ) {
+    }
+
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        memory: &Self::SupportedMemory,
+    ) {
+        self.has_pending_step = false;
+        let current = &state.vm_local_state.callstack.current;
+        if current.this_address == BOOTLOADER_ADDRESS || self.steps.len() >= self.params.step_limit
+        {
+            return;
+        }
+
+        let registers = if self.params.capture_registers {
+            state
+                .vm_local_state
+                .registers
+                .iter()
+                .map(|register| register.value)
+                .collect()
+        } else {
+            vec![]
+        };
+        let memory_interactions = if self.params.capture_memory {
+            Self::memory_interaction(&state, &data, memory)
+                .into_iter()
+                .collect()
+        } else {
+            vec![]
+        };
+        let storage_interactions = if self.params.capture_storage {
+            self.storage_interaction(&state, &data)
+                .into_iter()
+                .collect()
+        } else {
+            vec![]
+        };
+        let error = match data.opcode.variant.opcode {
+            Opcode::Ret(RetOpcode::Revert) => Some("execution reverted".to_owned()),
+            Opcode::Ret(RetOpcode::Panic) => Some("panic".to_owned()),
+            _ => None,
+        };
+
+        self.contracts.insert(current.this_address);
+        self.steps.push(VmExecutionStep {
+            contract_address: current.this_address,
+            memory_page_index: current.base_memory_page.0 as usize,
+            child_memory_index: 0,
+            pc: current.pc,
+            opcode: format!("{:?}", data.opcode.variant.opcode),
+            ergs_remaining: current.ergs_remaining,
+            ergs_cost: 0,
+            depth: state.vm_local_state.callstack.inner.len(),
+            set_flags: vec![],
+            registers,
+            register_interactions: Default::default(),
+            sp: Some(current.sp),
+            memory_interactions,
+            storage_interactions,
+            error,
+        });
+        self.has_pending_step = true;
+    }
+
+    fn after_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        _data: AfterExecutionData,
+        _memory: &Self::SupportedMemory,
+    ) {
+        if !self.has_pending_step {
+            return;
+        }
+        self.has_pending_step = false;
+
+        let step = self.steps.last_mut().unwrap();
+        // The cost can only be determined if the opcode didn't switch frames.
+        if state.vm_local_state.callstack.inner.len() == step.depth {
+            let ergs_remaining = state.vm_local_state.callstack.current.ergs_remaining;
+            step.ergs_cost = step.ergs_remaining.saturating_sub(ergs_remaining);
+        }
+    }
+}
@@ -9,8 +9,8 @@ use zksync_types::{vm_trace, U256};
 
 use crate::memory::SimpleMemory;
 use crate::oracles::tracer::{
-    CallTracer, ExecutionEndTracer, PendingRefundTracer, PubdataSpentTracer,
-    StorageInvocationTracer,
+    CallTracer, ExecutionEndTracer, PendingRefundTracer, PrestateTracer, PubdataSpentTracer,
+    StorageInvocationTracer, StructLogger,
 };
 use crate::vm::get_vm_hook_params;
 use crate::{
@@ -22,15 +22,17 @@ use crate::{
 };
 
 #[derive(Debug)]
-pub(crate) struct TransactionResultTracer<H: HistoryMode> {
+pub(crate) struct TransactionResultTracer<'a, H: HistoryMode> {
     pub(crate) revert_reason: Option<Vec<u8>>,
     gas_spent_on_bytecodes_and_long_messages: u32,
     pub(crate) call_tracer: Option<CallTracer<H>>,
+    pub(crate) prestate_tracer: Option<PrestateTracer<'a, H>>,
+    pub(crate) struct_logger: Option<StructLogger<'a, H>>,
     missed_storage_invocation_limit: usize,
     missed_storage_invocation: usize,
 }
 
-impl<H: HistoryMode> TransactionResultTracer<H> {
+impl<H: HistoryMode> TransactionResultTracer<'_, H> {
     pub(crate) fn new(missed_storage_invocation_limit: usize, with_call_tracer: bool) -> Self {
         let call_tracer = if with_call_tracer {
             Some(CallTracer::new())
@@ -43,6 +45,8 @@ impl<H: HistoryMode> TransactionResultTracer<H> {
             gas_spent_on_bytecodes_and_long_messages: 0,
             missed_storage_invocation: 0,
             call_tracer,
+            prestate_tracer: None,
+            struct_logger: None,
         }
     }
     pub fn call_trace(&mut self) -> Option<Vec<vm_trace::Call>> {
@@ -50,9 +54,21 @@ impl<H: HistoryMode> TransactionResultTracer<H> {
             .as_mut()
             .map(|call_tracer| call_tracer.extract_calls())
     }
+
+    pub fn prestate_trace(&mut self) -> Option<vm_trace::StorageAccessTrace> {
+        self.prestate_tracer
+            .take()
+            .map(|prestate_tracer| prestate_tracer.into_trace())
+    }
+
+    pub fn execution_trace(&mut self) -> Option<vm_trace::VmExecutionTrace> {
+        self.struct_logger
+            .take()
+            .map(|struct_logger| struct_logger.into_trace())
+    }
 }
 
-impl<H: HistoryMode> Tracer for TransactionResultTracer<H> {
+impl<H: HistoryMode> Tracer for TransactionResultTracer<'_, H> {
     const CALL_BEFORE_EXECUTION: bool = true;
     const CALL_AFTER_EXECUTION: bool = true;
     type SupportedMemory = SimpleMemory<H>;
@@ -90,6 +106,13 @@ impl<H: HistoryMode> Tracer for TransactionResultTracer<H> {
 
         self.gas_spent_on_bytecodes_and_long_messages +=
             gas_spent_on_bytecodes_and_long_messages_this_opcode(&state, &data);
+
+        if let Some(prestate_tracer) = self.prestate_tracer.as_mut() {
+            prestate_tracer.before_execution(state, data, memory);
+        }
+        if let Some(struct_logger) = self.struct_logger.as_mut() {
+            struct_logger.before_execution(state, data, memory);
+        }
     }
 
     fn after_execution(
@@ -101,25 +124,31 @@ impl<H: HistoryMode> Tracer for TransactionResultTracer<H> {
         if let Some(call_tracer) = self.call_tracer.as_mut() {
             call_tracer.after_execution(state, data, memory);
         }
+        if let Some(prestate_tracer) = self.prestate_tracer.as_mut() {
+            prestate_tracer.after_execution(state, data, memory);
+        }
+        if let Some(struct_logger) = self.struct_logger.as_mut() {
+            struct_logger.after_execution(state, data, memory);
+        }
     }
 }
 
-impl<H: HistoryMode> ExecutionEndTracer<H> for TransactionResultTracer<H> {
+impl<H: HistoryMode> ExecutionEndTracer<H> for TransactionResultTracer<'_, H> {
     // If we reach the limit of memory invocations, we stop the execution and return the error to user
     fn should_stop_execution(&self) -> bool {
         self.is_limit_reached()
     }
 }
 
-impl<H: HistoryMode> PendingRefundTracer<H> for TransactionResultTracer<H> {}
+impl<H: HistoryMode> PendingRefundTracer<H> for TransactionResultTracer<'_, H> {}
 
-impl<H: HistoryMode> PubdataSpentTracer<H> for TransactionResultTracer<H> {
+impl<H: HistoryMode> PubdataSpentTracer<H> for TransactionResultTracer<'_, H> {
     fn gas_spent_on_pubdata(&self, vm_local_state: &VmLocalState) -> u32 {
         self.gas_spent_on_bytecodes_and_long_messages + vm_local_state.spent_pubdata_counter
     }
 }
 
-impl<H: HistoryMode> StorageInvocationTracer<H> for TransactionResultTracer<H> {
+impl<H: HistoryMode> StorageInvocationTracer<H> for TransactionResultTracer<'_, H> {
     fn set_missed_storage_invocations(&mut self, missed_storage_invocation: usize) {
         self.missed_storage_invocation = missed_storage_invocation;
     }
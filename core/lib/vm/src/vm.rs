@@ -23,9 +23,9 @@ use crate::oracles::decommitter::DecommitterOracle;
 use crate::oracles::precompile::PrecompilesProcessorWithHistory;
 use crate::oracles::storage::StorageOracle;
 use crate::oracles::tracer::{
-    BootloaderTracer, ExecutionEndTracer, OneTxTracer, PendingRefundTracer, PubdataSpentTracer,
-    StorageInvocationTracer, TransactionResultTracer, ValidationError, ValidationTracer,
-    ValidationTracerParams,
+    BootloaderTracer, ExecutionEndTracer, OneTxTracer, PendingRefundTracer, PrestateTracer,
+    PubdataSpentTracer, StorageInvocationTracer, StructLogger, StructLoggerParams,
+    TransactionResultTracer, ValidationError, ValidationTracer, ValidationTracerParams,
 };
 use crate::oracles::OracleWithHistory;
 use crate::utils::{
@@ -153,6 +153,19 @@ pub enum VmExecutionStopReason {
     TracerRequestedStop,
 }
 
+/// Tracer used in [`VmInstance::execute_next_tx_with_tracer()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxTracer {
+    /// No tracing: the execution result has an empty trace.
+    None,
+    /// Call tracer: the call tree is returned as `VmTrace::CallTrace`.
+    Call,
+    /// Prestate tracer: touched storage slots are returned as `VmTrace::PrestateTrace`.
+    Prestate,
+    /// Struct logger: executed opcodes are returned as `VmTrace::ExecutionTrace`.
+    StructLogger(StructLoggerParams),
+}
+
 use crate::utils::VmExecutionResult as NewVmExecutionResult;
 
 fn vm_may_have_ended_inner<H: HistoryMode>(vm: &ZkSyncVmState<H>) -> Option<NewVmExecutionResult> {
@@ -192,6 +205,32 @@ fn vm_may_have_ended_inner<H: HistoryMode>(vm: &ZkSyncVmState<H>) -> Option<NewV
 //
 // `gas_before` argument is used to calculate the amount of gas spent by transaction.
 // It is required because the same VM instance is continuously used to apply several transactions.
+/// Parses the revert reason of a failed transaction from the data reported by the bootloader.
+fn parse_tx_revert_reason(reason: Option<Vec<u8>>) -> VmRevertReasonParsingResult {
+    reason
+        .map(|reason| {
+            let vm_revert_reason =
+                VmRevertReason::try_from(reason.as_slice()).unwrap_or_else(|_| {
+                    VmRevertReason::Unknown {
+                        function_selector: vec![],
+                        data: reason.clone(),
+                    }
+                });
+
+            VmRevertReasonParsingResult {
+                revert_reason: TxRevertReason::TxReverted(vm_revert_reason),
+                original_data: reason,
+            }
+        })
+        .unwrap_or_else(|| VmRevertReasonParsingResult {
+            revert_reason: TxRevertReason::TxReverted(VmRevertReason::General {
+                msg: "Transaction reverted with empty reason. Possibly out of gas".to_string(),
+                data: vec![],
+            }),
+            original_data: vec![],
+        })
+}
+
 fn vm_may_have_ended<H: HistoryMode>(
     vm: &VmInstance<H>,
     gas_before: u32,
@@ -586,10 +625,95 @@ impl<H: HistoryMode> VmInstance<'_, H> {
         validation_computational_gas_limit: u32,
         with_call_tracer: bool,
     ) -> Result<VmTxExecutionResult, TxRevertReason> {
-        let tx_index = self.bootloader_state.next_unexecuted_tx() as u32;
-
         let mut tx_tracer: OneTxTracer<H> =
             OneTxTracer::new(validation_computational_gas_limit, with_call_tracer);
+        self.execute_next_tx_with_one_tx_tracer(&mut tx_tracer, validation_computational_gas_limit)
+    }
+
+    /// Same as [`Self::execute_next_tx()`], but traces the transaction with the specified tracer
+    /// and returns the result in the same form as [`Self::execute_till_block_end()`].
+    ///
+    /// Unlike for the block execution, all values in the returned result (`gas_used`, events, logs etc.)
+    /// relate to the executed transaction only, and `return_data` is the data returned by the transaction.
+    /// A reverted transaction is not an error; its revert reason is returned in the result.
+    pub fn execute_next_tx_with_tracer(
+        &mut self,
+        validation_computational_gas_limit: u32,
+        tracer: TxTracer,
+    ) -> Result<VmExecutionResult, TxRevertReason> {
+        let mut tx_tracer: OneTxTracer<H> =
+            OneTxTracer::new(validation_computational_gas_limit, tracer == TxTracer::Call)
+                .with_execution_result();
+        match tracer {
+            TxTracer::None | TxTracer::Call => {}
+            TxTracer::Prestate => {
+                tx_tracer.prestate_tracer = Some(PrestateTracer::new(
+                    self.state.storage.storage.inner().get_ptr(),
+                ));
+            }
+            TxTracer::StructLogger(params) => {
+                tx_tracer.struct_logger = Some(StructLogger::new(
+                    self.state.storage.storage.inner().get_ptr(),
+                    params,
+                ));
+            }
+        }
+
+        let gas_remaining_before = self.gas_remaining();
+        let tx_result = self.execute_next_tx_with_one_tx_tracer(
+            &mut tx_tracer,
+            validation_computational_gas_limit,
+        )?;
+        let gas_used = gas_remaining_before
+            .checked_sub(self.gas_remaining())
+            .expect("underflow");
+
+        let (return_data, revert_reason) = match tx_result.status {
+            TxExecutionStatus::Success => {
+                let (_, mut output) = tx_tracer.execution_result.take().unwrap_or_default();
+                while output.len() % 32 != 0 {
+                    output.push(0);
+                }
+                (bytes_to_be_words(output), None)
+            }
+            TxExecutionStatus::Failure => {
+                let output = tx_tracer.execution_result.take().map(|(_, output)| output);
+                (vec![], Some(parse_tx_revert_reason(output)))
+            }
+        };
+        let trace = if let Some(prestate_tracer) = tx_tracer.prestate_tracer.take() {
+            VmTrace::PrestateTrace(prestate_tracer.into_trace())
+        } else if let Some(struct_logger) = tx_tracer.struct_logger.take() {
+            VmTrace::ExecutionTrace(struct_logger.into_trace())
+        } else if tracer == TxTracer::Call {
+            VmTrace::CallTrace(tx_result.call_traces)
+        } else {
+            VmTrace::ExecutionTrace(VmExecutionTrace::default())
+        };
+
+        let logs = tx_result.result.logs;
+        Ok(VmExecutionResult {
+            events: logs.events,
+            storage_log_queries: logs.storage_logs,
+            used_contract_hashes: self.get_used_contracts(),
+            l2_to_l1_logs: logs.l2_to_l1_logs,
+            return_data,
+            gas_used,
+            computational_gas_used: tx_result.result.computational_gas_used,
+            contracts_used: tx_result.result.contracts_used,
+            revert_reason,
+            trace,
+            total_log_queries: logs.total_log_queries_count,
+            cycles_used: tx_result.result.cycles_used,
+        })
+    }
+
+    fn execute_next_tx_with_one_tx_tracer(
+        &mut self,
+        tx_tracer: &mut OneTxTracer<'_, H>,
+        validation_computational_gas_limit: u32,
+    ) -> Result<VmTxExecutionResult, TxRevertReason> {
+        let tx_index = self.bootloader_state.next_unexecuted_tx() as u32;
 
         let timestamp_initial = Timestamp(self.state.local_state.timestamp);
         let cycles_initial = self.state.local_state.monotonic_cycle_counter;
@@ -597,7 +721,7 @@ impl<H: HistoryMode> VmInstance<'_, H> {
         let spent_pubdata_counter_before = self.state.local_state.spent_pubdata_counter;
 
         let (stop_reason, operator_suggested_refund) =
-            self.execute_with_custom_tracer_and_refunds(&mut tx_tracer);
+            self.execute_with_custom_tracer_and_refunds(tx_tracer);
         match stop_reason {
             VmExecutionStopReason::VmFinished => {
                 // Bootloader resulted in panic or revert, this means either the transaction is rejected
@@ -624,7 +748,7 @@ impl<H: HistoryMode> VmInstance<'_, H> {
 
                     let computational_gas_used = calculate_computational_gas_used(
                         self,
-                        &tx_tracer,
+                        &*tx_tracer,
                         gas_remaining_before,
                         spent_pubdata_counter_before,
                     );
@@ -688,6 +812,40 @@ impl<H: HistoryMode> VmInstance<'_, H> {
         block_result
     }
 
+    /// Same as [`Self::execute_till_block_end()`], but records storage slots touched during the execution
+    /// together with their values before and after it.
+    pub fn execute_till_block_end_with_prestate_tracer(
+        &mut self,
+        job_type: BootloaderJobType,
+    ) -> VmBlockResult {
+        let mut tracer =
+            TransactionResultTracer::new(self.execution_mode.invocation_limit(), false);
+        tracer.prestate_tracer = Some(PrestateTracer::new(
+            self.state.storage.storage.inner().get_ptr(),
+        ));
+        let mut block_result = self.execute_till_block_end_with_tracer(job_type, &mut tracer);
+        block_result.full_result.trace = VmTrace::PrestateTrace(tracer.prestate_trace().unwrap());
+        block_result
+    }
+
+    /// Same as [`Self::execute_till_block_end()`], but records every opcode executed outside
+    /// of the bootloader.
+    pub fn execute_till_block_end_with_struct_logger(
+        &mut self,
+        job_type: BootloaderJobType,
+        params: StructLoggerParams,
+    ) -> VmBlockResult {
+        let mut tracer =
+            TransactionResultTracer::new(self.execution_mode.invocation_limit(), false);
+        tracer.struct_logger = Some(StructLogger::new(
+            self.state.storage.storage.inner().get_ptr(),
+            params,
+        ));
+        let mut block_result = self.execute_till_block_end_with_tracer(job_type, &mut tracer);
+        block_result.full_result.trace = VmTrace::ExecutionTrace(tracer.execution_trace().unwrap());
+        block_result
+    }
+
     fn execute_till_block_end_with_tracer(
         &mut self,
         job_type: BootloaderJobType,
@@ -714,30 +872,8 @@ impl<H: HistoryMode> VmInstance<'_, H> {
                     && tx_has_failed(&self.state, 0)
                     && full_result.revert_reason.is_none()
                 {
-                    let revert_reason = tx_result_tracer
-                        .revert_reason
-                        .clone()
-                        .map(|reason| {
-                            let vm_revert_reason = VmRevertReason::try_from(reason.as_slice())
-                                .unwrap_or_else(|_| VmRevertReason::Unknown {
-                                    function_selector: vec![],
-                                    data: reason.clone(),
-                                });
-
-                            VmRevertReasonParsingResult {
-                                revert_reason: TxRevertReason::TxReverted(vm_revert_reason),
-                                original_data: reason,
-                            }
-                        })
-                        .unwrap_or_else(|| VmRevertReasonParsingResult {
-                            revert_reason: TxRevertReason::TxReverted(VmRevertReason::General {
-                                msg: "Transaction reverted with empty reason. Possibly out of gas"
-                                    .to_string(),
-                                data: vec![],
-                            }),
-                            original_data: vec![],
-                        });
-
+                    let revert_reason =
+                        parse_tx_revert_reason(tx_result_tracer.revert_reason.clone());
                     full_result.revert_reason = Some(revert_reason);
                 }
 
@@ -31,8 +31,20 @@ pub enum Web3Error {
     FilterNotFound,
     #[error("Not implemented")]
     NotImplemented,
+    #[error("Merkle tree is not available on this node")]
+    TreeApiUnavailable,
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
     LogsLimitExceeded(usize, u32, u32),
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
+    #[error("Invalid state override: {0}")]
+    InvalidStateOverride(String),
+    #[error("Invalid bundle size: it must contain from 1 to {0} transactions")]
+    InvalidBundleSize(usize),
+    #[error("Rate limit exceeded, try again later")]
+    RateLimitExceeded,
+    #[error("Tracing requires re-executing more than {0} transactions")]
+    TooManyReplayedTransactions(usize),
+    #[error("Trace exceeds the limit of {0} recorded steps")]
+    TooManyTraceSteps(usize),
 }
@@ -1,7 +1,7 @@
 use crate::types::H256;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
-use zksync_types::api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig};
+use zksync_types::api::{BlockId, BlockNumber, DebugTrace, ResultDebugCall, TracerConfig};
 use zksync_types::transaction_request::CallRequest;
 
 #[cfg_attr(
@@ -35,11 +35,11 @@ pub trait DebugNamespace {
         request: CallRequest,
         block: Option<BlockId>,
         options: Option<TracerConfig>,
-    ) -> RpcResult<DebugCall>;
+    ) -> RpcResult<DebugTrace>;
     #[method(name = "traceTransaction")]
     async fn trace_transaction(
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Option<DebugCall>>;
+    ) -> RpcResult<Option<DebugTrace>>;
 }
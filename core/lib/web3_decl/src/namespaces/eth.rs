@@ -8,7 +8,7 @@ use crate::types::{
 
 use zksync_types::{
     api::Transaction,
-    api::{BlockIdVariant, BlockNumber, TransactionVariant},
+    api::{BlockId, BlockIdVariant, BlockNumber, FeeHistory, StateOverride, TransactionVariant},
     transaction_request::CallRequest,
     Address, H256,
 };
@@ -35,14 +35,32 @@ pub trait EthNamespace {
     async fn chain_id(&self) -> RpcResult<U64>;
 
     #[method(name = "call")]
-    async fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> RpcResult<Bytes>;
+    async fn call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Bytes>;
 
     #[method(name = "estimateGas")]
-    async fn estimate_gas(&self, req: CallRequest, _block: Option<BlockNumber>) -> RpcResult<U256>;
+    async fn estimate_gas(
+        &self,
+        req: CallRequest,
+        _block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<U256>;
 
     #[method(name = "gasPrice")]
     async fn gas_price(&self) -> RpcResult<U256>;
 
+    #[method(name = "feeHistory")]
+    async fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f32>>,
+    ) -> RpcResult<FeeHistory>;
+
     #[method(name = "newFilter")]
     async fn new_filter(&self, filter: Filter) -> RpcResult<U256>;
 
@@ -132,6 +150,12 @@ pub trait EthNamespace {
     #[method(name = "getTransactionReceipt")]
     async fn get_transaction_receipt(&self, hash: H256) -> RpcResult<Option<TransactionReceipt>>;
 
+    #[method(name = "getBlockReceipts")]
+    async fn get_block_receipts(
+        &self,
+        block: BlockId,
+    ) -> RpcResult<Option<Vec<TransactionReceipt>>>;
+
     #[method(name = "protocolVersion")]
     async fn protocol_version(&self) -> RpcResult<String>;
 
@@ -2,7 +2,10 @@ use crate::types::Token;
 use bigdecimal::BigDecimal;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use std::collections::HashMap;
-use zksync_types::api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails};
+use zksync_types::api::{
+    BlockIdVariant, BridgeAddresses, L2ToL1LogProof, Proof, SimulatedTransaction, StateOverride,
+    TransactionDetails,
+};
 use zksync_types::transaction_request::CallRequest;
 use zksync_types::{
     api::U64,
@@ -98,4 +101,20 @@ pub trait ZksNamespace {
 
     #[method(name = "getL1GasPrice")]
     async fn get_l1_gas_price(&self) -> RpcResult<U64>;
+
+    #[method(name = "getProof")]
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<Proof>>;
+
+    #[method(name = "simulateBundle")]
+    async fn simulate_bundle(
+        &self,
+        reqs: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Vec<SimulatedTransaction>>;
 }
@@ -15,7 +15,10 @@ use rlp::Rlp;
 use serde::{de, Deserialize, Serialize, Serializer};
 
 pub use zksync_types::{
-    api::{Block, BlockNumber, Log, TransactionReceipt, TransactionRequest},
+    api::{
+        Block, BlockNumber, L1BatchStatusUpdate, Log, TransactionReceipt, TransactionRequest,
+        TxStatusUpdate,
+    },
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace, VmExecutionStep},
     web3::{
         ethabi,
@@ -339,6 +342,63 @@ fn topic_to_option<T>(topic: ethabi::Topic<T>) -> Option<Vec<T>> {
     }
 }
 
+/// Sync progress reported to `syncing` subscribers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PubSubSyncInfo {
+    pub starting_block: U64,
+    pub current_block: U64,
+    pub highest_block: U64,
+}
+
+/// Notification sent to `syncing` subscribers. Follows the format used by Geth:
+/// `{ "syncing": true, "status": { .. } }` while syncing, and `{ "syncing": false }` once synced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PubSubSyncStatus {
+    pub syncing: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<PubSubSyncInfo>,
+}
+
+impl PubSubSyncStatus {
+    pub fn synced() -> Self {
+        Self {
+            syncing: false,
+            status: None,
+        }
+    }
+
+    pub fn syncing(info: PubSubSyncInfo) -> Self {
+        Self {
+            syncing: true,
+            status: Some(info),
+        }
+    }
+}
+
+/// Parameters of the `txStatus` subscription. Updates are sent for transactions with any of
+/// the specified hashes or initiated by any of the specified accounts. The server limits
+/// the number of hashes and addresses in a single subscription.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PubSubTxStatusFilter {
+    #[serde(default)]
+    pub tx_hashes: Vec<H256>,
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+}
+
+impl PubSubTxStatusFilter {
+    pub fn is_empty(&self) -> bool {
+        self.tx_hashes.is_empty() && self.addresses.is_empty()
+    }
+
+    pub fn matches(&self, update: &TxStatusUpdate) -> bool {
+        self.tx_hashes.contains(&update.transaction_hash)
+            || self.addresses.contains(&update.initiator_address)
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -346,7 +406,9 @@ pub enum PubSubResult {
     Header(BlockHeader),
     Log(Log),
     TxHash(H256),
-    Syncing(bool),
+    Syncing(PubSubSyncStatus),
+    TxStatus(TxStatusUpdate),
+    L1BatchStatus(L1BatchStatusUpdate),
 }
 
 #[cfg(test)]
@@ -395,6 +457,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pubsub_sync_status_serde() {
+        let synced = serde_json::to_value(PubSubSyncStatus::synced()).unwrap();
+        assert_eq!(synced, serde_json::json!({ "syncing": false }));
+
+        let syncing = PubSubSyncStatus::syncing(PubSubSyncInfo {
+            starting_block: 0.into(),
+            current_block: 16.into(),
+            highest_block: 32.into(),
+        });
+        let serialized = serde_json::to_value(syncing).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "syncing": true,
+                "status": {
+                    "StartingBlock": "0x0",
+                    "CurrentBlock": "0x10",
+                    "HighestBlock": "0x20",
+                },
+            })
+        );
+        let deserialized: PubSubSyncStatus = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, syncing);
+    }
+
+    #[test]
+    fn pubsub_tx_status_filter() {
+        let filter: PubSubTxStatusFilter = serde_json::from_value(serde_json::json!({
+            "addresses": ["0x0000000000000000000000000000000000000001"],
+        }))
+        .unwrap();
+        assert!(!filter.is_empty());
+        assert!(filter.tx_hashes.is_empty());
+
+        let mut update = TxStatusUpdate {
+            transaction_hash: H256::repeat_byte(2),
+            initiator_address: Address::from_low_u64_be(1),
+            l1_batch_number: zksync_types::L1BatchNumber(1),
+            status: zksync_types::api::L1BatchStage::Committed,
+            eth_tx_hash: H256::zero(),
+        };
+        assert!(filter.matches(&update));
+        update.initiator_address = Address::repeat_byte(3);
+        assert!(!filter.matches(&update));
+
+        let empty_filter: PubSubTxStatusFilter =
+            serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(empty_filter.is_empty());
+    }
+
     #[test]
     fn block_id_variant_serializing() {
         let test_vector = &[